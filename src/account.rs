@@ -0,0 +1,289 @@
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::spawn;
+use tracing::{error, info};
+
+use crate::registry::AccountParser;
+use crate::transaction::TOP_LEVEL_PARENT_INDEX;
+use crate::{
+    join_error_reason, DecodeError, InstructionFunction, InstructionProperty, InstructionSet,
+    ParserRegistry, ValueType,
+};
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Account {
+    // The address of the account.
+    pub pubkey: String,
+    // The program owning the account, which decides how its data is decoded.
+    pub owner: String,
+    // The data held by the account.
+    pub data: Vec<u8>,
+    // The balance of the account.
+    pub lamports: u64,
+    // The slot the snapshot of the account was taken at.
+    pub slot: u64,
+    // The (chain) time the block at `slot` was produced, if known.
+    pub block_time: Option<i64>,
+}
+
+impl From<(String, String, Vec<u8>, u64, u64)> for Account {
+    fn from((pubkey, owner, data, lamports, slot): (String, String, Vec<u8>, u64, u64)) -> Self {
+        Account { pubkey, owner, data, lamports, slot, block_time: None }
+    }
+}
+
+/// The record account parsers are handed: the state of an account and when it was processed.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AccountRecord {
+    // The address of the account.
+    pub pubkey: String,
+    // The program owning the account.
+    pub owner: String,
+    // The data held by the account.
+    pub data: Vec<u8>,
+    // The time this record was processed at, in our time.
+    pub timestamp: i64,
+    // The slot the snapshot of the account was taken at.
+    pub slot: u64,
+    // The (chain) time the block at `slot` was produced, if known.
+    pub block_time: Option<i64>,
+}
+
+impl Account {
+    /// The record account parsers are handed for this account.
+    pub fn to_record(&self, timestamp: i64) -> AccountRecord {
+        AccountRecord {
+            pubkey: self.pubkey.clone(),
+            owner: self.owner.clone(),
+            data: self.data.clone(),
+            timestamp,
+            slot: self.slot,
+            block_time: self.block_time,
+        }
+    }
+}
+
+impl AccountRecord {
+    /// The function of the set decoded out of this account. Accounts are not part of a
+    /// transaction, so their rows have no transaction hash: the account they belong to is told
+    /// by the `AccountSet` holding them.
+    pub(crate) fn function(&self, function_name: &str) -> InstructionFunction {
+        InstructionFunction {
+            tx_instruction_id: 0,
+            transaction_hash: "".to_string(),
+            parent_index: TOP_LEVEL_PARENT_INDEX,
+            program: self.owner.clone(),
+            function_name: function_name.to_string(),
+            timestamp: self.timestamp,
+            slot: self.slot,
+            block_time: self.block_time,
+        }
+    }
+
+    /// A property of the set decoded out of this account.
+    pub(crate) fn property(
+        &self,
+        parent_key: &str,
+        key: &str,
+        value: String,
+        value_type: ValueType,
+    ) -> InstructionProperty {
+        InstructionProperty {
+            tx_instruction_id: 0,
+            transaction_hash: "".to_string(),
+            parent_index: TOP_LEVEL_PARENT_INDEX,
+            key: key.to_string(),
+            value,
+            value_type,
+            parent_key: parent_key.to_string(),
+            timestamp: self.timestamp,
+            slot: self.slot,
+            block_time: self.block_time,
+        }
+    }
+}
+
+/// An account decoded by `process_accounts`.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AccountSet {
+    // The address of the account.
+    pub pubkey: String,
+    // The decoded state of the account, along with its balance.
+    pub state: InstructionSet,
+}
+
+impl AccountSet {
+    /// The account as a single json object, its state with the account's `pubkey` added.
+    pub fn to_json(&self) -> Value {
+        let mut json = self.state.to_json();
+        if let Value::Object(ref mut map) = json {
+            map.insert("pubkey".to_string(), Value::String(self.pubkey.clone()));
+        }
+
+        json
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AccountFailure {
+    // The account that could not be decoded, kept whole so it can be re-processed.
+    pub account: Account,
+    // Why the account could not be decoded.
+    pub error: DecodeError,
+}
+
+/// What became of an account handed to `process_accounts`.
+#[derive(Clone, Serialize, Deserialize)]
+pub enum AccountOutcome {
+    // The account was decoded.
+    Decoded(AccountSet),
+    // No account parser is registered for the program owning the account.
+    UnsupportedProgram(AccountFailure),
+    // The parser could not make sense of the account's data.
+    DeserializationError(AccountFailure),
+    // The parser panicked while decoding the account.
+    Panic(AccountFailure),
+}
+
+impl AccountOutcome {
+    /// The decoded account, if it was decoded.
+    pub fn account_set(self) -> Option<AccountSet> {
+        match self {
+            AccountOutcome::Decoded(account_set) => Some(account_set),
+            _ => None,
+        }
+    }
+
+    /// Why the account was not decoded, if it was not.
+    pub fn failure(&self) -> Option<&AccountFailure> {
+        match self {
+            AccountOutcome::Decoded(_) => None,
+            AccountOutcome::UnsupportedProgram(failure)
+            | AccountOutcome::DeserializationError(failure)
+            | AccountOutcome::Panic(failure) => Some(failure),
+        }
+    }
+}
+
+/// Decodes snapshots of accounts, picking the decoder after the program owning each account.
+///
+/// Accepts `Account`s or `(pubkey, owner, data, lamports, slot)` tuples. Every decoded set also
+/// carries the account's `lamports`.
+pub async fn process_accounts<A: Into<Account>>(
+    accounts: Vec<A>,
+    timestamp: i64,
+) -> Vec<AccountOutcome> {
+    process_accounts_with_registry(&ParserRegistry::with_builtins(), accounts, timestamp).await
+}
+
+/// Same as `process_accounts`, but decodes the accounts with the account parsers of the
+/// registry given.
+pub async fn process_accounts_with_registry<A: Into<Account>>(
+    registry: &ParserRegistry,
+    accounts: Vec<A>,
+    timestamp: i64,
+) -> Vec<AccountOutcome> {
+    let account_jobs: Vec<_> = accounts.into_iter()
+        .map(|account| {
+            let account: Account = account.into();
+            let job = registry.get_account_parser(&account.owner)
+                .map(|parser: Arc<dyn AccountParser>| {
+                    let record = account.to_record(timestamp);
+                    let lamports = record.property(
+                        "", "lamports", account.lamports.to_string(), ValueType::U64);
+
+                    (spawn(async move { parser.parse(record).await }), lamports)
+                });
+
+            (account, job)
+        })
+        .collect();
+
+    let mut outcomes: Vec<AccountOutcome> = Vec::new();
+    for (account, job) in account_jobs {
+        let outcome = match job {
+            Some((job, lamports)) => match job.await {
+                Ok(Ok(mut state)) => {
+                    state.properties.push(lamports);
+
+                    AccountOutcome::Decoded(AccountSet { pubkey: account.pubkey, state })
+                }
+                Ok(Err(error)) => AccountOutcome::DeserializationError(AccountFailure {
+                    account,
+                    error,
+                }),
+                Err(join_err) => {
+                    let reason = join_error_reason(join_err);
+                    error!("[spi-wrapper] The account parser for program {} panicked: {}",
+                        account.owner, reason);
+
+                    let error = DecodeError::panic(&account.owner, &reason);
+                    AccountOutcome::Panic(AccountFailure { account, error })
+                }
+            },
+            None => {
+                info!("Looks like the program owning account {} ({}) is an unsupported one.",
+                    account.pubkey, account.owner);
+
+                let error = DecodeError::unsupported(&account.owner);
+                AccountOutcome::UnsupportedProgram(AccountFailure { account, error })
+            }
+        };
+
+        outcomes.push(outcome);
+    }
+
+    outcomes
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::programs;
+    use crate::test_utils::block_on;
+
+    #[test]
+    fn accounts_are_decoded_by_owner() {
+        use spl_token::solana_program::program_option::COption;
+        use spl_token::solana_program::program_pack::Pack;
+        use spl_token::state::Mint;
+
+        let mut data = vec![0; Mint::LEN];
+        Mint::pack(Mint {
+            mint_authority: COption::None,
+            supply: 42,
+            decimals: 6,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        }, &mut data).unwrap();
+
+        let outcomes = block_on(process_accounts(vec![
+            ("mint".to_string(), programs::native_token::PROGRAM_ADDRESS.to_string(), data,
+             1_461_600, 7),
+            ("unknown".to_string(), "Unknown111111111111111111111111111111111111".to_string(),
+             vec![], 0, 7),
+            ("wallet".to_string(), programs::native_system::PROGRAM_ADDRESS.to_string(), vec![],
+             5, 7),
+        ], 0));
+
+        let mint = outcomes[0].clone().account_set().unwrap();
+        assert_eq!(mint.pubkey, "mint");
+        let mint = mint.state;
+        assert_eq!(mint.function.function_name, "mint");
+        assert_eq!(mint.function.slot, 7);
+        let value = |key: &str| mint.properties.iter()
+            .find(|property| property.key == key)
+            .map(|property| property.value.clone())
+            .unwrap();
+        assert_eq!(value("supply"), "42");
+        assert_eq!(value("lamports"), "1461600");
+        assert!(matches!(outcomes[1], AccountOutcome::UnsupportedProgram(_)));
+
+        let wallet = outcomes[2].clone().account_set().unwrap().to_json();
+        assert_eq!(wallet["function_name"], "system-account");
+        assert_eq!(wallet["pubkey"], "wallet");
+        assert_eq!(wallet["properties"]["lamports"], 5);
+    }
+}
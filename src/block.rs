@@ -0,0 +1,143 @@
+use solana_sdk::reward_type::RewardType;
+use solana_transaction_status::{ConfirmedBlock, EncodedConfirmedBlock, Reward};
+
+use crate::transaction::{
+    process_flattened_transaction, FlattenedTransaction, TOP_LEVEL_PARENT_INDEX,
+};
+use crate::{
+    DecodeError, InstructionFunction, InstructionOutcome, InstructionProperty, InstructionSet,
+    ParserRegistry, ValueType,
+};
+
+/// The `program` block rewards are emitted under, as they are not invoked by any program.
+pub const REWARDS_PROGRAM: &str = "rewards";
+
+/// Decodes every transaction of a confirmed block, followed by the block's rewards.
+///
+/// Every row carries the `slot` given and the block's `block_time`, along with `timestamp`: the
+/// time the block is processed at, in our (wall clock) time rather than the chain's.
+pub async fn process_block(
+    registry: &ParserRegistry,
+    slot: u64,
+    block: &ConfirmedBlock,
+    timestamp: i64,
+) -> Vec<InstructionOutcome> {
+    let mut outcomes: Vec<InstructionOutcome> = Vec::new();
+
+    for transaction in block.transactions.iter() {
+        let flattened = FlattenedTransaction::from_transaction_with_status_meta(transaction);
+        outcomes.extend(process_flattened_transaction(
+            registry, flattened, slot, block.block_time, timestamp).await);
+    }
+
+    outcomes.extend(fragment_rewards(
+        &block.blockhash, &block.rewards, slot, block.block_time, timestamp)
+        .into_iter()
+        .map(InstructionOutcome::Decoded));
+
+    outcomes
+}
+
+/// Decodes every transaction of an RPC-encoded confirmed block, followed by the block's rewards.
+/// An error is returned if any of the transactions cannot be decoded.
+///
+/// Every row carries the `slot` given and the block's `block_time`, along with `timestamp`: the
+/// time the block is processed at, in our (wall clock) time rather than the chain's.
+pub async fn process_encoded_block(
+    registry: &ParserRegistry,
+    slot: u64,
+    block: &EncodedConfirmedBlock,
+    timestamp: i64,
+) -> Result<Vec<InstructionOutcome>, DecodeError> {
+    let mut outcomes: Vec<InstructionOutcome> = Vec::new();
+
+    for transaction in block.transactions.iter() {
+        let flattened = FlattenedTransaction::from_encoded_transaction_with_status_meta(
+            transaction)?;
+        outcomes.extend(process_flattened_transaction(
+            registry, flattened, slot, block.block_time, timestamp).await);
+    }
+
+    outcomes.extend(fragment_rewards(
+        &block.blockhash, &block.rewards, slot, block.block_time, timestamp)
+        .into_iter()
+        .map(InstructionOutcome::Decoded));
+
+    Ok(outcomes)
+}
+
+/// Turns the rewards of a block into one record each, under the `REWARDS_PROGRAM` program and the
+/// block's hash. Rewards are numbered by their position in the block as `tx_instruction_id`. The
+/// function name tells the kind of reward (e.g. `staking-reward`).
+pub fn fragment_rewards(
+    blockhash: &str,
+    rewards: &[Reward],
+    slot: u64,
+    block_time: Option<i64>,
+    timestamp: i64,
+) -> Vec<InstructionSet> {
+    rewards.iter()
+        .enumerate()
+        .map(|(idx, reward)| {
+            let property = |key: &str, value: String, value_type: ValueType| InstructionProperty {
+                tx_instruction_id: idx as i32,
+                transaction_hash: blockhash.to_string(),
+                parent_index: TOP_LEVEL_PARENT_INDEX,
+                key: key.to_string(),
+                value,
+                value_type,
+                parent_key: "".to_string(),
+                timestamp,
+                slot,
+                block_time,
+            };
+
+            InstructionSet {
+                function: InstructionFunction {
+                    tx_instruction_id: idx as i32,
+                    transaction_hash: blockhash.to_string(),
+                    parent_index: TOP_LEVEL_PARENT_INDEX,
+                    program: REWARDS_PROGRAM.to_string(),
+                    function_name: match reward.reward_type {
+                        Some(RewardType::Fee) => "fee-reward",
+                        Some(RewardType::Rent) => "rent-reward",
+                        Some(RewardType::Staking) => "staking-reward",
+                        Some(RewardType::Voting) => "voting-reward",
+                        None => "reward",
+                    }.to_string(),
+                    timestamp,
+                    slot,
+                    block_time,
+                },
+                properties: vec![
+                    property("pubkey", reward.pubkey.clone(), ValueType::Pubkey),
+                    property("lamports", reward.lamports.to_string(), ValueType::I64),
+                    property("post_balance", reward.post_balance.to_string(), ValueType::U64),
+                ],
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rewards_past_the_range_of_an_i16_keep_their_position() {
+        let rewards: Vec<Reward> = (0..40_000)
+            .map(|idx| Reward {
+                pubkey: format!("voter{}", idx),
+                lamports: 1,
+                post_balance: 2,
+                reward_type: Some(RewardType::Voting),
+            })
+            .collect();
+
+        let sets = fragment_rewards("blockhash", &rewards, 7, None, 0);
+        let last = sets.last().unwrap();
+        assert_eq!(last.function.tx_instruction_id, 39_999);
+        assert_eq!(last.function.function_name, "voting-reward");
+        assert!(last.properties.iter().all(|property| property.tx_instruction_id == 39_999));
+    }
+}
@@ -0,0 +1,201 @@
+use num_traits::FromPrimitive;
+use serde::{Deserialize, Serialize};
+use serum_dex::error::DexErrorCode;
+use solana_program::instruction::InstructionError;
+use solana_program::program_error::ProgramError;
+use solana_sdk::transaction::TransactionError;
+use spl_token::error::TokenError;
+use spl_token_lending::error::LendingError;
+use spl_token_swap::error::SwapError;
+use std::convert::TryFrom;
+use std::fmt;
+
+use crate::programs;
+
+/// The family an error belongs to, which tells how its `code` should be read.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ErrorKind {
+    // A runtime `InstructionError`; the code is its variant index.
+    Instruction,
+    // A builtin `ProgramError`; the code is its `u64` representation.
+    Program,
+    // A program specific `Custom(code)` error.
+    Custom,
+    // The instruction data could not be deserialized.
+    Deserialization,
+    // No parser is registered for the program.
+    Unsupported,
+    // The parser panicked.
+    Panic,
+    // A `TransactionError` that is not tied to an instruction; the code is its variant index.
+    Transaction,
+}
+
+/// A stable, serializable description of why something could not be decoded (or why a program
+/// failed on-chain), shared by every parser.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DecodeError {
+    // The program the error belongs to.
+    pub program: String,
+    pub kind: ErrorKind,
+    // The numeric code of the error within its kind.
+    pub code: u64,
+    // The name of the error, e.g. `InvalidInstructionData`.
+    pub name: String,
+    // A human readable description of the error.
+    pub message: String,
+}
+
+impl DecodeError {
+    pub fn new(program: &str, kind: ErrorKind, code: u64, name: &str, message: &str) -> Self {
+        DecodeError {
+            program: program.to_string(),
+            kind,
+            code,
+            name: name.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    pub fn from_instruction_error(program: &str, err: &InstructionError) -> Self {
+        if let InstructionError::Custom(code) = err {
+            return DecodeError::from_custom(program, *code);
+        }
+
+        DecodeError::new(program, ErrorKind::Instruction, variant_index(err), &variant_name(err),
+                         &err.to_string())
+    }
+
+    /// The error a transaction was rejected with. Errors raised by an instruction are attributed
+    /// to `program`, which should be the program that raised them.
+    pub fn from_transaction_error(program: &str, err: &TransactionError) -> Self {
+        if let TransactionError::InstructionError(_, instruction_err) = err {
+            return DecodeError::from_instruction_error(program, instruction_err);
+        }
+
+        DecodeError::new(program, ErrorKind::Transaction, variant_index(err), &variant_name(err),
+                         &err.to_string())
+    }
+
+    pub fn from_program_error(program: &str, err: &ProgramError) -> Self {
+        if let ProgramError::Custom(code) = err {
+            return DecodeError::from_custom(program, *code);
+        }
+
+        DecodeError::new(program, ErrorKind::Program, u64::from(err.clone()), &variant_name(err),
+                         &err.to_string())
+    }
+
+    pub fn from_bincode_error(program: &str, err: &bincode::Error) -> Self {
+        DecodeError::new(program, ErrorKind::Deserialization, 0, &variant_name(err.as_ref()),
+                         &err.to_string())
+    }
+
+    /// A program specific error, named after the program's error type when it is known to this
+    /// crate (see `describe_custom_error`).
+    pub fn from_custom(program: &str, code: u32) -> Self {
+        match describe_custom_error(program, code) {
+            Some((name, message)) => DecodeError::new(program, ErrorKind::Custom, code as u64,
+                                                      &name, &message),
+            None => DecodeError::new(program, ErrorKind::Custom, code as u64, "Custom",
+                                     &format!("custom program error: {:#x}", code)),
+        }
+    }
+
+    /// An error for data that does not match the program's layout, when there is no underlying
+    /// error type to classify.
+    pub fn deserialization(program: &str, name: &str, message: &str) -> Self {
+        DecodeError::new(program, ErrorKind::Deserialization, 0, name, message)
+    }
+
+    pub fn unsupported(program: &str) -> Self {
+        DecodeError::new(program, ErrorKind::Unsupported, 0, "UnsupportedProgram",
+                         &format!("No parser is registered for program {}.", program))
+    }
+
+    pub fn panic(program: &str, message: &str) -> Self {
+        DecodeError::new(program, ErrorKind::Panic, 0, "Panic", message)
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({}): {}", self.name, self.code, self.message)
+    }
+}
+
+/// Looks up the name and message of a `Custom(code)` error returned by the given program.
+///
+/// `None` is returned for programs whose error type is not known to this crate, or for codes
+/// their error type does not define.
+pub fn describe_custom_error(program: &str, code: u32) -> Option<(String, String)> {
+    fn describe<E: fmt::Debug + fmt::Display>(err: E) -> (String, String) {
+        (variant_name(&err), err.to_string())
+    }
+
+    match program {
+        programs::native_token::PROGRAM_ADDRESS => TokenError::from_u32(code).map(describe),
+        programs::native_token_swap::PROGRAM_ADDRESS => SwapError::from_u32(code).map(describe),
+        programs::native_token_lending::PROGRAM_ADDRESS =>
+            LendingError::from_u32(code).map(describe),
+        programs::solend_token_lending::PROGRAM_ADDRESS =>
+            programs::solend::error::LendingError::from_u32(code).map(describe),
+        programs::serum_market::PROGRAM_ADDRESS_V1
+        | programs::serum_market::PROGRAM_ADDRESS_V2
+        | programs::serum_market::PROGRAM_ADDRESS_V3 => DexErrorCode::try_from(code).ok()
+            // The dex error codes carry no description, their names are all there is.
+            .map(|err| (variant_name(&err), format!("dex error: {:?}", err))),
+        _ => None,
+    }
+}
+
+/// The index of an enum variant, which is what bincode serializes it by and keeps it stable.
+fn variant_index<T: Serialize>(value: &T) -> u64 {
+    bincode::serialize(value).ok()
+        .and_then(|bytes| bytes.get(..4).map(|tag| [tag[0], tag[1], tag[2], tag[3]]))
+        .map(|tag| u32::from_le_bytes(tag) as u64)
+        .unwrap_or_default()
+}
+
+/// The name of an enum variant as printed by `Debug`, without its fields.
+fn variant_name<T: fmt::Debug + ?Sized>(value: &T) -> String {
+    let debug = format!("{:?}", value);
+    debug.split(|c: char| c == '(' || c == ' ' || c == '{')
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn decode_errors_are_classified() {
+        let program = "Vote111111111111111111111111111111111111111";
+        let invalid_data = DecodeError::from_instruction_error(
+            program, &InstructionError::InvalidInstructionData);
+        assert_eq!(invalid_data.kind, ErrorKind::Instruction);
+        assert_eq!(invalid_data.code, 2);
+        assert_eq!(invalid_data.name, "InvalidInstructionData");
+
+        let borsh = DecodeError::from_instruction_error(
+            program, &InstructionError::BorshIoError("eof".to_string()));
+        assert_eq!(borsh.name, "BorshIoError");
+
+        let custom = DecodeError::from_program_error(program, &ProgramError::Custom(7));
+        assert_eq!((custom.kind, custom.code), (ErrorKind::Custom, 7));
+        assert_eq!(custom.name, "Custom");
+    }
+
+    #[test]
+    fn custom_errors_are_named_per_program() {
+        let token = DecodeError::from_custom(programs::native_token::PROGRAM_ADDRESS, 1);
+        assert_eq!(token.name, "InsufficientFunds");
+        assert_eq!(token.message, "Insufficient funds");
+
+        let solend = describe_custom_error(programs::solend_token_lending::PROGRAM_ADDRESS, 1);
+        assert_eq!(solend.unwrap().0, "AlreadyInitialized");
+        assert!(describe_custom_error(programs::native_system::PROGRAM_ADDRESS, 1).is_none());
+    }
+}
@@ -0,0 +1,260 @@
+use std::str::FromStr;
+
+use serde_json::{json, Map, Value};
+use solana_sdk::instruction::CompiledInstruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_transaction_status::parse_instruction::{parse, ParsedInstruction};
+use solana_transaction_status::{UiParsedInstruction, UiPartiallyDecodedInstruction};
+
+use crate::path::properties_to_json;
+use crate::{
+    process_with_registry, programs, Instruction, InstructionSet, ParserRegistry,
+    ACCOUNTS_PARENT_KEY,
+};
+
+/// Decodes instructions and renders them the way the RPC does for `jsonParsed` transactions.
+///
+/// Instructions of the programs the RPC knows how to parse (system, token, stake, vote, BPF
+/// loaders, ...) are handed to its own parser, so their output is the RPC's. The others are
+/// rendered from the `InstructionSet` the registry decodes them into (see
+/// `render_instruction_set`), and left partially decoded, as the RPC does, when they could not be
+/// decoded either.
+pub async fn process_json_parsed(
+    registry: &ParserRegistry,
+    instructions: Vec<Instruction>,
+    og_instructions: Option<Vec<CompiledInstruction>>,
+) -> Vec<UiParsedInstruction> {
+    let outcomes = process_with_registry(registry, instructions.clone(), og_instructions).await;
+
+    instructions.iter()
+        .zip(outcomes.into_iter())
+        .map(|(instruction, outcome)| render(instruction, outcome.instruction_set().as_ref()))
+        .collect()
+}
+
+/// Renders a single instruction, along with what it was decoded into (if it was). See
+/// `process_json_parsed`.
+pub fn render(
+    instruction: &Instruction,
+    instruction_set: Option<&InstructionSet>,
+) -> UiParsedInstruction {
+    if let Some(parsed) = parse_as_rpc(instruction) {
+        return UiParsedInstruction::Parsed(parsed);
+    }
+
+    match instruction_set {
+        Some(instruction_set) => {
+            UiParsedInstruction::Parsed(render_instruction_set(instruction_set))
+        }
+        None => UiParsedInstruction::PartiallyDecoded(UiPartiallyDecodedInstruction {
+            program_id: instruction.program.clone(),
+            accounts: instruction.accounts.clone(),
+            data: bs58::encode(&instruction.data).into_string(),
+        }),
+    }
+}
+
+/// Renders a decoded instruction in the `jsonParsed` shape: the kebab-case function name
+/// becomes a camelCase `type`, and the properties are nested into `info` with camelCase keys.
+/// Named accounts are listed in `info` next to the other properties, as the RPC does.
+pub fn render_instruction_set(instruction_set: &InstructionSet) -> ParsedInstruction {
+    let mut info = match properties_to_json(&instruction_set.properties) {
+        Value::Object(info) => info,
+        _ => Map::new(),
+    };
+
+    if let Some(Value::Object(accounts)) = info.remove(ACCOUNTS_PARENT_KEY) {
+        for (role, account) in accounts {
+            info.entry(role).or_insert(account);
+        }
+    }
+
+    ParsedInstruction {
+        program: program_name(&instruction_set.function.program),
+        program_id: instruction_set.function.program.clone(),
+        parsed: json!({
+            "type": camel_case(&instruction_set.function.function_name),
+            "info": camel_case_keys(Value::Object(info)),
+        }),
+    }
+}
+
+/// The name programs go by in `jsonParsed` output. Programs the RPC does not know of are named
+/// after their crate, and unknown ones after their address.
+pub fn program_name(program_id: &str) -> String {
+    match program_id {
+        programs::native_system::PROGRAM_ADDRESS => "system",
+        programs::native_token::PROGRAM_ADDRESS => "spl-token",
+        programs::native_stake::PROGRAM_ADDRESS => "stake",
+        programs::native_stake_pool::PROGRAM_ADDRESS => "spl-stake-pool",
+        programs::native_vote::PROGRAM_ADDRESS => "vote",
+        programs::bpf_loader::PROGRAM_ADDRESS
+        | programs::bpf_loader::PROGRAM_ADDRESS_2 => "bpf-loader",
+        programs::bpf_loader_upgradeable::PROGRAM_ADDRESS => "bpf-upgradeable-loader",
+        programs::native_address_lookup_table::PROGRAM_ADDRESS => "address-lookup-table",
+        programs::metaplex_token_metadata::PROGRAM_ADDRESS => "token-metadata",
+        programs::native_associated_token_account::PROGRAM_ADDRESS =>
+            "spl-associated-token-account",
+        programs::native_compute_budget::PROGRAM_ADDRESS => "compute-budget",
+        programs::native_config::PROGRAM_ADDRESS => "config",
+        programs::native_memo::PROGRAM_ADDRESS_V1
+        | programs::native_memo::PROGRAM_ADDRESS_V2 => "spl-memo",
+        programs::native_loader::PROGRAM_ADDRESS => "native-loader",
+        programs::native_secp256k1::PROGRAM_ADDRESS => "secp256k1",
+        programs::native_ed25519::PROGRAM_ADDRESS => "ed25519",
+        programs::native_token_swap::PROGRAM_ADDRESS => "spl-token-swap",
+        programs::native_token_lending::PROGRAM_ADDRESS => "spl-token-lending",
+        programs::solend_token_lending::PROGRAM_ADDRESS => "solend",
+        programs::serum_market::PROGRAM_ADDRESS_V1
+        | programs::serum_market::PROGRAM_ADDRESS_V2
+        | programs::serum_market::PROGRAM_ADDRESS_V3 => "serum-dex",
+        _ => program_id,
+    }.to_string()
+}
+
+/// Hands the instruction to the RPC's parser, if the RPC can parse its program.
+fn parse_as_rpc(instruction: &Instruction) -> Option<ParsedInstruction> {
+    let program_id = Pubkey::from_str(&instruction.program).ok()?;
+    let mut account_keys = instruction.accounts.iter()
+        .map(|account| Pubkey::from_str(account).ok())
+        .collect::<Option<Vec<Pubkey>>>()?;
+    account_keys.push(program_id);
+
+    // The accounts are already resolved, so the instruction refers to them in order.
+    let compiled = CompiledInstruction {
+        program_id_index: instruction.accounts.len() as u8,
+        accounts: (0..instruction.accounts.len() as u8).collect(),
+        data: instruction.data.clone(),
+    };
+
+    parse(&program_id, &compiled, &account_keys).ok()
+}
+
+/// `new-order-v3` and `new_order_v3` both become `newOrderV3`.
+fn camel_case(name: &str) -> String {
+    let mut camel = String::with_capacity(name.len());
+    let mut upper_next = false;
+    for c in name.chars() {
+        if c == '-' || c == '_' {
+            upper_next = !camel.is_empty();
+        } else if upper_next {
+            camel.extend(c.to_uppercase());
+            upper_next = false;
+        } else {
+            camel.push(c);
+        }
+    }
+
+    camel
+}
+
+fn camel_case_keys(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(map.into_iter()
+            .map(|(key, value)| (camel_case(&key), camel_case_keys(value)))
+            .collect()),
+        Value::Array(values) => Value::Array(values.into_iter().map(camel_case_keys).collect()),
+        value => value,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serum_dex::instruction::MarketInstruction;
+
+    use super::*;
+    use crate::test_utils::{block_on, instruction};
+
+    #[test]
+    fn json_parsed_matches_the_rpc_shape() {
+        let accounts = [
+            "4rL4RCWHz3iNCdCaveD8KcHfV9YWGsqSHFPo7X2zBNwa",
+            "8qbHbw2BbbTHBW1sbeqakYXVKRQM8Ne7pLK7m6CVfeR",
+        ];
+
+        let rendered = block_on(process_json_parsed(
+            &ParserRegistry::with_builtins(),
+            vec![
+                instruction(programs::native_system::PROGRAM_ADDRESS, &accounts,
+                            vec![2, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0]),
+                instruction(programs::serum_market::PROGRAM_ADDRESS_V3, &accounts, vec![]),
+            ],
+            None,
+        ));
+
+        match &rendered[0] {
+            UiParsedInstruction::Parsed(parsed) => {
+                assert_eq!(parsed.program, "system");
+                assert_eq!(parsed.parsed["type"], "transfer");
+                assert_eq!(parsed.parsed["info"]["lamports"], 1);
+                assert_eq!(parsed.parsed["info"]["destination"], accounts[1]);
+            }
+            _ => panic!("the transfer should be parsed"),
+        }
+        assert!(matches!(rendered[1], UiParsedInstruction::PartiallyDecoded(_)));
+    }
+
+    #[test]
+    fn programs_the_rpc_does_not_know_are_rendered_from_the_registry() {
+        let accounts = ["market", "bids", "asks", "open-orders", "owner", "event-queue"];
+        let serum = instruction(
+            programs::serum_market::PROGRAM_ADDRESS_V3,
+            &accounts,
+            MarketInstruction::CancelOrderByClientIdV2(42).pack(),
+        );
+
+        let rendered = block_on(process_json_parsed(
+            &ParserRegistry::with_builtins(),
+            vec![serum.clone()],
+            None,
+        ));
+
+        match &rendered[0] {
+            UiParsedInstruction::Parsed(parsed) => {
+                assert_eq!(parsed.program, "serum-dex");
+                assert_eq!(parsed.program_id, programs::serum_market::PROGRAM_ADDRESS_V3);
+                assert_eq!(parsed.parsed["type"], "cancelOrderByClientIdV2");
+                assert_eq!(parsed.parsed["info"]["clientId"], 42);
+                assert_eq!(parsed.parsed["info"]["openOrders"], "open-orders");
+            }
+            _ => panic!("the cancellation should be parsed"),
+        }
+
+        // Without a decoded instruction set, the RPC has nothing to offer for serum either.
+        assert!(matches!(render(&serum, None), UiParsedInstruction::PartiallyDecoded(_)));
+    }
+
+    #[test]
+    fn token_transfers_are_rendered_as_the_rpc_does() {
+        let accounts = [
+            "4rL4RCWHz3iNCdCaveD8KcHfV9YWGsqSHFPo7X2zBNwa",
+            "8qbHbw2BbbTHBW1sbeqakYXVKRQM8Ne7pLK7m6CVfeR",
+            "3aTBQEWsrnFk2pdmkEGC1VZNCvovUJWh4xRdXCgqhsZS",
+        ];
+        let mut data = vec![3];
+        data.extend(&1_500u64.to_le_bytes());
+
+        let rendered = block_on(process_json_parsed(
+            &ParserRegistry::with_builtins(),
+            vec![instruction(programs::native_token::PROGRAM_ADDRESS, &accounts, data)],
+            None,
+        ));
+
+        match &rendered[0] {
+            UiParsedInstruction::Parsed(parsed) => {
+                assert_eq!(parsed.program, "spl-token");
+                // Amounts are strings, and accounts are named by the RPC, not by our parser.
+                assert_eq!(parsed.parsed, json!({
+                    "type": "transfer",
+                    "info": {
+                        "source": accounts[0],
+                        "destination": accounts[1],
+                        "authority": accounts[2],
+                        "amount": "1500",
+                    },
+                }));
+            }
+            _ => panic!("the transfer should be parsed"),
+        }
+    }
+}
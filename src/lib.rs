@@ -1,30 +1,65 @@
-mod programs;
+pub mod account;
+pub mod block;
+pub mod error;
+pub mod json_parsed;
+pub mod path;
+pub mod programs;
+pub mod registry;
+pub mod transaction;
+
+#[cfg(test)]
+mod test_utils;
+
+pub use account::{
+    process_accounts, process_accounts_with_registry, Account, AccountFailure, AccountOutcome,
+    AccountRecord, AccountSet,
+};
+pub use block::{process_block, process_encoded_block};
+pub use error::{describe_custom_error, DecodeError, ErrorKind};
+pub use json_parsed::process_json_parsed;
+pub use registry::{
+    AccountParseFn, AccountParser, FnAccountParser, FnParser, ParseFn, ParseFuture,
+    ParserRegistry, ProgramParser,
+};
+pub use transaction::{
+    encoded_transaction_error, process_confirmed_transaction,
+    process_encoded_confirmed_transaction, transaction_error,
+};
+
+use std::sync::Arc;
 
 use serde::{Serialize, Deserialize};
 use solana_sdk::instruction::CompiledInstruction;
 use tokio::spawn;
-use tracing::info;
+use tokio::task::JoinError;
+use tracing::{error, info};
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct Instruction {
     // The local unique identifier of the instruction according to the transaction (not based on solana)
-    pub tx_instruction_id: i16,
+    pub tx_instruction_id: i32,
     // The transaction this instruction belongs to.
     pub transaction_hash: String,
     // The name of the program invoking this instruction.
     pub program: String,
+    // The accounts (pubkeys) passed into this instruction, in the order the program expects them.
+    pub accounts: Vec<String>,
     // The data contained from invoking this instruction.
     pub data: Vec<u8>,
     // If this is an inner instruction, we should depend on this
     pub parent_index: i16,
-    // The time this log was created in our time
+    // The time this row was processed at, in our (wall clock) time rather than the chain's.
     pub timestamp: i64,
+    // The slot of the block this instruction was confirmed in.
+    pub slot: u64,
+    // The (chain) time the block this instruction belongs to was produced, if known.
+    pub block_time: Option<i64>,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct InstructionFunction {
     // The local unique identifier of the instruction according to the transaction (not based on solana)
-    pub tx_instruction_id: i16,
+    pub tx_instruction_id: i32,
     // The transaction this instruction belongs to.
     pub transaction_hash: String,
     // If this is an inner instruction, we should depend on this
@@ -33,22 +68,58 @@ pub struct InstructionFunction {
     pub program: String,
     // Which function is this function? (Well duh)
     pub function_name: String,
-    // Like what it means dude.
-    pub timestamp: i64
+    // The time this row was processed at, in our (wall clock) time rather than the chain's.
+    pub timestamp: i64,
+    // The slot of the block this function was invoked in.
+    pub slot: u64,
+    // The (chain) time the block this function was invoked in was produced, if known.
+    pub block_time: Option<i64>,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct InstructionProperty {
     // The local unique identifier of the instruction according to the transaction (not based on solana)
-    pub tx_instruction_id: i16,
+    pub tx_instruction_id: i32,
     // The local unique identifier of the instruction type (not based on solana)
     pub transaction_hash: String,
     // If this is an inner instruction, we should depend on this
     pub parent_index: i16,
     pub key: String,
     pub value: String,
+    // How `value` should be read, so it can be cast safely.
+    pub value_type: ValueType,
     pub parent_key: String,
+    // The time this row was processed at, in our (wall clock) time rather than the chain's.
     pub timestamp: i64,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+}
+
+/// The type of an `InstructionProperty`'s value. Values are always stored as strings, this tells
+/// what they hold.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValueType {
+    // An unsigned integer of up to 64 bits, in decimal.
+    U64,
+    // An unsigned integer of up to 128 bits, in decimal.
+    U128,
+    // A number with a fractional part, in decimal, e.g. `1.500000000000000000` or `0.25`.
+    Decimal,
+    // A signed integer of up to 64 bits, in decimal.
+    I64,
+    // A base58 encoded public key.
+    Pubkey,
+    // Either `true` or `false`.
+    Bool,
+    // Binary data, base64 encoded.
+    Bytes,
+    // The name of an enum variant, e.g. `MintTokens`.
+    EnumName,
+    // Free form text.
+    String,
+    // An optional value that is not set. The value is empty.
+    Null,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -57,12 +128,241 @@ pub struct InstructionSet {
     pub properties: Vec<InstructionProperty>
 }
 
+#[derive(Clone, Serialize, Deserialize)]
+pub struct InstructionFailure {
+    // The instruction that could not be decoded, kept whole so it can be re-processed.
+    pub instruction: Instruction,
+    // Why the instruction could not be decoded.
+    pub error: DecodeError,
+}
+
+/// What became of an instruction handed to `process`.
+#[derive(Clone, Serialize, Deserialize)]
+pub enum InstructionOutcome {
+    // The instruction was decoded.
+    Decoded(InstructionSet),
+    // No parser is registered for the instruction's program.
+    UnsupportedProgram(InstructionFailure),
+    // The parser could not make sense of the instruction's data.
+    DeserializationError(InstructionFailure),
+    // The parser panicked while decoding the instruction.
+    Panic(InstructionFailure),
+}
+
+impl InstructionOutcome {
+    /// The decoded instruction, if it was decoded.
+    pub fn instruction_set(self) -> Option<InstructionSet> {
+        match self {
+            InstructionOutcome::Decoded(instruction_set) => Some(instruction_set),
+            _ => None,
+        }
+    }
+
+    /// Why the instruction was not decoded, if it was not.
+    pub fn failure(&self) -> Option<&InstructionFailure> {
+        match self {
+            InstructionOutcome::Decoded(_) => None,
+            InstructionOutcome::UnsupportedProgram(failure)
+            | InstructionOutcome::DeserializationError(failure)
+            | InstructionOutcome::Panic(failure) => Some(failure),
+        }
+    }
+}
+
+/// Keeps only the instructions that were decoded.
+pub fn decoded(outcomes: Vec<InstructionOutcome>) -> Vec<InstructionSet> {
+    outcomes.into_iter()
+        .filter_map(|outcome| outcome.instruction_set())
+        .collect()
+}
+
+/// What the rows of a decoded set are stamped from: an instruction, or the record of an account.
+/// Lets the helpers of a program build properties for both.
+pub(crate) trait Record {
+    fn function(&self, function_name: &str) -> InstructionFunction;
+
+    fn property(
+        &self,
+        parent_key: &str,
+        key: &str,
+        value: String,
+        value_type: ValueType,
+    ) -> InstructionProperty;
+
+    /// A property holding an optional value, of type `value_type` when it is set. A value that
+    /// is not set is emitted empty, as `ValueType::Null`.
+    fn optional_property(
+        &self,
+        parent_key: &str,
+        key: &str,
+        value: Option<String>,
+        value_type: ValueType,
+    ) -> InstructionProperty {
+        match value {
+            Some(value) => self.property(parent_key, key, value, value_type),
+            None => self.property(parent_key, key, "".to_string(), ValueType::Null),
+        }
+    }
+}
+
+impl Record for Instruction {
+    fn function(&self, function_name: &str) -> InstructionFunction {
+        Instruction::function(self, function_name)
+    }
+
+    fn property(
+        &self,
+        parent_key: &str,
+        key: &str,
+        value: String,
+        value_type: ValueType,
+    ) -> InstructionProperty {
+        Instruction::property(self, parent_key, key, value, value_type)
+    }
+}
+
+impl Record for AccountRecord {
+    fn function(&self, function_name: &str) -> InstructionFunction {
+        AccountRecord::function(self, function_name)
+    }
+
+    fn property(
+        &self,
+        parent_key: &str,
+        key: &str,
+        value: String,
+        value_type: ValueType,
+    ) -> InstructionProperty {
+        AccountRecord::property(self, parent_key, key, value, value_type)
+    }
+}
+
+/// The parent key every account role property is emitted under.
+pub const ACCOUNTS_PARENT_KEY: &str = "accounts";
+
+impl Instruction {
+    /// The function of a set decoded out of this instruction.
+    pub(crate) fn function(&self, function_name: &str) -> InstructionFunction {
+        InstructionFunction {
+            tx_instruction_id: self.tx_instruction_id,
+            transaction_hash: self.transaction_hash.clone(),
+            parent_index: self.parent_index,
+            program: self.program.clone(),
+            function_name: function_name.to_string(),
+            timestamp: self.timestamp,
+            slot: self.slot,
+            block_time: self.block_time,
+        }
+    }
+
+    /// A property of a set decoded out of this instruction.
+    pub(crate) fn property(
+        &self,
+        parent_key: &str,
+        key: &str,
+        value: String,
+        value_type: ValueType,
+    ) -> InstructionProperty {
+        InstructionProperty {
+            tx_instruction_id: self.tx_instruction_id,
+            transaction_hash: self.transaction_hash.clone(),
+            parent_index: self.parent_index,
+            key: key.to_string(),
+            value,
+            value_type,
+            parent_key: parent_key.to_string(),
+            timestamp: self.timestamp,
+            slot: self.slot,
+            block_time: self.block_time,
+        }
+    }
+
+    /// Names the accounts of this instruction after the roles given, in the order the program
+    /// expects them. Accounts that were not passed in are skipped. A role ending with `*`
+    /// (e.g. `signers*`) takes every account not claimed by the other roles, emitted as the
+    /// array `accounts/signers` with keys `0`, `1` and so on, and the roles after it are matched
+    /// from the end.
+    pub(crate) fn account_properties(&self, roles: &[&str]) -> Vec<InstructionProperty> {
+        // (parent_key, key, account)
+        let mut named_accounts: Vec<(String, String, &String)> = Vec::new();
+
+        match roles.iter().position(|role| role.ends_with('*')) {
+            Some(list_idx) => {
+                let trailing_roles = &roles[list_idx + 1..];
+                let list_end = self.accounts.len().saturating_sub(trailing_roles.len())
+                    .max(list_idx);
+
+                for (role, account) in roles[..list_idx].iter().zip(self.accounts.iter()) {
+                    named_accounts.push((ACCOUNTS_PARENT_KEY.to_string(), role.to_string(),
+                                         account));
+                }
+
+                let list_key = path::join(&[ACCOUNTS_PARENT_KEY,
+                                            roles[list_idx].trim_end_matches('*')]);
+                for (idx, account) in self.accounts.iter().enumerate()
+                    .take(list_end)
+                    .skip(list_idx) {
+                    named_accounts.push((list_key.clone(), (idx - list_idx).to_string(),
+                                         account));
+                }
+
+                for (role, account) in trailing_roles.iter()
+                    .zip(self.accounts.iter().skip(list_end)) {
+                    named_accounts.push((ACCOUNTS_PARENT_KEY.to_string(), role.to_string(),
+                                         account));
+                }
+            }
+            None => {
+                for (role, account) in roles.iter().zip(self.accounts.iter()) {
+                    named_accounts.push((ACCOUNTS_PARENT_KEY.to_string(), role.to_string(),
+                                         account));
+                }
+            }
+        }
+
+        named_accounts.into_iter()
+            .map(|(parent_key, key, account)| InstructionProperty {
+                tx_instruction_id: self.tx_instruction_id.clone(),
+                transaction_hash: self.transaction_hash.clone(),
+                parent_index: self.parent_index.clone(),
+                key,
+                value: account.clone(),
+                value_type: ValueType::Pubkey,
+                parent_key,
+                timestamp: self.timestamp.clone(),
+                slot: self.slot.clone(),
+                block_time: self.block_time.clone(),
+            })
+            .collect()
+    }
+}
+
 /// Derive a simple, singular function that 'decompiles' support program instruction invocations
 /// into a database and json-compatible format based on Solana FM's instruction properties.
 pub async fn process(
     instructions: Vec<Instruction>,
     og_instructions: Option<Vec<CompiledInstruction>>
-) -> Vec<InstructionSet> {
+) -> Vec<InstructionOutcome> {
+    process_with_registry(&ParserRegistry::with_builtins(), instructions, og_instructions).await
+}
+
+/// Same as `process`, but decodes the instructions with the parsers of the registry given,
+/// allowing callers to add, override or disable parsers.
+pub async fn process_with_registry(
+    registry: &ParserRegistry,
+    instructions: Vec<Instruction>,
+    og_instructions: Option<Vec<CompiledInstruction>>
+) -> Vec<InstructionOutcome> {
+    process_with_parsers(instructions, og_instructions, |program| registry.get(program)).await
+}
+
+/// Runs every instruction through the parser `find_parser` picks for its program.
+pub(crate) async fn process_with_parsers<F>(
+    instructions: Vec<Instruction>,
+    og_instructions: Option<Vec<CompiledInstruction>>,
+    find_parser: F,
+) -> Vec<InstructionOutcome>
+    where F: Fn(&str) -> Option<Arc<dyn ProgramParser>> {
     let instruction_jobs: Vec<_> = instructions.into_iter()
         .map(|instruction| {
             let ogi = if let Some(res) = og_instructions.clone() {
@@ -70,101 +370,117 @@ pub async fn process(
             } else {
                 None
             };
+            let job = find_parser(instruction.program.as_str())
+                .map(|parser| {
+                    let job_instruction = instruction.clone();
+                    spawn(async move { parser.parse(job_instruction, ogi).await })
+                });
 
-            spawn(async move {
-                match instruction.program.as_str() {
-                    programs::native_associated_token_account::PROGRAM_ADDRESS => {
-                        crate::programs::native_associated_token_account::fragment_instruction(
-                            instruction).await
-                    },
-                    programs::native_config::PROGRAM_ADDRESS => {
-                        crate::programs::native_config::fragment_instruction(instruction)
-                            .await
-                    },
-                    programs::native_loader::PROGRAM_ADDRESS => {
-                        crate::programs::native_loader::fragment_instruction(instruction)
-                            .await
-                    },
-                    programs::bpf_loader::PROGRAM_ADDRESS |
-                    programs::bpf_loader::PROGRAM_ADDRESS_2 => {
-                        crate::programs::bpf_loader::fragment_instruction(instruction)
-                            .await
-                    },
-                    programs::bpf_loader_upgradeable::PROGRAM_ADDRESS => {
-                        crate::programs::bpf_loader_upgradeable::fragment_instruction(instruction)
-                            .await
-                    }
-                    programs::native_secp256k1::PROGRAM_ADDRESS => {
-                        if let Some(og_instructs) = ogi {
-                            crate::programs::native_secp256k1::fragment_instruction(instruction,
-                                                                                    og_instructs.as_slice())
-                                .await
-                        } else {
-                            None
-                        }
-                    }
-                    programs::native_stake::PROGRAM_ADDRESS => {
-                        crate::programs::native_stake::fragment_instruction(instruction)
-                            .await
-                    }
-                    programs::native_system::PROGRAM_ADDRESS => {
-                        crate::programs::native_system::fragment_instruction(instruction)
-                            .await
-                    }
-                    programs::native_token::PROGRAM_ADDRESS => {
-                        crate::programs::native_token::fragment_instruction(instruction)
-                            .await
-                    }
-                    programs::native_token_lending::PROGRAM_ADDRESS => {
-                        crate::programs::native_token_lending::fragment_instruction(instruction)
-                            .await
-                    }
-                    programs::native_token_swap::PROGRAM_ADDRESS => {
-                        crate::programs::native_token_swap::fragment_instruction(instruction)
-                            .await
-                    }
-                    programs::serum_market::PROGRAM_ADDRESS_V1
-                        | programs::serum_market::PROGRAM_ADDRESS_V2
-                        | programs::serum_market::PROGRAM_ADDRESS_V3 => {
-                        crate::programs::serum_market::fragment_instruction(instruction)
-                            .await
-                    }
-                    programs::native_vote::PROGRAM_ADDRESS => {
-                        crate::programs::native_vote::fragment_instruction(instruction)
-                            .await
-                    }
-                    programs::solend_token_lending::PROGRAM_ADDRESS => {
-                        crate::programs::solend_token_lending::fragment_instruction(instruction)
-                            .await
-                    }
-                    _ => {
-                        info!("Looks like this program ({}) is an unsupported one.",
-                            instruction.program.to_string());
-
-                        None
-                    }
-                }
-            })
+            (instruction, job)
         })
         .collect();
 
-    let mut instruction_sets: Vec<InstructionSet> = Vec::new();
-    for job in instruction_jobs {
-        let res = job.await;
-        if let Ok(instruction_job_result) = res {
-            if let Some(instruction_set) = instruction_job_result {
-                instruction_sets.push(instruction_set);
+    let mut outcomes: Vec<InstructionOutcome> = Vec::new();
+    for (instruction, job) in instruction_jobs {
+        let outcome = match job {
+            Some(job) => match job.await {
+                Ok(Ok(instruction_set)) => InstructionOutcome::Decoded(instruction_set),
+                Ok(Err(error)) => InstructionOutcome::DeserializationError(InstructionFailure {
+                    instruction,
+                    error,
+                }),
+                Err(join_err) => {
+                    let reason = join_error_reason(join_err);
+                    error!("[spi-wrapper] The parser for program {} panicked: {}",
+                        instruction.program, reason);
+
+                    let error = DecodeError::panic(&instruction.program, &reason);
+                    InstructionOutcome::Panic(InstructionFailure { instruction, error })
+                }
+            },
+            None => {
+                info!("Looks like this program ({}) is an unsupported one.",
+                    instruction.program.to_string());
+
+                let error = DecodeError::unsupported(&instruction.program);
+                InstructionOutcome::UnsupportedProgram(InstructionFailure { instruction, error })
             }
-        }
+        };
+
+        outcomes.push(outcome);
     }
 
-    instruction_sets
+    outcomes
+}
+
+/// Why a parser's job did not complete: the message it panicked with, if any.
+pub(crate) fn join_error_reason(join_err: JoinError) -> String {
+    if join_err.is_panic() {
+        let panic = join_err.into_panic();
+        if let Some(message) = panic.downcast_ref::<&str>() {
+            message.to_string()
+        } else if let Some(message) = panic.downcast_ref::<String>() {
+            message.clone()
+        } else {
+            "The parser panicked.".to_string()
+        }
+    } else {
+        "The parser was cancelled.".to_string()
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use crate::test_utils::{block_on, instruction};
+
     #[test]
     fn it_works() {
         assert_eq!(2 + 2, 4);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn account_properties_follow_roles() {
+        let instruction = instruction(
+            "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+            &["source", "destination", "multisig", "signer_a", "signer_b"],
+            vec![],
+        );
+
+        let properties = instruction.account_properties(
+            &["source", "destination", "authority", "signers*"]);
+        let keys: Vec<&str> = properties.iter().map(|p| p.key.as_str()).collect();
+        assert_eq!(keys, vec!["source", "destination", "authority", "0", "1"]);
+        assert_eq!(properties[4].value, "signer_b");
+        assert_eq!(properties[4].parent_key, "accounts/signers");
+        assert!(properties[..3].iter().all(|p| p.parent_key == ACCOUNTS_PARENT_KEY));
+
+        let missing = instruction.account_properties(
+            &["a", "b", "c", "d", "e", "f", "g"]);
+        assert_eq!(missing.len(), 5);
+
+        let leading = instruction.account_properties(
+            &["open_orders*", "market", "event_queue"]);
+        let keys: Vec<&str> = leading.iter().map(|p| p.key.as_str()).collect();
+        assert_eq!(keys, vec!["0", "1", "2", "market", "event_queue"]);
+        assert_eq!(leading[3].value, "signer_a");
+    }
+
+    #[test]
+    fn process_reports_failures() {
+        let system = "11111111111111111111111111111111";
+        let unknown = "Unknown111111111111111111111111111111111111";
+
+        let outcomes = block_on(process(vec![
+            instruction(system, &[], vec![2, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0]),
+            instruction(system, &[], vec![255]),
+            instruction(unknown, &[], vec![]),
+        ], None));
+
+        assert!(matches!(outcomes[0], InstructionOutcome::Decoded(_)));
+        assert!(matches!(outcomes[1], InstructionOutcome::DeserializationError(_)));
+        assert_eq!(outcomes[1].failure().unwrap().error.kind, ErrorKind::Deserialization);
+        assert!(matches!(outcomes[2], InstructionOutcome::UnsupportedProgram(_)));
+        assert_eq!(outcomes[2].failure().unwrap().instruction.program, unknown);
+    }
+}
@@ -0,0 +1,166 @@
+//! The hierarchy of an instruction's properties.
+//!
+//! Every property sits at a path: its `parent_key` followed by its `key`. A `parent_key` is made
+//! of segments joined by `SEPARATOR`, starting from the root of the instruction (`""` is the root
+//! itself), and a `key` is always a single segment. Segments made of digits only are positions in
+//! an array, so `config_keys/0` is the first element of the `config_keys` array.
+use serde_json::{Map, Number, Value};
+
+use crate::{InstructionProperty, InstructionSet, ValueType};
+
+/// What separates the segments of a `parent_key`.
+pub const SEPARATOR: char = '/';
+
+/// Joins segments into a `parent_key`, skipping empty ones.
+pub fn join(segments: &[&str]) -> String {
+    segments.iter()
+        .filter(|segment| !segment.is_empty())
+        .cloned()
+        .collect::<Vec<&str>>()
+        .join(&SEPARATOR.to_string())
+}
+
+/// Splits a `parent_key` into its segments. The root has none.
+pub fn split(parent_key: &str) -> Vec<&str> {
+    parent_key.split(SEPARATOR)
+        .filter(|segment| !segment.is_empty())
+        .collect()
+}
+
+/// Whether a segment is a position in an array.
+pub fn is_index(segment: &str) -> bool {
+    !segment.is_empty() && segment.chars().all(|c| c.is_ascii_digit())
+}
+
+impl InstructionProperty {
+    /// The full path of the property, its `parent_key` segments followed by its `key`.
+    pub fn path(&self) -> Vec<&str> {
+        let mut segments = split(&self.parent_key);
+        segments.push(self.key.as_str());
+        segments
+    }
+
+    /// The value of the property as json, read according to its `value_type`. `Null` values
+    /// (e.g. an unset optional authority) and other empty values are `null`, and numbers that
+    /// do not fit a json number are kept as strings.
+    pub fn json_value(&self) -> Value {
+        if self.value_type == ValueType::Null
+            || (self.value.is_empty() && self.value_type != ValueType::String) {
+            return Value::Null;
+        }
+
+        let number = match self.value_type {
+            ValueType::U64 => self.value.parse::<u64>().ok().map(Number::from),
+            ValueType::I64 => self.value.parse::<i64>().ok().map(Number::from),
+            ValueType::Bool => return self.value.parse::<bool>()
+                .map(Value::Bool)
+                .unwrap_or_else(|_| Value::String(self.value.clone())),
+            _ => None,
+        };
+
+        number.map(Value::Number)
+            .unwrap_or_else(|| Value::String(self.value.clone()))
+    }
+}
+
+impl InstructionSet {
+    /// The instruction as a single json object: the fields of its function, with the properties
+    /// nested under `properties` following their paths.
+    pub fn to_json(&self) -> Value {
+        let mut json = match serde_json::to_value(&self.function) {
+            Ok(Value::Object(function)) => function,
+            _ => Map::new(),
+        };
+        json.insert("properties".to_string(), properties_to_json(&self.properties));
+
+        Value::Object(json)
+    }
+}
+
+/// Nests a flat list of properties following their paths. Objects whose keys are all array
+/// positions become arrays, ordered by position. When two properties share a path, the last one
+/// wins.
+pub fn properties_to_json(properties: &[InstructionProperty]) -> Value {
+    let mut root = Map::new();
+
+    for property in properties {
+        let path = property.path();
+        let (leaf, parents) = match path.split_last() {
+            Some(split) => split,
+            None => continue,
+        };
+
+        let mut node = &mut root;
+        for segment in parents {
+            let child = node.entry(segment.to_string())
+                .or_insert_with(|| Value::Object(Map::new()));
+            if !child.is_object() {
+                *child = Value::Object(Map::new());
+            }
+            node = match child {
+                Value::Object(map) => map,
+                _ => unreachable!(),
+            };
+        }
+
+        node.insert(leaf.to_string(), property.json_value());
+    }
+
+    into_arrays(Value::Object(root))
+}
+
+fn into_arrays(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            if !map.is_empty() && map.keys().all(|key| is_index(key)) {
+                let mut elements: Vec<(usize, Value)> = map.into_iter()
+                    .map(|(key, value)| (key.parse().unwrap_or_default(), into_arrays(value)))
+                    .collect();
+                elements.sort_by_key(|(idx, _)| *idx);
+
+                Value::Array(elements.into_iter().map(|(_, value)| value).collect())
+            } else {
+                Value::Object(map.into_iter()
+                    .map(|(key, value)| (key, into_arrays(value)))
+                    .collect())
+            }
+        }
+        value => value,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::instruction;
+
+    #[test]
+    fn instruction_sets_nest_into_json() {
+        let instruction = instruction(
+            "Config1111111111111111111111111111111111111",
+            &["config", "signer_a", "signer_b"],
+            vec![],
+        );
+        let property = |parent_key: &str, key: &str, value: &str, value_type: ValueType| {
+            instruction.property(parent_key, key, value.to_string(), value_type)
+        };
+
+        let mut properties = vec![
+            property("config_keys/1", "pubkey", "b", ValueType::Pubkey),
+            property("config_keys/0", "pubkey", "a", ValueType::Pubkey),
+            property("config_keys/0", "signer", "true", ValueType::Bool),
+            property("config/fees", "borrow_fee_wad", "5", ValueType::U64),
+            property("", "authority", "", ValueType::Pubkey),
+        ];
+        properties.extend(instruction.account_properties(&["config_account", "signers*"]));
+
+        let json = properties_to_json(&properties);
+        assert_eq!(json["config_keys"][0]["pubkey"], "a");
+        assert_eq!(json["config_keys"][0]["signer"], true);
+        assert_eq!(json["config_keys"][1]["pubkey"], "b");
+        assert_eq!(json["config"]["fees"]["borrow_fee_wad"], 5);
+        assert!(json["authority"].is_null());
+        assert_eq!(json["accounts"]["config_account"], "config");
+        assert_eq!(json["accounts"]["signers"][1], "signer_b");
+    }
+}
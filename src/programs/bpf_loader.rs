@@ -2,7 +2,8 @@ use bincode::deserialize;
 use solana_sdk::loader_instruction::LoaderInstruction;
 use tracing::error;
 
-use crate::{InstructionProperty, Instruction, InstructionSet, InstructionFunction};
+use crate::error::DecodeError;
+use crate::{InstructionProperty, Instruction, InstructionSet, InstructionFunction, ValueType};
 
 pub const PROGRAM_ADDRESS: &str = "BPFLoader1111111111111111111111111111111111";
 pub const PROGRAM_ADDRESS_2: &str = "BPFLoader2111111111111111111111111111111111";
@@ -14,16 +15,19 @@ pub const PROGRAM_ADDRESS_2: &str = "BPFLoader2111111111111111111111111111111111
 pub async fn fragment_instruction(
     // The instruction
     _instruction: Instruction,
-) -> Option<InstructionSet> {
+) -> Result<InstructionSet, DecodeError> {
     let bpf_loader_dr = deserialize::<LoaderInstruction>(
         &_instruction.data);
 
     return match bpf_loader_dr {
         Ok(ref bld) => {
             let deserialized_bpf_loader = bld.clone();
-            return match deserialized_bpf_loader {
+            let account_properties = _instruction.account_properties(
+                account_roles(&deserialized_bpf_loader));
+            let instruction_set: Result<InstructionSet, DecodeError> =
+                match deserialized_bpf_loader {
                 LoaderInstruction::Write { offset, bytes } => {
-                    Option::from(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: _instruction.tx_instruction_id.clone(),
                             transaction_hash: _instruction.transaction_hash.clone(),
@@ -31,6 +35,8 @@ pub async fn fragment_instruction(
                             program: _instruction.program.clone(),
                             function_name: "write".to_string(),
                             timestamp: _instruction.timestamp.clone(),
+                            slot: _instruction.slot.clone(),
+                            block_time: _instruction.block_time.clone(),
                         },
                         properties: vec![
                             InstructionProperty {
@@ -39,8 +45,11 @@ pub async fn fragment_instruction(
                                 parent_index: _instruction.parent_index.clone(),
                                 key: "offset".to_string(),
                                 value: offset.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: _instruction.timestamp.clone(),
+                                slot: _instruction.slot.clone(),
+                                block_time: _instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: _instruction.tx_instruction_id.clone(),
@@ -48,14 +57,17 @@ pub async fn fragment_instruction(
                                 parent_index: _instruction.parent_index.clone(),
                                 key: "bytes".to_string(),
                                 value: base64::encode(&bytes),
-                                parent_key: "info".to_string(),
+                                value_type: ValueType::Bytes,
+                                parent_key: "".to_string(),
                                 timestamp: _instruction.timestamp.clone(),
+                                slot: _instruction.slot.clone(),
+                                block_time: _instruction.block_time.clone(),
                             }
                         ],
                     })
                 }
                 LoaderInstruction::Finalize => {
-                    Option::from(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: _instruction.tx_instruction_id.clone(),
                             transaction_hash: _instruction.transaction_hash.clone(),
@@ -63,18 +75,33 @@ pub async fn fragment_instruction(
                             program: _instruction.program.clone(),
                             function_name: "finalize".to_string(),
                             timestamp: _instruction.timestamp.clone(),
+                            slot: _instruction.slot.clone(),
+                            block_time: _instruction.block_time.clone(),
                         },
                         properties: vec![],
                     })
                 }
-            }
+            };
+
+            instruction_set.map(|mut set| {
+                set.properties.extend(account_properties);
+                set
+            })
         }
         Err(err) => {
             // If the instruction parsing is failing, bail out
             error!("[spi-wrapper/bpf_loader] Attempt to parse instruction from program {} failed due to \
         {}.", _instruction.program, err);
 
-            None
+            Err(DecodeError::from_bincode_error(&_instruction.program, &err))
         }
     }
-}
\ No newline at end of file
+}
+
+/// The roles of the accounts each instruction expects, in order.
+fn account_roles(instruction: &LoaderInstruction) -> &'static [&'static str] {
+    match instruction {
+        LoaderInstruction::Write { .. } => &["account"],
+        LoaderInstruction::Finalize => &["account", "rent_sysvar"],
+    }
+}
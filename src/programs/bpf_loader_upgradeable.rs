@@ -1,10 +1,14 @@
-use solana_account_decoder::parse_bpf_loader::{
-    parse_bpf_upgradeable_loader, BpfUpgradeableLoaderAccountType,
-};
+use bincode::deserialize;
+use solana_sdk::bpf_loader_upgradeable::UpgradeableLoaderState;
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::loader_upgradeable_instruction::UpgradeableLoaderInstruction;
 use tracing::error;
 
-use crate::{Instruction, InstructionFunction, InstructionProperty, InstructionSet};
-use solana_account_decoder::parse_account_data::{ParseAccountError, ParsableAccount};
+use crate::error::DecodeError;
+use crate::{
+    AccountRecord, Instruction, InstructionFunction, InstructionProperty, InstructionSet, Record,
+    ValueType,
+};
 
 pub const PROGRAM_ADDRESS: &str = "BPFLoaderUpgradeab1e11111111111111111111111";
 
@@ -15,172 +19,286 @@ pub const PROGRAM_ADDRESS: &str = "BPFLoaderUpgradeab1e11111111111111111111111";
 pub async fn fragment_instruction(
     // The instruction
     instruction: Instruction,
-) -> Option<InstructionSet> {
-    let bpf_loader_upgradeable_dr =
-        parse_bpf_upgradeable_loader(instruction.data.as_slice());
+) -> Result<InstructionSet, DecodeError> {
+    let bpf_loader_upgradeable_dr = deserialize::<UpgradeableLoaderInstruction>(
+        &instruction.data.as_slice());
 
     return match bpf_loader_upgradeable_dr {
         Ok(ref blu) => {
             let bpf_loader_upgradeable_i = blu.clone();
-
-            match bpf_loader_upgradeable_i {
-                BpfUpgradeableLoaderAccountType::Uninitialized => {
-                    Some(InstructionSet {
+            let account_properties = instruction.account_properties(
+                account_roles(&bpf_loader_upgradeable_i));
+            let instruction_set = match bpf_loader_upgradeable_i {
+                UpgradeableLoaderInstruction::InitializeBuffer => {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
                             parent_index: instruction.parent_index.clone(),
                             program: instruction.program.clone(),
-                            function_name: "uninitialized".to_string(),
-                            timestamp: instruction.timestamp.clone()
+                            function_name: "initialize-buffer".to_string(),
+                            timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
-                        properties: vec![]
+                        properties: vec![],
                     })
                 }
-                BpfUpgradeableLoaderAccountType::Buffer(buffer) => {
-                    Some(InstructionSet {
+                UpgradeableLoaderInstruction::Write { offset, bytes } => {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
                             parent_index: instruction.parent_index.clone(),
                             program: instruction.program.clone(),
-                            function_name: "buffer".to_string(),
-                            timestamp: instruction.timestamp.clone()
+                            function_name: "write".to_string(),
+                            timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
                                 transaction_hash: instruction.transaction_hash.clone(),
                                 parent_index: instruction.parent_index.clone(),
-                                key: "authority".to_string(),
-                                value: if let Some(ba) = buffer.authority.clone() {
-                                    ba
-                                } else {
-                                    "".to_string()
-                                },
-                                parent_key: "buffer".to_string(),
+                                key: "offset".to_string(),
+                                value: offset.to_string(),
+                                value_type: ValueType::U64,
+                                parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
                                 transaction_hash: instruction.transaction_hash.clone(),
                                 parent_index: instruction.parent_index.clone(),
-                                key: "data".to_string(),
-                                value: serde_json::to_string(&buffer.data).unwrap().to_string(),
-                                parent_key: "buffer".to_string(),
+                                key: "bytes".to_string(),
+                                value: base64::encode(&bytes),
+                                value_type: ValueType::Bytes,
+                                parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
-                        ]
+                        ],
                     })
                 }
-                BpfUpgradeableLoaderAccountType::Program(program) => {
-                    Some(InstructionSet {
+                UpgradeableLoaderInstruction::DeployWithMaxDataLen { max_data_len } => {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
                             parent_index: instruction.parent_index.clone(),
                             program: instruction.program.clone(),
-                            function_name: "program".to_string(),
-                            timestamp: instruction.timestamp.clone()
+                            function_name: "deploy-with-max-data-len".to_string(),
+                            timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
                                 transaction_hash: instruction.transaction_hash.clone(),
                                 parent_index: instruction.parent_index.clone(),
-                                key: "program_data".to_string(),
-                                value: serde_json::to_string(&program.program_data).unwrap().to_string(),
-                                parent_key: "program".to_string(),
+                                key: "max_data_len".to_string(),
+                                value: max_data_len.to_string(),
+                                value_type: ValueType::U64,
+                                parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
-                            }
-                        ]
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
+                            },
+                        ],
                     })
                 }
-                BpfUpgradeableLoaderAccountType::ProgramData(program_data) => {
-                    Some(InstructionSet {
+                UpgradeableLoaderInstruction::Upgrade => {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
                             parent_index: instruction.parent_index.clone(),
                             program: instruction.program.clone(),
-                            function_name: "program-data".to_string(),
-                            timestamp: instruction.timestamp.clone()
+                            function_name: "upgrade".to_string(),
+                            timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
-                        properties: vec![
-                            InstructionProperty {
-                                tx_instruction_id: instruction.tx_instruction_id.clone(),
-                                transaction_hash: instruction.transaction_hash.clone(),
-                                parent_index: instruction.parent_index.clone(),
-                                key: "authority".to_string(),
-                                value: if let Some(auth) = program_data.authority.clone() {
-                                    auth
-                                } else {
-                                    "".to_string()
-                                },
-                                parent_key: "program_data".to_string(),
-                                timestamp: instruction.timestamp.clone(),
-                            },
-                            InstructionProperty {
-                                tx_instruction_id: instruction.tx_instruction_id.clone(),
-                                transaction_hash: instruction.transaction_hash.clone(),
-                                parent_index: instruction.parent_index.clone(),
-                                key: "data".to_string(),
-                                value: serde_json::to_string(&program_data.data).unwrap().to_string(),
-                                parent_key: "program_data".to_string(),
-                                timestamp: instruction.timestamp.clone(),
-                            },
-                            InstructionProperty {
-                                tx_instruction_id: instruction.tx_instruction_id.clone(),
-                                transaction_hash: instruction.transaction_hash.clone(),
-                                parent_index: instruction.parent_index.clone(),
-                                key: "slot".to_string(),
-                                value: program_data.slot.to_string(),
-                                parent_key: "program_data".to_string(),
-                                timestamp: instruction.timestamp.clone(),
-                            },
-                        ]
+                        properties: vec![],
                     })
                 }
-            }
-        }
-        Err(instruction_err) => {
-            // If the instruction parsing is failing, bail out
-            match instruction_err {
-                ParseAccountError::AccountNotParsable(parseable_account) => {
-                    let account_involved = match parseable_account {
-                        ParsableAccount::BpfUpgradeableLoader => "BpfUpgradeableLoader",
-                        ParsableAccount::Config => "Config",
-                        ParsableAccount::Nonce => "Nonce",
-                        ParsableAccount::SplToken => "SplToken",
-                        ParsableAccount::Stake => "Stake",
-                        ParsableAccount::Sysvar => "Sysvar",
-                        ParsableAccount::Vote => "Vote",
-                    };
-
-                    error!("[spi-wrapper/bpf_loader_upgradeable] Attempt to parse instruction from \
-                program {} failed as the account was not parsable ({} was not parseable).",
-                    instruction.program, account_involved);
-                }
-                ParseAccountError::ProgramNotParsable => {
-                    error!("[spi-wrapper/bpf_loader_upgradeable] Attempt to parse instruction from \
-                program {} failed as it was not parsable.", instruction.program);
-                }
-                ParseAccountError::AdditionalDataMissing(missing) => {
-                    error!("[spi-wrapper/bpf_loader_upgradeable] Attempt to parse instruction from \
-                program {} failed as it was missing data for {}.", instruction.program, missing);
-                }
-                ParseAccountError::InstructionError(_err) => {
-                    // TODO: Tell us what instruction error it exactly is.
-                    error!("[spi-wrapper/bpf_loader_upgradeable] Attempt to parse instruction from \
-                program {} failed as there was an instruction error.", instruction.program);
+                UpgradeableLoaderInstruction::SetAuthority => {
+                    Ok(InstructionSet {
+                        function: InstructionFunction {
+                            tx_instruction_id: instruction.tx_instruction_id.clone(),
+                            transaction_hash: instruction.transaction_hash.clone(),
+                            parent_index: instruction.parent_index.clone(),
+                            program: instruction.program.clone(),
+                            function_name: "set-authority".to_string(),
+                            timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
+                        },
+                        properties: vec![],
+                    })
                 }
-                ParseAccountError::SerdeJsonError(err) => {
-                    error!("[spi-wrapper/bpf_loader_upgradeable] Attempt to parse instruction from \
-                program {} failed as there was serde json error: {}.", instruction.program, err);
+                UpgradeableLoaderInstruction::Close => {
+                    Ok(InstructionSet {
+                        function: InstructionFunction {
+                            tx_instruction_id: instruction.tx_instruction_id.clone(),
+                            transaction_hash: instruction.transaction_hash.clone(),
+                            parent_index: instruction.parent_index.clone(),
+                            program: instruction.program.clone(),
+                            function_name: "close".to_string(),
+                            timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
+                        },
+                        properties: vec![],
+                    })
                 }
-            }
+            };
+
+            instruction_set.map(|mut set| {
+                set.properties.extend(account_properties);
+                set
+            })
+        }
+        Err(err) => {
+            // If the instruction parsing is failing, bail out
+            error!("[spi-wrapper/bpf_loader_upgradeable] Attempt to parse instruction from program \
+            {} failed due to {}.", instruction.program, err);
 
-            None
+            Err(DecodeError::from_bincode_error(&instruction.program, &err))
         }
     }
-}
\ No newline at end of file
+}
+
+/// Decodes the data of an upgradeable loader *account* (a buffer, a program or its program data)
+/// rather than an instruction. The bytes stored by buffers and program data accounts are
+/// emitted base64 encoded.
+pub async fn fragment_account(
+    // The record of the account
+    account: AccountRecord,
+) -> Result<InstructionSet, DecodeError> {
+    let state = deserialize::<UpgradeableLoaderState>(account.data.as_slice()).map_err(|err| {
+        error!("[spi-wrapper/bpf_loader_upgradeable] Attempt to parse account {} failed due to \
+        {}.", account.pubkey, err);
+
+        DecodeError::from_bincode_error(&account.owner, &err)
+    })?;
+
+    // The bytes following the metadata of a buffer or program data account.
+    let stored_data = |offset: Result<usize, InstructionError>| {
+        offset
+            .map(|offset| base64::encode(account.data.get(offset..).unwrap_or_default()))
+            .map_err(|err| DecodeError::from_instruction_error(&account.owner, &err))
+    };
+
+    match state {
+        UpgradeableLoaderState::Uninitialized => Ok(InstructionSet {
+            function: account.function("uninitialized"),
+            properties: vec![],
+        }),
+        UpgradeableLoaderState::Buffer { authority_address } => Ok(InstructionSet {
+            function: account.function("buffer"),
+            properties: vec![
+                account.optional_property("buffer", "authority",
+                                          authority_address.map(|address| address.to_string()),
+                                          ValueType::Pubkey),
+                account.property("buffer", "data",
+                                 stored_data(UpgradeableLoaderState::buffer_data_offset())?,
+                                 ValueType::Bytes),
+            ],
+        }),
+        UpgradeableLoaderState::Program { programdata_address } => Ok(InstructionSet {
+            function: account.function("program"),
+            properties: vec![
+                account.property("program", "program_data", programdata_address.to_string(),
+                                 ValueType::Pubkey),
+            ],
+        }),
+        UpgradeableLoaderState::ProgramData { slot, upgrade_authority_address } => {
+            Ok(InstructionSet {
+                function: account.function("program-data"),
+                properties: vec![
+                    account.optional_property(
+                        "program_data", "authority",
+                        upgrade_authority_address.map(|address| address.to_string()),
+                        ValueType::Pubkey),
+                    account.property(
+                        "program_data", "data",
+                        stored_data(UpgradeableLoaderState::programdata_data_offset())?,
+                        ValueType::Bytes),
+                    account.property("program_data", "slot", slot.to_string(), ValueType::U64),
+                ],
+            })
+        }
+    }
+}
+
+/// The roles of the accounts each instruction expects, in order.
+fn account_roles(instruction: &UpgradeableLoaderInstruction) -> &'static [&'static str] {
+    match instruction {
+        UpgradeableLoaderInstruction::InitializeBuffer => &["buffer", "authority"],
+        UpgradeableLoaderInstruction::Write { .. } => &["buffer", "authority"],
+        UpgradeableLoaderInstruction::DeployWithMaxDataLen { .. } => &[
+            "payer", "program_data", "program", "buffer", "rent_sysvar", "clock_sysvar",
+            "system_program", "authority",
+        ],
+        UpgradeableLoaderInstruction::Upgrade => &[
+            "program_data", "program", "buffer", "spill", "rent_sysvar", "clock_sysvar",
+            "authority",
+        ],
+        UpgradeableLoaderInstruction::SetAuthority => &[
+            "account", "current_authority", "new_authority",
+        ],
+        UpgradeableLoaderInstruction::Close => &["account", "recipient", "authority", "program"],
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{decode, decode_account, instruction};
+    use solana_sdk::pubkey::Pubkey;
+
+    #[test]
+    fn buffers_and_programs_are_decoded() {
+        let authority = Pubkey::new_unique();
+        let mut buffer = bincode::serialize(&UpgradeableLoaderState::Buffer {
+            authority_address: Some(authority),
+        }).unwrap();
+        buffer.extend(b"elf");
+
+        let buffer = decode_account(PROGRAM_ADDRESS, buffer).to_json();
+        assert_eq!(buffer["function_name"], "buffer");
+        assert_eq!(buffer["properties"]["buffer"]["authority"], authority.to_string());
+        assert_eq!(buffer["properties"]["buffer"]["data"], "ZWxm");
+
+        let program_data = Pubkey::new_unique();
+        let program = bincode::serialize(&UpgradeableLoaderState::Program {
+            programdata_address: program_data,
+        }).unwrap();
+
+        let program = decode_account(PROGRAM_ADDRESS, program).state;
+        let property = &program.properties[0];
+        assert_eq!(property.key, "program_data");
+        assert_eq!(property.value, program_data.to_string());
+        assert_eq!(property.value_type, ValueType::Pubkey);
+    }
+
+    #[test]
+    fn closing_program_data_names_the_program() {
+        let data = bincode::serialize(&UpgradeableLoaderInstruction::Close).unwrap();
+        let close = decode(instruction(PROGRAM_ADDRESS,
+                                       &["program-data", "recipient", "authority", "program"],
+                                       data)).to_json();
+
+        assert_eq!(close["function_name"], "close");
+        let accounts = &close["properties"]["accounts"];
+        assert_eq!(accounts["program"], "program");
+        assert_eq!(accounts["authority"], "authority");
+        assert!(accounts.get("signers").is_none());
+    }
+}
@@ -0,0 +1,250 @@
+//! The account decoding shared by the token-lending program and its Solend fork. Both lay out
+//! their reserves, obligations and lending markets the same way, each with its own state types.
+
+use std::fmt::Display;
+
+use solana_program::program_pack::{IsInitialized, Pack};
+use solana_sdk::pubkey::Pubkey;
+use tracing::error;
+
+use crate::error::DecodeError;
+use crate::{path, AccountRecord, InstructionProperty, InstructionSet, Record, ValueType};
+
+/// A reserve, obligation or lending market, as one of the token-lending programs stores it.
+pub(crate) trait LendingState: Pack + IsInitialized {
+    /// The function accounts holding this state are decoded into, e.g. `reserve`.
+    const FUNCTION_NAME: &'static str;
+
+    /// The rows of the account. The rows both programs have come from `common_*_properties`.
+    fn properties(&self, account: &AccountRecord) -> Vec<InstructionProperty>;
+}
+
+/// Decodes the data of a token-lending account holding `S`.
+pub(crate) fn lending_account_properties<S: LendingState>(
+    account: &AccountRecord,
+) -> Result<InstructionSet, DecodeError> {
+    match S::unpack(account.data.as_slice()) {
+        Ok(state) => Ok(InstructionSet {
+            function: account.function(S::FUNCTION_NAME),
+            properties: state.properties(account),
+        }),
+        Err(err) => {
+            let decode_error = DecodeError::from_program_error(&account.owner, &err);
+            error!("[spi-wrapper/programs/lending_account] Attempt to parse account {} of {} \
+            failed due to {}.", account.pubkey, account.owner, decode_error);
+
+            Err(decode_error)
+        }
+    }
+}
+
+/// The fields of a lending market both programs have.
+pub(crate) struct CommonLendingMarket<'a> {
+    pub version: u8,
+    pub bump_seed: u8,
+    pub owner: &'a Pubkey,
+    pub quote_currency: &'a [u8; 32],
+    pub token_program_id: &'a Pubkey,
+    pub oracle_program_id: &'a Pubkey,
+}
+
+/// The fields of a reserve both programs have. Decimals are displayed as their `Decimal` does.
+pub(crate) struct CommonReserve<'a> {
+    pub version: u8,
+    pub lending_market: &'a Pubkey,
+    pub last_update: CommonLastUpdate,
+    pub liquidity_mint_pubkey: &'a Pubkey,
+    pub liquidity_mint_decimals: u8,
+    pub liquidity_supply_pubkey: &'a Pubkey,
+    pub liquidity_available_amount: u64,
+    pub liquidity_borrowed_amount_wads: &'a dyn Display,
+    pub liquidity_cumulative_borrow_rate_wads: &'a dyn Display,
+    pub liquidity_market_price: &'a dyn Display,
+    pub collateral_mint_pubkey: &'a Pubkey,
+    pub collateral_mint_total_supply: u64,
+    pub collateral_supply_pubkey: &'a Pubkey,
+    pub config_optimal_utilization_rate: u8,
+    pub config_loan_to_value_ratio: u8,
+    pub config_liquidation_bonus: u8,
+    pub config_liquidation_threshold: u8,
+    pub config_min_borrow_rate: u8,
+    pub config_optimal_borrow_rate: u8,
+    pub config_max_borrow_rate: u8,
+    pub config_borrow_fee_wad: u64,
+    pub config_flash_loan_fee_wad: u64,
+    pub config_host_fee_percentage: u8,
+}
+
+/// The fields of an obligation, which both programs have all of.
+pub(crate) struct CommonObligation<'a> {
+    pub version: u8,
+    pub lending_market: &'a Pubkey,
+    pub owner: &'a Pubkey,
+    pub last_update: CommonLastUpdate,
+    pub deposits: Vec<CommonDeposit<'a>>,
+    pub borrows: Vec<CommonBorrow<'a>>,
+    pub deposited_value: &'a dyn Display,
+    pub borrowed_value: &'a dyn Display,
+    pub allowed_borrow_value: &'a dyn Display,
+    pub unhealthy_borrow_value: &'a dyn Display,
+    // The share of the deposits' value that is borrowed, as of the last refresh. There is none
+    // without deposits.
+    pub loan_to_value: Option<String>,
+}
+
+pub(crate) struct CommonDeposit<'a> {
+    pub deposit_reserve: &'a Pubkey,
+    pub deposited_amount: u64,
+    pub market_value: &'a dyn Display,
+}
+
+pub(crate) struct CommonBorrow<'a> {
+    pub borrow_reserve: &'a Pubkey,
+    pub cumulative_borrow_rate_wads: &'a dyn Display,
+    pub borrowed_amount_wads: &'a dyn Display,
+    pub market_value: &'a dyn Display,
+}
+
+pub(crate) struct CommonLastUpdate {
+    pub slot: u64,
+    pub stale: bool,
+}
+
+pub(crate) fn common_lending_market_properties(
+    account: &AccountRecord,
+    lending_market: CommonLendingMarket,
+) -> Vec<InstructionProperty> {
+    vec![
+        account.property("", "version", lending_market.version.to_string(), ValueType::U64),
+        account.property("", "bump_seed", lending_market.bump_seed.to_string(), ValueType::U64),
+        account.property("", "owner", lending_market.owner.to_string(), ValueType::Pubkey),
+        account.property("", "quote_currency",
+                         Pubkey::new_from_array(*lending_market.quote_currency).to_string(),
+                         ValueType::Pubkey),
+        account.property("", "token_program_id", lending_market.token_program_id.to_string(),
+                         ValueType::Pubkey),
+        account.property("", "oracle_program_id", lending_market.oracle_program_id.to_string(),
+                         ValueType::Pubkey),
+    ]
+}
+
+pub(crate) fn common_reserve_properties(
+    account: &AccountRecord,
+    reserve: CommonReserve,
+) -> Vec<InstructionProperty> {
+    let mut properties = vec![
+        account.property("", "version", reserve.version.to_string(), ValueType::U64),
+        account.property("", "lending_market", reserve.lending_market.to_string(),
+                         ValueType::Pubkey),
+    ];
+    properties.extend(last_update_properties(account, &reserve.last_update));
+    properties.extend(vec![
+        account.property("liquidity", "mint_pubkey", reserve.liquidity_mint_pubkey.to_string(),
+                         ValueType::Pubkey),
+        account.property("liquidity", "mint_decimals",
+                         reserve.liquidity_mint_decimals.to_string(), ValueType::U64),
+        account.property("liquidity", "supply_pubkey",
+                         reserve.liquidity_supply_pubkey.to_string(), ValueType::Pubkey),
+        account.property("liquidity", "available_amount",
+                         reserve.liquidity_available_amount.to_string(), ValueType::U64),
+        account.property("liquidity", "borrowed_amount_wads",
+                         reserve.liquidity_borrowed_amount_wads.to_string(), ValueType::Decimal),
+        account.property("liquidity", "cumulative_borrow_rate_wads",
+                         reserve.liquidity_cumulative_borrow_rate_wads.to_string(),
+                         ValueType::Decimal),
+        account.property("liquidity", "market_price", reserve.liquidity_market_price.to_string(),
+                         ValueType::Decimal),
+        account.property("collateral", "mint_pubkey", reserve.collateral_mint_pubkey.to_string(),
+                         ValueType::Pubkey),
+        account.property("collateral", "mint_total_supply",
+                         reserve.collateral_mint_total_supply.to_string(), ValueType::U64),
+        account.property("collateral", "supply_pubkey",
+                         reserve.collateral_supply_pubkey.to_string(), ValueType::Pubkey),
+        account.property("config", "optimal_utilization_rate",
+                         reserve.config_optimal_utilization_rate.to_string(), ValueType::U64),
+        account.property("config", "loan_to_value_ratio",
+                         reserve.config_loan_to_value_ratio.to_string(), ValueType::U64),
+        account.property("config", "liquidation_bonus",
+                         reserve.config_liquidation_bonus.to_string(), ValueType::U64),
+        account.property("config", "liquidation_threshold",
+                         reserve.config_liquidation_threshold.to_string(), ValueType::U64),
+        account.property("config", "min_borrow_rate",
+                         reserve.config_min_borrow_rate.to_string(), ValueType::U64),
+        account.property("config", "optimal_borrow_rate",
+                         reserve.config_optimal_borrow_rate.to_string(), ValueType::U64),
+        account.property("config", "max_borrow_rate",
+                         reserve.config_max_borrow_rate.to_string(), ValueType::U64),
+        account.property("config/fees", "borrow_fee_wad",
+                         reserve.config_borrow_fee_wad.to_string(), ValueType::U64),
+        account.property("config/fees", "flash_loan_fee_wad",
+                         reserve.config_flash_loan_fee_wad.to_string(), ValueType::U64),
+        account.property("config/fees", "host_fee_percentage",
+                         reserve.config_host_fee_percentage.to_string(), ValueType::U64),
+    ]);
+
+    properties
+}
+
+pub(crate) fn common_obligation_properties(
+    account: &AccountRecord,
+    obligation: CommonObligation,
+) -> Vec<InstructionProperty> {
+    let mut properties = vec![
+        account.property("", "version", obligation.version.to_string(), ValueType::U64),
+        account.property("", "lending_market", obligation.lending_market.to_string(),
+                         ValueType::Pubkey),
+        account.property("", "owner", obligation.owner.to_string(), ValueType::Pubkey),
+    ];
+    properties.extend(last_update_properties(account, &obligation.last_update));
+
+    for (idx, deposit) in obligation.deposits.iter().enumerate() {
+        let parent_key = path::join(&["deposits", &idx.to_string()]);
+        properties.extend(vec![
+            account.property(&parent_key, "deposit_reserve", deposit.deposit_reserve.to_string(),
+                             ValueType::Pubkey),
+            account.property(&parent_key, "deposited_amount",
+                             deposit.deposited_amount.to_string(), ValueType::U64),
+            account.property(&parent_key, "market_value", deposit.market_value.to_string(),
+                             ValueType::Decimal),
+        ]);
+    }
+
+    for (idx, borrow) in obligation.borrows.iter().enumerate() {
+        let parent_key = path::join(&["borrows", &idx.to_string()]);
+        properties.extend(vec![
+            account.property(&parent_key, "borrow_reserve", borrow.borrow_reserve.to_string(),
+                             ValueType::Pubkey),
+            account.property(&parent_key, "cumulative_borrow_rate_wads",
+                             borrow.cumulative_borrow_rate_wads.to_string(), ValueType::Decimal),
+            account.property(&parent_key, "borrowed_amount_wads",
+                             borrow.borrowed_amount_wads.to_string(), ValueType::Decimal),
+            account.property(&parent_key, "market_value", borrow.market_value.to_string(),
+                             ValueType::Decimal),
+        ]);
+    }
+
+    properties.extend(vec![
+        account.property("", "deposited_value", obligation.deposited_value.to_string(),
+                         ValueType::Decimal),
+        account.property("", "borrowed_value", obligation.borrowed_value.to_string(),
+                         ValueType::Decimal),
+        account.property("", "allowed_borrow_value",
+                         obligation.allowed_borrow_value.to_string(), ValueType::Decimal),
+        account.property("", "unhealthy_borrow_value",
+                         obligation.unhealthy_borrow_value.to_string(), ValueType::Decimal),
+        account.optional_property("", "loan_to_value", obligation.loan_to_value,
+                                  ValueType::Decimal),
+    ]);
+
+    properties
+}
+
+fn last_update_properties(
+    account: &AccountRecord,
+    last_update: &CommonLastUpdate,
+) -> Vec<InstructionProperty> {
+    vec![
+        account.property("last_update", "slot", last_update.slot.to_string(), ValueType::U64),
+        account.property("last_update", "stale", last_update.stale.to_string(), ValueType::Bool),
+    ]
+}
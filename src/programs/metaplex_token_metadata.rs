@@ -0,0 +1,684 @@
+use borsh::BorshDeserialize;
+use solana_program::pubkey::Pubkey;
+use tracing::error;
+
+use crate::error::DecodeError;
+use crate::path;
+use crate::{AccountRecord, Instruction, InstructionProperty, InstructionSet, Record, ValueType};
+
+pub const PROGRAM_ADDRESS: &str = "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s";
+
+// The types below mirror the Borsh layout of the token metadata program
+// (https://github.com/metaplex-foundation/metaplex-program-library/tree/master/token-metadata),
+// whose crate requires a newer sdk than ours.
+
+#[derive(BorshDeserialize, Clone, Debug)]
+pub struct Creator {
+    pub address: Pubkey,
+    pub verified: bool,
+    // In percentages, the shares of all creators add up to 100.
+    pub share: u8,
+}
+
+#[derive(BorshDeserialize, Clone, Debug)]
+pub struct Data {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub seller_fee_basis_points: u16,
+    pub creators: Option<Vec<Creator>>,
+}
+
+#[derive(BorshDeserialize, Clone, Debug)]
+pub struct Collection {
+    pub verified: bool,
+    pub key: Pubkey,
+}
+
+#[derive(BorshDeserialize, Clone, Debug)]
+pub enum UseMethod {
+    Burn,
+    Multiple,
+    Single,
+}
+
+#[derive(BorshDeserialize, Clone, Debug)]
+pub struct Uses {
+    pub use_method: UseMethod,
+    pub remaining: u64,
+    pub total: u64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug)]
+pub struct DataV2 {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub seller_fee_basis_points: u16,
+    pub creators: Option<Vec<Creator>>,
+    pub collection: Option<Collection>,
+    pub uses: Option<Uses>,
+}
+
+impl From<Data> for DataV2 {
+    fn from(data: Data) -> Self {
+        DataV2 {
+            name: data.name,
+            symbol: data.symbol,
+            uri: data.uri,
+            seller_fee_basis_points: data.seller_fee_basis_points,
+            creators: data.creators,
+            collection: None,
+            uses: None,
+        }
+    }
+}
+
+#[derive(BorshDeserialize, Clone, Debug)]
+pub struct Reservation {
+    pub address: Pubkey,
+    pub spots_remaining: u64,
+    pub total_spots: u64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug)]
+pub enum MetadataInstruction {
+    CreateMetadataAccount { data: Data, is_mutable: bool },
+    UpdateMetadataAccount {
+        data: Option<Data>,
+        update_authority: Option<Pubkey>,
+        primary_sale_happened: Option<bool>,
+    },
+    DeprecatedCreateMasterEdition { max_supply: Option<u64> },
+    DeprecatedMintNewEditionFromMasterEditionViaPrintingToken,
+    UpdatePrimarySaleHappenedViaToken,
+    DeprecatedSetReservationList {
+        reservations: Vec<Reservation>,
+        total_reservation_spots: Option<u64>,
+        offset: u64,
+        total_spot_offset: u64,
+    },
+    DeprecatedCreateReservationList,
+    SignMetadata,
+    DeprecatedMintPrintingTokensViaToken { supply: u64 },
+    DeprecatedMintPrintingTokens { supply: u64 },
+    CreateMasterEdition { max_supply: Option<u64> },
+    MintNewEditionFromMasterEditionViaToken { edition: u64 },
+    ConvertMasterEditionV1ToV2,
+    MintNewEditionFromMasterEditionViaVaultProxy { edition: u64 },
+    PuffMetadata,
+    UpdateMetadataAccountV2 {
+        data: Option<DataV2>,
+        update_authority: Option<Pubkey>,
+        primary_sale_happened: Option<bool>,
+        is_mutable: Option<bool>,
+    },
+    CreateMetadataAccountV2 { data: DataV2, is_mutable: bool },
+    CreateMasterEditionV3 { max_supply: Option<u64> },
+    VerifyCollection,
+    Utilize { number_of_uses: u64 },
+    ApproveUseAuthority { number_of_uses: u64 },
+    RevokeUseAuthority,
+    UnverifyCollection,
+    ApproveCollectionAuthority,
+    RevokeCollectionAuthority,
+    SetAndVerifyCollection,
+    FreezeDelegatedAccount,
+    ThawDelegatedAccount,
+    RemoveCreatorVerification,
+}
+
+/// What a metadata program account holds, told by its first byte.
+#[derive(BorshDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum Key {
+    Uninitialized,
+    EditionV1,
+    MasterEditionV1,
+    ReservationListV1,
+    MetadataV1,
+    ReservationListV2,
+    MasterEditionV2,
+    EditionMarker,
+    UseAuthorityRecord,
+    CollectionAuthorityRecord,
+}
+
+#[derive(BorshDeserialize, Clone, Copy, Debug)]
+pub enum TokenStandard {
+    NonFungible,
+    FungibleAsset,
+    Fungible,
+    NonFungibleEdition,
+}
+
+/// A metadata account. The fields after `is_mutable` were added over time, so older accounts
+/// end before them.
+#[derive(Clone, Debug)]
+pub struct Metadata {
+    pub update_authority: Pubkey,
+    pub mint: Pubkey,
+    pub data: Data,
+    pub primary_sale_happened: bool,
+    pub is_mutable: bool,
+    pub edition_nonce: Option<u8>,
+    pub token_standard: Option<TokenStandard>,
+    pub collection: Option<Collection>,
+    pub uses: Option<Uses>,
+}
+
+impl Metadata {
+    /// Reads a metadata account, key included. Accounts are zero padded past their data.
+    pub fn unpack(data: &[u8]) -> std::io::Result<Self> {
+        let buf = &mut &data[..];
+        Key::deserialize(buf)?;
+
+        Ok(Metadata {
+            update_authority: Pubkey::deserialize(buf)?,
+            mint: Pubkey::deserialize(buf)?,
+            data: Data::deserialize(buf)?,
+            primary_sale_happened: bool::deserialize(buf)?,
+            is_mutable: bool::deserialize(buf)?,
+            edition_nonce: Option::<u8>::deserialize(buf).unwrap_or(None),
+            token_standard: Option::<TokenStandard>::deserialize(buf).unwrap_or(None),
+            collection: Option::<Collection>::deserialize(buf).unwrap_or(None),
+            uses: Option::<Uses>::deserialize(buf).unwrap_or(None),
+        })
+    }
+}
+
+/// A print of a master edition.
+#[derive(BorshDeserialize, Clone, Debug)]
+pub struct Edition {
+    pub key: Key,
+    pub parent: Pubkey,
+    pub edition: u64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug)]
+pub struct MasterEditionV2 {
+    pub key: Key,
+    pub supply: u64,
+    pub max_supply: Option<u64>,
+}
+
+/// Extracts the contents of an instruction into small bits and pieces, or what we would call,
+/// instruction_properties.
+///
+/// The function should return a list of instruction properties extracted from an instruction.
+pub async fn fragment_instruction(
+    // The instruction
+    instruction: Instruction,
+) -> Result<InstructionSet, DecodeError> {
+    let metadata_instruction = match MetadataInstruction::try_from_slice(
+        instruction.data.as_slice()) {
+        Ok(metadata_instruction) => metadata_instruction,
+        Err(err) => {
+            error!("[spi-wrapper/programs/metaplex_token_metadata] Attempt to parse instruction \
+            from program {} failed due to {}.", instruction.program, err);
+
+            return Err(DecodeError::deserialization(
+                &instruction.program, "InvalidInstructionData", &err.to_string()));
+        }
+    };
+
+    let account_properties = instruction.account_properties(
+        account_roles(&metadata_instruction));
+    let (function_name, mut properties): (&str, Vec<InstructionProperty>) =
+        match metadata_instruction {
+            MetadataInstruction::CreateMetadataAccount { data, is_mutable } => {
+                let mut properties = data_properties(&instruction, "data", &data.into());
+                properties.push(instruction.property("", "is_mutable", is_mutable.to_string(),
+                                                     ValueType::Bool));
+                ("create-metadata-account", properties)
+            }
+            MetadataInstruction::CreateMetadataAccountV2 { data, is_mutable } => {
+                let mut properties = data_properties(&instruction, "data", &data);
+                properties.push(instruction.property("", "is_mutable", is_mutable.to_string(),
+                                                     ValueType::Bool));
+                ("create-metadata-account-v2", properties)
+            }
+            MetadataInstruction::UpdateMetadataAccount {
+                data, update_authority, primary_sale_happened,
+            } => {
+                let mut properties = match data {
+                    Some(data) => data_properties(&instruction, "data", &data.into()),
+                    None => vec![],
+                };
+                properties.extend(update_properties(
+                    &instruction, update_authority, primary_sale_happened, None));
+                ("update-metadata-account", properties)
+            }
+            MetadataInstruction::UpdateMetadataAccountV2 {
+                data, update_authority, primary_sale_happened, is_mutable,
+            } => {
+                let mut properties = match data {
+                    Some(data) => data_properties(&instruction, "data", &data),
+                    None => vec![],
+                };
+                properties.extend(update_properties(
+                    &instruction, update_authority, primary_sale_happened, is_mutable));
+                ("update-metadata-account-v2", properties)
+            }
+            MetadataInstruction::DeprecatedCreateMasterEdition { max_supply } => {
+                ("deprecated-create-master-edition",
+                 vec![max_supply_property(&instruction, max_supply)])
+            }
+            MetadataInstruction::CreateMasterEdition { max_supply } => {
+                ("create-master-edition", vec![max_supply_property(&instruction, max_supply)])
+            }
+            MetadataInstruction::CreateMasterEditionV3 { max_supply } => {
+                ("create-master-edition-v3", vec![max_supply_property(&instruction, max_supply)])
+            }
+            MetadataInstruction::DeprecatedMintNewEditionFromMasterEditionViaPrintingToken => {
+                ("deprecated-mint-new-edition-from-master-edition-via-printing-token", vec![])
+            }
+            MetadataInstruction::MintNewEditionFromMasterEditionViaToken { edition } => {
+                ("mint-new-edition-from-master-edition-via-token", vec![
+                    instruction.property("", "edition", edition.to_string(), ValueType::U64),
+                ])
+            }
+            MetadataInstruction::MintNewEditionFromMasterEditionViaVaultProxy { edition } => {
+                ("mint-new-edition-from-master-edition-via-vault-proxy", vec![
+                    instruction.property("", "edition", edition.to_string(), ValueType::U64),
+                ])
+            }
+            MetadataInstruction::UpdatePrimarySaleHappenedViaToken => {
+                ("update-primary-sale-happened-via-token", vec![])
+            }
+            MetadataInstruction::DeprecatedSetReservationList {
+                reservations, total_reservation_spots, offset, total_spot_offset,
+            } => {
+                let mut properties = vec![
+                    instruction.optional_property("", "total_reservation_spots",
+                                                  total_reservation_spots.map(|spots| {
+                                                      spots.to_string()
+                                                  }), ValueType::U64),
+                    instruction.property("", "offset", offset.to_string(), ValueType::U64),
+                    instruction.property("", "total_spot_offset", total_spot_offset.to_string(),
+                                         ValueType::U64),
+                ];
+                for (idx, reservation) in reservations.iter().enumerate() {
+                    let parent_key = path::join(&["reservations", &idx.to_string()]);
+                    properties.extend(vec![
+                        instruction.property(&parent_key, "address",
+                                             reservation.address.to_string(), ValueType::Pubkey),
+                        instruction.property(&parent_key, "spots_remaining",
+                                             reservation.spots_remaining.to_string(),
+                                             ValueType::U64),
+                        instruction.property(&parent_key, "total_spots",
+                                             reservation.total_spots.to_string(), ValueType::U64),
+                    ]);
+                }
+                ("deprecated-set-reservation-list", properties)
+            }
+            MetadataInstruction::DeprecatedCreateReservationList => {
+                ("deprecated-create-reservation-list", vec![])
+            }
+            MetadataInstruction::SignMetadata => ("sign-metadata", vec![]),
+            MetadataInstruction::DeprecatedMintPrintingTokensViaToken { supply } => {
+                ("deprecated-mint-printing-tokens-via-token", vec![
+                    instruction.property("", "supply", supply.to_string(), ValueType::U64),
+                ])
+            }
+            MetadataInstruction::DeprecatedMintPrintingTokens { supply } => {
+                ("deprecated-mint-printing-tokens", vec![
+                    instruction.property("", "supply", supply.to_string(), ValueType::U64),
+                ])
+            }
+            MetadataInstruction::ConvertMasterEditionV1ToV2 => {
+                ("convert-master-edition-v1-to-v2", vec![])
+            }
+            MetadataInstruction::PuffMetadata => ("puff-metadata", vec![]),
+            MetadataInstruction::VerifyCollection => ("verify-collection", vec![]),
+            MetadataInstruction::Utilize { number_of_uses } => {
+                ("utilize", vec![
+                    instruction.property("", "number_of_uses", number_of_uses.to_string(),
+                                         ValueType::U64),
+                ])
+            }
+            MetadataInstruction::ApproveUseAuthority { number_of_uses } => {
+                ("approve-use-authority", vec![
+                    instruction.property("", "number_of_uses", number_of_uses.to_string(),
+                                         ValueType::U64),
+                ])
+            }
+            MetadataInstruction::RevokeUseAuthority => ("revoke-use-authority", vec![]),
+            MetadataInstruction::UnverifyCollection => ("unverify-collection", vec![]),
+            MetadataInstruction::ApproveCollectionAuthority => {
+                ("approve-collection-authority", vec![])
+            }
+            MetadataInstruction::RevokeCollectionAuthority => {
+                ("revoke-collection-authority", vec![])
+            }
+            MetadataInstruction::SetAndVerifyCollection => ("set-and-verify-collection", vec![]),
+            MetadataInstruction::FreezeDelegatedAccount => ("freeze-delegated-account", vec![]),
+            MetadataInstruction::ThawDelegatedAccount => ("thaw-delegated-account", vec![]),
+            MetadataInstruction::RemoveCreatorVerification => {
+                ("remove-creator-verification", vec![])
+            }
+        };
+
+    properties.extend(account_properties);
+    Ok(InstructionSet {
+        function: instruction.function(function_name),
+        properties,
+    })
+}
+
+/// Decodes the data of a metadata, edition or master edition account.
+pub async fn fragment_account(
+    // The record holding the account's data
+    account: AccountRecord,
+) -> Result<InstructionSet, DecodeError> {
+    let key = account.data.first().cloned()
+        .and_then(|key| Key::try_from_slice(&[key]).ok());
+
+    let decoded = match key {
+        Some(Key::MetadataV1) => Metadata::unpack(account.data.as_slice())
+            .map(|metadata| InstructionSet {
+                function: account.function("metadata"),
+                properties: metadata_properties(&account, &metadata),
+            }),
+        Some(Key::EditionV1) => Edition::deserialize(&mut account.data.as_slice())
+            .map(|edition| InstructionSet {
+                function: account.function("edition"),
+                properties: vec![
+                    account.property("", "parent", edition.parent.to_string(), ValueType::Pubkey),
+                    account.property("", "edition", edition.edition.to_string(), ValueType::U64),
+                ],
+            }),
+        // The first master editions share the supplies, ahead of their printing mints.
+        Some(Key::MasterEditionV1) | Some(Key::MasterEditionV2) =>
+            MasterEditionV2::deserialize(&mut account.data.as_slice())
+                .map(|master_edition| InstructionSet {
+                    function: account.function("master-edition"),
+                    properties: vec![
+                        account.property("", "supply", master_edition.supply.to_string(),
+                                         ValueType::U64),
+                        max_supply_property(&account, master_edition.max_supply),
+                    ],
+                }),
+        key => Err(std::io::Error::new(std::io::ErrorKind::InvalidData,
+                                       format!("Unsupported account key {:?}.", key))),
+    };
+
+    decoded.map_err(|err| {
+        error!("[spi-wrapper/programs/metaplex_token_metadata] Attempt to parse account {} \
+        failed due to {}.", account.pubkey, err);
+
+        DecodeError::deserialization(&account.owner, "ParseAccountError", &err.to_string())
+    })
+}
+
+fn metadata_properties(account: &AccountRecord, metadata: &Metadata) -> Vec<InstructionProperty> {
+    let data = DataV2 {
+        collection: metadata.collection.clone(),
+        uses: metadata.uses.clone(),
+        ..metadata.data.clone().into()
+    };
+
+    let mut properties = vec![
+        account.property("", "update_authority", metadata.update_authority.to_string(),
+                         ValueType::Pubkey),
+        account.property("", "mint", metadata.mint.to_string(), ValueType::Pubkey),
+        account.property("", "primary_sale_happened",
+                         metadata.primary_sale_happened.to_string(), ValueType::Bool),
+        account.property("", "is_mutable", metadata.is_mutable.to_string(), ValueType::Bool),
+        account.optional_property("", "edition_nonce",
+                                  metadata.edition_nonce.map(|nonce| nonce.to_string()),
+                                  ValueType::U64),
+        account.optional_property("", "token_standard",
+                                  metadata.token_standard.map(|standard| {
+                                      format!("{:?}", standard)
+                                  }), ValueType::EnumName),
+    ];
+    properties.extend(data_properties(account, "data", &data));
+
+    properties
+}
+
+/// The name, symbol and uri are stored zero padded to a fixed length, the padding is dropped.
+fn data_properties<R: Record>(
+    record: &R,
+    parent_key: &str,
+    data: &DataV2,
+) -> Vec<InstructionProperty> {
+    let mut properties = vec![
+        record.property(parent_key, "name", data.name.trim_end_matches('\0').to_string(),
+                        ValueType::String),
+        record.property(parent_key, "symbol",
+                        data.symbol.trim_end_matches('\0').to_string(), ValueType::String),
+        record.property(parent_key, "uri", data.uri.trim_end_matches('\0').to_string(),
+                        ValueType::String),
+        record.property(parent_key, "seller_fee_basis_points",
+                        data.seller_fee_basis_points.to_string(), ValueType::U64),
+    ];
+
+    for (idx, creator) in data.creators.iter().flatten().enumerate() {
+        let creator_key = path::join(&[parent_key, "creators", &idx.to_string()]);
+        properties.extend(vec![
+            record.property(&creator_key, "address", creator.address.to_string(),
+                            ValueType::Pubkey),
+            record.property(&creator_key, "verified", creator.verified.to_string(),
+                            ValueType::Bool),
+            record.property(&creator_key, "share", creator.share.to_string(),
+                            ValueType::U64),
+        ]);
+    }
+
+    if let Some(collection) = &data.collection {
+        let collection_key = path::join(&[parent_key, "collection"]);
+        properties.extend(vec![
+            record.property(&collection_key, "verified", collection.verified.to_string(),
+                            ValueType::Bool),
+            record.property(&collection_key, "key", collection.key.to_string(),
+                            ValueType::Pubkey),
+        ]);
+    }
+
+    if let Some(uses) = &data.uses {
+        let uses_key = path::join(&[parent_key, "uses"]);
+        properties.extend(vec![
+            record.property(&uses_key, "use_method", format!("{:?}", uses.use_method),
+                            ValueType::EnumName),
+            record.property(&uses_key, "remaining", uses.remaining.to_string(),
+                            ValueType::U64),
+            record.property(&uses_key, "total", uses.total.to_string(), ValueType::U64),
+        ]);
+    }
+
+    properties
+}
+
+/// The fields an update sets. Fields left unset are left out.
+fn update_properties(
+    instruction: &Instruction,
+    update_authority: Option<Pubkey>,
+    primary_sale_happened: Option<bool>,
+    is_mutable: Option<bool>,
+) -> Vec<InstructionProperty> {
+    let mut properties = vec![];
+    if let Some(update_authority) = update_authority {
+        properties.push(instruction.property("", "update_authority",
+                                             update_authority.to_string(), ValueType::Pubkey));
+    }
+    if let Some(primary_sale_happened) = primary_sale_happened {
+        properties.push(instruction.property("", "primary_sale_happened",
+                                             primary_sale_happened.to_string(), ValueType::Bool));
+    }
+    if let Some(is_mutable) = is_mutable {
+        properties.push(instruction.property("", "is_mutable", is_mutable.to_string(),
+                                             ValueType::Bool));
+    }
+
+    properties
+}
+
+/// An unlimited supply has no maximum.
+fn max_supply_property<R: Record>(record: &R, max_supply: Option<u64>) -> InstructionProperty {
+    record.optional_property("", "max_supply", max_supply.map(|max_supply| max_supply.to_string()),
+                             ValueType::U64)
+}
+
+/// The roles of the accounts each instruction expects, in order.
+fn account_roles(instruction: &MetadataInstruction) -> &'static [&'static str] {
+    match instruction {
+        MetadataInstruction::CreateMetadataAccount { .. }
+        | MetadataInstruction::CreateMetadataAccountV2 { .. } => &[
+            "metadata", "mint", "mint_authority", "payer", "update_authority", "system_program",
+            "rent_sysvar",
+        ],
+        MetadataInstruction::UpdateMetadataAccount { .. }
+        | MetadataInstruction::UpdateMetadataAccountV2 { .. } => &[
+            "metadata", "update_authority",
+        ],
+        MetadataInstruction::DeprecatedCreateMasterEdition { .. } => &[
+            "edition", "mint", "printing_mint", "one_time_printing_authorization_mint",
+            "update_authority", "printing_mint_authority", "mint_authority", "metadata", "payer",
+            "token_program", "system_program", "rent_sysvar",
+            "one_time_printing_authorization_mint_authority",
+        ],
+        MetadataInstruction::DeprecatedMintNewEditionFromMasterEditionViaPrintingToken => &[
+            "metadata", "edition", "master_edition", "mint", "mint_authority", "printing_mint",
+            "master_token_account", "edition_marker", "burn_authority", "payer",
+            "master_update_authority", "master_metadata", "token_program", "system_program",
+            "rent_sysvar", "reservation_list",
+        ],
+        MetadataInstruction::UpdatePrimarySaleHappenedViaToken => &[
+            "metadata", "owner", "token_account",
+        ],
+        MetadataInstruction::DeprecatedSetReservationList { .. } => &[
+            "master_edition", "reservation_list", "resource",
+        ],
+        MetadataInstruction::DeprecatedCreateReservationList => &[
+            "reservation_list", "payer", "update_authority", "master_edition", "resource",
+            "metadata", "system_program", "rent_sysvar",
+        ],
+        MetadataInstruction::SignMetadata
+        | MetadataInstruction::RemoveCreatorVerification => &["metadata", "creator"],
+        MetadataInstruction::DeprecatedMintPrintingTokensViaToken { .. } => &[
+            "destination", "token", "one_time_printing_authorization_mint", "printing_mint",
+            "burn_authority", "metadata", "master_edition", "token_program", "rent_sysvar",
+        ],
+        MetadataInstruction::DeprecatedMintPrintingTokens { .. } => &[
+            "destination", "printing_mint", "update_authority", "metadata", "master_edition",
+            "token_program", "rent_sysvar",
+        ],
+        MetadataInstruction::CreateMasterEdition { .. }
+        | MetadataInstruction::CreateMasterEditionV3 { .. } => &[
+            "edition", "mint", "update_authority", "mint_authority", "payer", "metadata",
+            "token_program", "system_program", "rent_sysvar",
+        ],
+        MetadataInstruction::MintNewEditionFromMasterEditionViaToken { .. } => &[
+            "new_metadata", "new_edition", "master_edition", "new_mint", "edition_mark_pda",
+            "new_mint_authority", "payer", "token_account_owner", "token_account",
+            "new_metadata_update_authority", "metadata", "token_program", "system_program",
+            "rent_sysvar",
+        ],
+        MetadataInstruction::ConvertMasterEditionV1ToV2 => &[
+            "master_edition", "one_time_auth_mint", "printing_mint",
+        ],
+        MetadataInstruction::MintNewEditionFromMasterEditionViaVaultProxy { .. } => &[
+            "new_metadata", "new_edition", "master_edition", "new_mint", "edition_mark_pda",
+            "new_mint_authority", "payer", "vault_authority", "safety_deposit_store",
+            "safety_deposit_box", "vault", "new_metadata_update_authority", "metadata",
+            "token_program", "token_vault_program", "system_program", "rent_sysvar",
+        ],
+        MetadataInstruction::PuffMetadata => &["metadata"],
+        MetadataInstruction::VerifyCollection => &[
+            "metadata", "collection_authority", "payer", "collection_mint", "collection",
+            "collection_master_edition", "collection_authority_record",
+        ],
+        MetadataInstruction::Utilize { .. } => &[
+            "metadata", "token_account", "mint", "use_authority", "owner", "token_program",
+            "ata_program", "system_program", "rent_sysvar", "use_authority_record", "burner",
+        ],
+        MetadataInstruction::ApproveUseAuthority { .. } => &[
+            "use_authority_record", "owner", "payer", "user", "owner_token_account", "metadata",
+            "mint", "burner", "token_program", "system_program", "rent_sysvar",
+        ],
+        MetadataInstruction::RevokeUseAuthority => &[
+            "use_authority_record", "owner", "user", "owner_token_account", "mint", "metadata",
+            "token_program", "system_program", "rent_sysvar",
+        ],
+        MetadataInstruction::UnverifyCollection => &[
+            "metadata", "collection_authority", "collection_mint", "collection",
+            "collection_master_edition", "collection_authority_record",
+        ],
+        MetadataInstruction::ApproveCollectionAuthority => &[
+            "collection_authority_record", "new_collection_authority", "update_authority",
+            "payer", "metadata", "mint", "system_program", "rent_sysvar",
+        ],
+        MetadataInstruction::RevokeCollectionAuthority => &[
+            "collection_authority_record", "delegate_authority", "revoke_authority", "metadata",
+            "mint",
+        ],
+        MetadataInstruction::SetAndVerifyCollection => &[
+            "metadata", "collection_authority", "payer", "update_authority", "collection_mint",
+            "collection", "collection_master_edition", "collection_authority_record",
+        ],
+        MetadataInstruction::FreezeDelegatedAccount
+        | MetadataInstruction::ThawDelegatedAccount => &[
+            "delegate", "token_account", "edition", "mint", "token_program",
+        ],
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{decode, decode_account, instruction};
+
+    #[test]
+    fn metadata_accounts_are_decoded() {
+        let string = |value: &str, len: usize| {
+            let mut bytes = (len as u32).to_le_bytes().to_vec();
+            bytes.extend(value.as_bytes());
+            bytes.resize(4 + len, 0);
+            bytes
+        };
+        let creator = Pubkey::new_unique();
+
+        // An older metadata account, ending after its edition nonce and zero padded.
+        let mut metadata = vec![4];
+        metadata.extend(Pubkey::new_unique().as_ref());
+        metadata.extend(Pubkey::new_unique().as_ref());
+        metadata.extend(string("Degen Ape #1", 32));
+        metadata.extend(string("DAPE", 10));
+        metadata.extend(string("https://arweave.net/ape", 200));
+        metadata.extend(&500u16.to_le_bytes());
+        metadata.push(1);
+        metadata.extend(&1u32.to_le_bytes());
+        metadata.extend(creator.as_ref());
+        metadata.extend(&[1, 100]);
+        metadata.extend(&[1, 1, 1, 254]);
+        metadata.resize(679, 0);
+
+        let metadata = decode_account(PROGRAM_ADDRESS, metadata).to_json();
+        assert_eq!(metadata["function_name"], "metadata");
+        assert_eq!(metadata["properties"]["data"]["name"], "Degen Ape #1");
+        assert_eq!(metadata["properties"]["data"]["seller_fee_basis_points"], 500);
+        assert_eq!(metadata["properties"]["data"]["creators"][0]["address"], creator.to_string());
+        assert_eq!(metadata["properties"]["data"]["creators"][0]["share"], 100);
+        assert_eq!(metadata["properties"]["primary_sale_happened"], true);
+        assert_eq!(metadata["properties"]["edition_nonce"], 254);
+        assert!(metadata["properties"]["token_standard"].is_null());
+    }
+
+    #[test]
+    fn utilize_names_its_accounts() {
+        let accounts = ["metadata", "token-account", "mint", "use-authority", "owner",
+                        "token-program", "ata-program", "system-program", "rent"];
+        let mut data = vec![19];
+        data.extend(&3u64.to_le_bytes());
+
+        let utilize = decode(instruction(PROGRAM_ADDRESS, &accounts, data)).to_json();
+        assert_eq!(utilize["function_name"], "utilize");
+        assert_eq!(utilize["properties"]["number_of_uses"], 3);
+        assert_eq!(utilize["properties"]["accounts"]["metadata"], "metadata");
+        assert_eq!(utilize["properties"]["accounts"]["use_authority"], "use-authority");
+        assert_eq!(utilize["properties"]["accounts"]["rent_sysvar"], "rent");
+        // The use authority record and burner are only passed along with a delegate.
+        assert!(utilize["properties"]["accounts"]["use_authority_record"].is_null());
+    }
+}
@@ -1,15 +1,24 @@
 pub mod bpf_loader;
 pub mod bpf_loader_upgradeable;
+mod lending_account;
+pub mod metaplex_token_metadata;
+pub mod native_address_lookup_table;
 pub mod native_associated_token_account;
+pub mod native_compute_budget;
 pub mod native_config;
+pub mod native_ed25519;
 pub mod native_loader;
+pub mod native_memo;
 pub mod native_secp256k1;
 pub mod native_stake;
+pub mod native_stake_pool;
 pub mod native_system;
 pub mod native_token;
 pub mod native_token_swap;
 pub mod native_token_lending;
 pub mod native_vote;
+pub mod pyth_oracle;
 pub mod serum_market;
+mod signature_check;
 pub mod solend;
 pub mod solend_token_lending;
\ No newline at end of file
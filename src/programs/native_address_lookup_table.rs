@@ -0,0 +1,202 @@
+use bincode::deserialize;
+use serde::Deserialize;
+use solana_sdk::pubkey::Pubkey;
+use tracing::error;
+
+use crate::error::DecodeError;
+use crate::{AccountRecord, Instruction, InstructionSet, Record, ValueType};
+
+pub const PROGRAM_ADDRESS: &str = "AddressLookupTab1e1111111111111111111111111";
+
+/// The size of the state preceding the addresses of a lookup table account.
+pub const LOOKUP_TABLE_META_SIZE: usize = 56;
+
+/// The instructions of the address lookup table program, which this sdk predates. They are
+/// bincode encoded, like `SystemInstruction`.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub enum ProgramInstruction {
+    CreateLookupTable { recent_slot: u64, bump_seed: u8 },
+    FreezeLookupTable,
+    ExtendLookupTable { new_addresses: Vec<Pubkey> },
+    DeactivateLookupTable,
+    CloseLookupTable,
+}
+
+/// The state of a lookup table account, bincode encoded as well.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub enum ProgramState {
+    Uninitialized,
+    LookupTable(LookupTableMeta),
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct LookupTableMeta {
+    // The slot the table was deactivated at, `u64::MAX` while it is active.
+    pub deactivation_slot: u64,
+    // The slot the table was last extended at.
+    pub last_extended_slot: u64,
+    // Where the addresses added in `last_extended_slot` start.
+    pub last_extended_slot_start_index: u8,
+    // The authority allowed to extend, deactivate and close the table, none once frozen.
+    pub authority: Option<Pubkey>,
+    pub _padding: u16,
+}
+
+/// Extracts the contents of an instruction into small bits and pieces, or what we would call,
+/// instruction_properties.
+///
+/// The function should return a list of instruction properties extracted from an instruction.
+pub async fn fragment_instruction(
+    // The instruction
+    instruction: Instruction,
+) -> Result<InstructionSet, DecodeError> {
+    match deserialize::<ProgramInstruction>(instruction.data.as_slice()) {
+        Ok(program_instruction) => {
+            let account_properties = instruction.account_properties(
+                account_roles(&program_instruction));
+            let mut instruction_set = match program_instruction {
+                ProgramInstruction::CreateLookupTable { recent_slot, bump_seed } => InstructionSet {
+                    function: instruction.function("create-lookup-table"),
+                    properties: vec![
+                        instruction.property("", "recent_slot", recent_slot.to_string(),
+                                             ValueType::U64),
+                        instruction.property("", "bump_seed", bump_seed.to_string(),
+                                             ValueType::U64),
+                    ],
+                },
+                ProgramInstruction::FreezeLookupTable => InstructionSet {
+                    function: instruction.function("freeze-lookup-table"),
+                    properties: vec![],
+                },
+                ProgramInstruction::ExtendLookupTable { new_addresses } => InstructionSet {
+                    function: instruction.function("extend-lookup-table"),
+                    properties: new_addresses.iter()
+                        .enumerate()
+                        .map(|(idx, address)| instruction.property(
+                            "new_addresses", &idx.to_string(), address.to_string(),
+                            ValueType::Pubkey))
+                        .collect(),
+                },
+                ProgramInstruction::DeactivateLookupTable => InstructionSet {
+                    function: instruction.function("deactivate-lookup-table"),
+                    properties: vec![],
+                },
+                ProgramInstruction::CloseLookupTable => InstructionSet {
+                    function: instruction.function("close-lookup-table"),
+                    properties: vec![],
+                },
+            };
+
+            instruction_set.properties.extend(account_properties);
+            Ok(instruction_set)
+        }
+        Err(err) => {
+            error!("[spi-wrapper/programs/native_address_lookup_table] Attempt to parse \
+            instruction from program {} failed due to {}.", instruction.program, err);
+
+            Err(DecodeError::from_bincode_error(&instruction.program, &err))
+        }
+    }
+}
+
+/// Decodes the data of a lookup table account: its state followed by the addresses it holds.
+pub async fn fragment_account(
+    // The record holding the account's data
+    account: AccountRecord,
+) -> Result<InstructionSet, DecodeError> {
+    let meta_data = &account.data[..account.data.len().min(LOOKUP_TABLE_META_SIZE)];
+    match deserialize::<ProgramState>(meta_data) {
+        Ok(ProgramState::Uninitialized) => Ok(InstructionSet {
+            function: account.function("lookup-table"),
+            properties: vec![
+                account.property("", "state", "Uninitialized".to_string(), ValueType::EnumName),
+            ],
+        }),
+        Ok(ProgramState::LookupTable(meta)) => {
+            let mut properties = vec![
+                account.property("", "state", "LookupTable".to_string(), ValueType::EnumName),
+                account.property("", "deactivation_slot", meta.deactivation_slot.to_string(),
+                                 ValueType::U64),
+                account.property("", "is_active",
+                                 (meta.deactivation_slot == u64::MAX).to_string(),
+                                 ValueType::Bool),
+                account.property("", "last_extended_slot", meta.last_extended_slot.to_string(),
+                                 ValueType::U64),
+                account.property("", "last_extended_slot_start_index",
+                                 meta.last_extended_slot_start_index.to_string(), ValueType::U64),
+                account.optional_property("", "authority",
+                                          meta.authority.map(|authority| authority.to_string()),
+                                          ValueType::Pubkey),
+            ];
+
+            let addresses = account.data.get(LOOKUP_TABLE_META_SIZE..).unwrap_or_default();
+            for (idx, address) in addresses.chunks_exact(32).enumerate() {
+                properties.push(account.property(
+                    "addresses", &idx.to_string(),
+                    Pubkey::new(address).to_string(), ValueType::Pubkey));
+            }
+
+            Ok(InstructionSet {
+                function: account.function("lookup-table"),
+                properties,
+            })
+        }
+        Err(err) => {
+            error!("[spi-wrapper/programs/native_address_lookup_table] Attempt to parse account \
+            {} failed due to {}.", account.pubkey, err);
+
+            Err(DecodeError::from_bincode_error(&account.owner, &err))
+        }
+    }
+}
+
+/// The roles of the accounts each instruction expects, in order.
+fn account_roles(instruction: &ProgramInstruction) -> &'static [&'static str] {
+    match instruction {
+        ProgramInstruction::CreateLookupTable { .. } => &[
+            "lookup_table", "authority", "payer", "system_program",
+        ],
+        ProgramInstruction::FreezeLookupTable => &["lookup_table", "authority"],
+        ProgramInstruction::ExtendLookupTable { .. } => &[
+            "lookup_table", "authority", "payer", "system_program",
+        ],
+        ProgramInstruction::DeactivateLookupTable => &["lookup_table", "authority"],
+        ProgramInstruction::CloseLookupTable => &["lookup_table", "authority", "recipient"],
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{decode, decode_account, instruction};
+
+    #[test]
+    fn lookup_tables_are_decoded() {
+        let addresses = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+
+        let mut extend = 2u32.to_le_bytes().to_vec();
+        extend.extend(&(addresses.len() as u64).to_le_bytes());
+        addresses.iter().for_each(|address| extend.extend(address.as_ref()));
+
+        let extend = decode(instruction(PROGRAM_ADDRESS, &["table", "authority"], extend))
+            .to_json();
+        assert_eq!(extend["function_name"], "extend-lookup-table");
+        assert_eq!(extend["properties"]["new_addresses"][1], addresses[1].to_string());
+        assert_eq!(extend["properties"]["accounts"]["lookup_table"], "table");
+
+        // An active table, with an authority, holding the same addresses.
+        let mut table = 1u32.to_le_bytes().to_vec();
+        table.extend(&u64::MAX.to_le_bytes());
+        table.extend(&7u64.to_le_bytes());
+        table.push(0);
+        table.push(1);
+        table.extend(Pubkey::new_unique().as_ref());
+        table.resize(56, 0);
+        addresses.iter().for_each(|address| table.extend(address.as_ref()));
+
+        let table = decode_account(PROGRAM_ADDRESS, table).to_json();
+        assert_eq!(table["properties"]["is_active"], true);
+        assert_eq!(table["properties"]["last_extended_slot"], 7);
+        assert_eq!(table["properties"]["addresses"][0], addresses[0].to_string());
+    }
+}
@@ -1,7 +1,11 @@
-use bincode::deserialize;
+use std::str::FromStr;
+
+use solana_program::pubkey::Pubkey;
 use tracing::error;
 
-use crate::{InstructionProperty, Instruction, InstructionSet, InstructionFunction};
+use crate::error::DecodeError;
+use crate::programs::native_token;
+use crate::{Instruction, InstructionSet, Record, ValueType};
 
 pub const PROGRAM_ADDRESS: &str = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL";
 
@@ -12,108 +16,139 @@ pub const PROGRAM_ADDRESS: &str = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"
 pub async fn fragment_instruction(
     // The instruction
     instruction: Instruction,
-) -> Option<InstructionSet> {
-    let atadr = deserialize::<solana_program::instruction::Instruction>(
-        &instruction.data.as_slice());
-
-    return match atadr {
-        Ok(ref ati) => {
-            let associated_token_instruction = ati.clone();
-            // Create an associated token account for the given wallet address and token mint
-            //
-            // Accounts expected by this instruction:
-            //
-            //   0. `[writeable,signer]` Funding account (must be a system account)
-            //   1. `[writeable]` Associated token account address to be created
-            //   2. `[]` Wallet address for the new associated token account
-            //   3. `[]` The token mint for the new associated token account
-            //   4. `[]` System program
-            //   5. `[]` SPL Token program
-            //   6. `[]` Rent sysvar
-            let account_sets: Vec<Vec<InstructionProperty>> = associated_token_instruction.accounts
-                .into_iter().map(|am| {
-                vec![
-                    InstructionProperty {
-                        tx_instruction_id: instruction.tx_instruction_id.clone(),
-                        transaction_hash: instruction.transaction_hash.clone(),
-                        parent_index: instruction.parent_index.clone(),
-                        key: "pubkey".to_string(),
-                        value: am.pubkey.to_string(),
-                        parent_key: "".to_string(),
-                        timestamp: instruction.timestamp.clone(),
-                    },
-                    InstructionProperty {
-                        tx_instruction_id: instruction.tx_instruction_id.clone(),
-                        transaction_hash: instruction.transaction_hash.clone(),
-                        parent_index: instruction.parent_index.clone(),
-                        key: "is_signer".to_string(),
-                        value: if am.is_signer {
-                            "1".to_string()
-                        } else {
-                            "0".to_string()
-                        },
-                        parent_key: "".to_string(),
-                        timestamp: instruction.timestamp.clone(),
-                    },
-                    InstructionProperty {
-                        tx_instruction_id: instruction.tx_instruction_id.clone(),
-                        transaction_hash: instruction.transaction_hash.clone(),
-                        parent_index: instruction.parent_index.clone(),
-                        key: "is_writable".to_string(),
-                        value: if am.is_writable {
-                            "1".to_string()
-                        } else {
-                            "0".to_string()
-                        },
-                        parent_key: "".to_string(),
-                        timestamp: instruction.timestamp.clone(),
-                    }
-                ]
-            }).collect();
-
-            let mut properties = vec![
-                InstructionProperty {
-                    tx_instruction_id: instruction.tx_instruction_id.clone(),
-                    transaction_hash: instruction.transaction_hash.clone(),
-                    parent_index: instruction.parent_index.clone(),
-                    key: "data".to_string(),
-                    value: bs58::encode(associated_token_instruction.data).into_string(),
-                    parent_key: "".to_string(),
-                    timestamp: instruction.timestamp.clone(),
-                },
-                InstructionProperty {
-                    tx_instruction_id: instruction.tx_instruction_id.clone(),
-                    transaction_hash: instruction.transaction_hash.clone(),
-                    parent_index: instruction.parent_index.clone(),
-                    key: "program_id".to_string(),
-                    value: associated_token_instruction.program_id.to_string(),
-                    parent_key: "".to_string(),
-                    timestamp: instruction.timestamp.clone(),
-                }
-            ];
-
-            for ac in account_sets {
-                properties.extend(ac);
-            }
-
-            Some(InstructionSet {
-                function: InstructionFunction {
-                    tx_instruction_id: instruction.tx_instruction_id.clone(),
-                    transaction_hash: instruction.transaction_hash.clone(),
-                    parent_index: instruction.parent_index.clone(),
-                    program: instruction.program.clone(),
-                    function_name: "".to_string(),
-                    timestamp: instruction.timestamp
-                },
-                properties
-            })
-        }
-        Err(err) => {
-            // If the instruction parsing is failing, bail out
-            error!("[spi-wrapper/bpf_loader] Attempt to parse instruction from program {} failed due to \
-        {}.", instruction.program, err);
+) -> Result<InstructionSet, DecodeError> {
+    // Create used to carry no data at all, later versions of the program tag it with 0 and add
+    // an idempotent variant sharing its accounts.
+    let function_name = match instruction.data.as_slice() {
+        [] | [0] => "create",
+        [1] => "create-idempotent",
+        _ => {
+            error!("[spi-wrapper/programs/native_associated_token_account] Attempt to parse \
+            instruction from program {} failed due to unrecognised data.", instruction.program);
 
-            None
+            return Err(DecodeError::deserialization(
+                &instruction.program,
+                "InvalidInstructionData",
+                "The instruction is not a create instruction.",
+            ));
         }
+    };
+
+    // Create an associated token account for the given wallet address and token mint
+    //
+    // Accounts expected by this instruction:
+    //
+    //   0. `[writeable,signer]` Funding account (must be a system account)
+    //   1. `[writeable]` Associated token account address to be created
+    //   2. `[]` Wallet address for the new associated token account
+    //   3. `[]` The token mint for the new associated token account
+    //   4. `[]` System program
+    //   5. `[]` SPL Token program
+    //   6. `[]` Rent sysvar
+    if instruction.accounts.len() < 4 {
+        error!("[spi-wrapper/programs/native_associated_token_account] Attempt to parse \
+        instruction from program {} failed due to missing accounts.", instruction.program);
+
+        return Err(DecodeError::deserialization(
+            &instruction.program,
+            "NotEnoughAccountKeys",
+            "The instruction is missing the associated account, wallet or mint.",
+        ));
+    }
+
+    // The token program passed in is not trusted, a spoofed create could pass its own.
+    let expected_address = associated_token_address(&instruction.accounts[2],
+                                                    &instruction.accounts[3]);
+    // Whether the account created is the one derived from the wallet and mint, which flags
+    // spoofed or malformed creates.
+    let is_valid = expected_address.as_ref() == Some(&instruction.accounts[1]);
+
+    let mut properties = vec![
+        instruction.optional_property("", "expected_associated_account", expected_address,
+                                      ValueType::Pubkey),
+        instruction.property("", "is_associated_account_valid", is_valid.to_string(),
+                             ValueType::Bool),
+    ];
+    properties.extend(instruction.account_properties(&[
+        "funder", "associated_account", "wallet", "mint", "system_program",
+        "token_program", "rent_sysvar",
+    ]));
+
+    Ok(InstructionSet {
+        function: instruction.function(function_name),
+        properties,
+    })
+}
+
+/// The address of the associated token account of a wallet for a mint, as derived by
+/// `get_associated_token_address`, with the SPL token program. `None` if one of the addresses
+/// given is not a valid pubkey.
+fn associated_token_address(wallet: &str, mint: &str) -> Option<String> {
+    let wallet = Pubkey::from_str(wallet).ok()?;
+    let mint = Pubkey::from_str(mint).ok()?;
+    let token_program = Pubkey::from_str(native_token::PROGRAM_ADDRESS).ok()?;
+    let program_id = Pubkey::from_str(PROGRAM_ADDRESS).ok()?;
+
+    let (address, _) = Pubkey::find_program_address(
+        &[wallet.as_ref(), token_program.as_ref(), mint.as_ref()],
+        &program_id,
+    );
+
+    Some(address.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{block_on, instruction};
+    use crate::{process, InstructionOutcome};
+
+    #[test]
+    fn associated_token_accounts_are_checked_against_their_derivation() {
+        let token_program = native_token::PROGRAM_ADDRESS;
+        let wallet = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let (associated_account, _) = Pubkey::find_program_address(
+            &[wallet.as_ref(), Pubkey::from_str(token_program).unwrap().as_ref(), mint.as_ref()],
+            &Pubkey::from_str(PROGRAM_ADDRESS).unwrap(),
+        );
+
+        // A create passing its own token program, along with the address derived from it.
+        let spoofed_program = Pubkey::new_unique();
+        let (spoofed_account, _) = Pubkey::find_program_address(
+            &[wallet.as_ref(), spoofed_program.as_ref(), mint.as_ref()],
+            &Pubkey::from_str(PROGRAM_ADDRESS).unwrap(),
+        );
+
+        let (wallet, mint) = (wallet.to_string(), mint.to_string());
+        let create = |associated_account: &str, token_program: &str| {
+            instruction(PROGRAM_ADDRESS, &[
+                &wallet,
+                associated_account,
+                &wallet,
+                &mint,
+                "11111111111111111111111111111111",
+                token_program,
+                "SysvarRent111111111111111111111111111111111",
+            ], vec![])
+        };
+
+        let outcomes = block_on(process(vec![
+            create(&associated_account.to_string(), token_program),
+            create(&Pubkey::new_unique().to_string(), token_program),
+            create(&spoofed_account.to_string(), &spoofed_program.to_string()),
+        ], None));
+
+        let is_valid = |outcome: &InstructionOutcome| {
+            let set = outcome.clone().instruction_set().unwrap();
+            assert_eq!(set.function.function_name, "create");
+            set.properties.iter()
+                .find(|property| property.key == "is_associated_account_valid")
+                .map(|property| property.value.clone())
+                .unwrap()
+        };
+        assert_eq!(is_valid(&outcomes[0]), "true");
+        assert_eq!(is_valid(&outcomes[1]), "false");
+        assert_eq!(is_valid(&outcomes[2]), "false");
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,149 @@
+use std::convert::TryInto;
+
+use tracing::error;
+
+use crate::error::DecodeError;
+use crate::{Instruction, InstructionProperty, InstructionSet, ValueType};
+
+pub const PROGRAM_ADDRESS: &str = "ComputeBudget111111111111111111111111111111";
+
+/// The instructions of the compute budget program. They are Borsh encoded: a one byte tag
+/// followed by the little endian values of the variant.
+#[derive(Debug, PartialEq)]
+pub enum ComputeBudgetInstruction {
+    // Deprecated, requests a transaction-wide compute unit limit along with a fee in lamports.
+    RequestUnits { units: u32, additional_fee: u32 },
+    // Requests a heap frame of the given size in bytes for every program of the transaction.
+    RequestHeapFrame(u32),
+    // Sets the transaction-wide compute unit limit.
+    SetComputeUnitLimit(u32),
+    // Sets the price of a compute unit in micro-lamports, the priority fee.
+    SetComputeUnitPrice(u64),
+    // Sets the limit on the size of the accounts the transaction loads, in bytes.
+    SetLoadedAccountsDataSizeLimit(u32),
+}
+
+impl ComputeBudgetInstruction {
+    pub fn unpack(data: &[u8]) -> Option<Self> {
+        let (tag, rest) = data.split_first()?;
+        let u32_at = |offset: usize| -> Option<u32> {
+            Some(u32::from_le_bytes(rest.get(offset..offset + 4)?.try_into().ok()?))
+        };
+
+        let (instruction, len) = match tag {
+            0 => (ComputeBudgetInstruction::RequestUnits {
+                units: u32_at(0)?,
+                additional_fee: u32_at(4)?,
+            }, 8),
+            1 => (ComputeBudgetInstruction::RequestHeapFrame(u32_at(0)?), 4),
+            2 => (ComputeBudgetInstruction::SetComputeUnitLimit(u32_at(0)?), 4),
+            3 => (ComputeBudgetInstruction::SetComputeUnitPrice(
+                u64::from_le_bytes(rest.get(0..8)?.try_into().ok()?)), 8),
+            4 => (ComputeBudgetInstruction::SetLoadedAccountsDataSizeLimit(u32_at(0)?), 4),
+            _ => return None,
+        };
+
+        // Borsh rejects trailing bytes.
+        if rest.len() != len {
+            return None;
+        }
+
+        Some(instruction)
+    }
+}
+
+/// Extracts the contents of an instruction into small bits and pieces, or what we would call,
+/// instruction_properties.
+///
+/// The function should return a list of instruction properties extracted from an instruction.
+pub async fn fragment_instruction(
+    // The instruction
+    instruction: Instruction,
+) -> Result<InstructionSet, DecodeError> {
+    let (function_name, properties): (&str, Vec<InstructionProperty>) =
+        match ComputeBudgetInstruction::unpack(instruction.data.as_slice()) {
+            Some(ComputeBudgetInstruction::RequestUnits { units, additional_fee }) => {
+                ("request-units", vec![
+                    instruction.property("", "units", units.to_string(), ValueType::U64),
+                    instruction.property("", "additional_fee", additional_fee.to_string(),
+                                         ValueType::U64),
+                ])
+            }
+            Some(ComputeBudgetInstruction::RequestHeapFrame(bytes)) => {
+                ("request-heap-frame", vec![
+                    instruction.property("", "bytes", bytes.to_string(), ValueType::U64),
+                ])
+            }
+            Some(ComputeBudgetInstruction::SetComputeUnitLimit(units)) => {
+                ("set-compute-unit-limit", vec![
+                    instruction.property("", "units", units.to_string(), ValueType::U64),
+                ])
+            }
+            Some(ComputeBudgetInstruction::SetComputeUnitPrice(micro_lamports)) => {
+                ("set-compute-unit-price", vec![
+                    instruction.property("", "micro_lamports", micro_lamports.to_string(),
+                                         ValueType::U64),
+                ])
+            }
+            Some(ComputeBudgetInstruction::SetLoadedAccountsDataSizeLimit(bytes)) => {
+                ("set-loaded-accounts-data-size-limit", vec![
+                    instruction.property("", "bytes", bytes.to_string(), ValueType::U64),
+                ])
+            }
+            None => {
+                error!("[spi-wrapper/programs/native_compute_budget] Attempt to parse instruction \
+                from program {} failed due to unrecognised data.", instruction.program);
+
+                return Err(DecodeError::deserialization(
+                    &instruction.program,
+                    "InvalidInstructionData",
+                    "The instruction is not a compute budget instruction.",
+                ));
+            }
+        };
+
+    Ok(InstructionSet {
+        function: instruction.function(function_name),
+        properties,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{decode, instruction};
+
+    #[test]
+    fn compute_budget_instructions_are_unpacked() {
+        let mut price = vec![3];
+        price.extend(&25_000u64.to_le_bytes());
+        assert_eq!(ComputeBudgetInstruction::unpack(&price),
+                   Some(ComputeBudgetInstruction::SetComputeUnitPrice(25_000)));
+
+        let mut units = vec![0];
+        units.extend(&200_000u32.to_le_bytes());
+        units.extend(&5_000u32.to_le_bytes());
+        assert_eq!(ComputeBudgetInstruction::unpack(&units),
+                   Some(ComputeBudgetInstruction::RequestUnits {
+                       units: 200_000,
+                       additional_fee: 5_000,
+                   }));
+
+        assert_eq!(ComputeBudgetInstruction::unpack(&[2, 1, 0]), None);
+        assert_eq!(ComputeBudgetInstruction::unpack(&[]), None);
+    }
+
+    #[test]
+    fn priority_fees_are_decoded() {
+        let mut data = vec![3];
+        data.extend(&25_000u64.to_le_bytes());
+
+        let price = decode(instruction(PROGRAM_ADDRESS, &[], data)).to_json();
+        assert_eq!(price["function_name"], "set-compute-unit-price");
+        assert_eq!(price["properties"]["micro_lamports"], 25_000);
+
+        let limit = decode(instruction(PROGRAM_ADDRESS, &[], vec![2, 64, 13, 3, 0])).to_json();
+        assert_eq!(limit["function_name"], "set-compute-unit-limit");
+        assert_eq!(limit["properties"]["units"], 200_000);
+    }
+}
@@ -1,9 +1,17 @@
+use std::str::FromStr;
+
+use serde_json::Value;
+use solana_account_decoder::parse_config::{parse_config, ConfigAccountType};
 use solana_config_program::ConfigKeys;
-use solana_program::instruction::InstructionError;
+use solana_sdk::pubkey::Pubkey;
 use solana_sdk::program_utils::limited_deserialize;
 use tracing::error;
 
-use crate::{InstructionProperty, Instruction, InstructionSet, InstructionFunction};
+use crate::error::DecodeError;
+use crate::path;
+use crate::{
+    AccountRecord, InstructionProperty, Instruction, InstructionSet, InstructionFunction, ValueType,
+};
 
 pub const PROGRAM_ADDRESS: &str = "Config1111111111111111111111111111111111111";
 
@@ -14,7 +22,7 @@ pub const PROGRAM_ADDRESS: &str = "Config1111111111111111111111111111111111111";
 pub async fn fragment_instruction(
     // The instruction
     instruction: Instruction,
-) -> Option<InstructionSet> {
+) -> Result<InstructionSet, DecodeError> {
     let key_list_result = limited_deserialize::<ConfigKeys>(
         instruction.data.as_slice());
 
@@ -30,6 +38,8 @@ pub async fn fragment_instruction(
                     program: instruction.program.clone(),
                     function_name: "".to_string(),
                     timestamp: instruction.timestamp.clone(),
+                    slot: instruction.slot.clone(),
+                    block_time: instruction.block_time.clone(),
                 },
                 properties: vec![],
             };
@@ -40,27 +50,31 @@ pub async fn fragment_instruction(
                     let cloned_key_list = key_list.keys.clone();
 
                     let (pk, is_signer) = cloned_key_list[idx];
-                    let key_name = "config_keys/".to_owned() + &*idx.to_string();
-                    let pubkey_name = key_name.clone() + &"/pubkey".to_owned();
+                    let key_name = path::join(&["config_keys", &idx.to_string()]);
                     properties.push(InstructionProperty {
                         tx_instruction_id: instruction.tx_instruction_id.clone(),
                         transaction_hash: instruction.transaction_hash.clone(),
                         parent_index: instruction.parent_index.clone(),
-                        key: pubkey_name,
+                        key: "pubkey".to_string(),
                         value: pk.to_string(),
+                        value_type: ValueType::Pubkey,
                         parent_key: key_name.clone(),
                         timestamp: instruction.timestamp.clone(),
+                        slot: instruction.slot.clone(),
+                        block_time: instruction.block_time.clone(),
                     });
 
-                    let signer_name = key_name.clone() + &"/signer".to_owned();
                     properties.push(InstructionProperty {
                         tx_instruction_id: instruction.tx_instruction_id.clone(),
                         transaction_hash: instruction.transaction_hash.clone(),
                         parent_index: instruction.parent_index.clone(),
-                        key: signer_name.clone(),
-                        value: (is_signer as i32).to_string(),
+                        key: "signer".to_string(),
+                        value: is_signer.to_string(),
+                        value_type: ValueType::Bool,
                         parent_key: key_name,
                         timestamp: instruction.timestamp.clone(),
+                        slot: instruction.slot.clone(),
+                        block_time: instruction.block_time.clone(),
                     });
 
                     properties
@@ -71,67 +85,74 @@ pub async fn fragment_instruction(
                 properties.extend(ck);
             }
             instruction_set.properties = properties;
+            instruction_set.properties.extend(
+                instruction.account_properties(&["config_account", "signers*"]));
 
-            Some(instruction_set)
+            Ok(instruction_set)
         }
         Err(err) => {
-            let err_msg = match err {
-                InstructionError::GenericError => "GenericError".to_string(),
-                InstructionError::InvalidArgument => "InvalidArgument".to_string(),
-                InstructionError::InvalidInstructionData => "InvalidInstructionData".to_string(),
-                InstructionError::InvalidAccountData => "InvalidAccountData".to_string(),
-                InstructionError::AccountDataTooSmall => "AccountDataTooSmall".to_string(),
-                InstructionError::InsufficientFunds => "InsufficientFunds".to_string(),
-                InstructionError::IncorrectProgramId => "IncorrectProgramId".to_string(),
-                InstructionError::MissingRequiredSignature => "MissingRequiredSignature".to_string(),
-                InstructionError::AccountAlreadyInitialized => "AccountAlreadyInitialized".to_string(),
-                InstructionError::UninitializedAccount => "UninitializedAccount".to_string(),
-                InstructionError::UnbalancedInstruction => "UnbalancedInstruction".to_string(),
-                InstructionError::ModifiedProgramId => "ModifiedProgramId".to_string(),
-                InstructionError::ExternalAccountLamportSpend => "ExternalAccountLamportSpend".to_string(),
-                InstructionError::ExternalAccountDataModified => "ExternalAccountDataModified".to_string(),
-                InstructionError::ReadonlyLamportChange => "ReadonlyLamportChange".to_string(),
-                InstructionError::ReadonlyDataModified => "ReadonlyDataModified".to_string(),
-                InstructionError::DuplicateAccountIndex => "DuplicateAccountIndex".to_string(),
-                InstructionError::ExecutableModified => "ExecutableModified".to_string(),
-                InstructionError::RentEpochModified => "RentEpochModified".to_string(),
-                InstructionError::NotEnoughAccountKeys => "NotEnoughAccountKeys".to_string(),
-                InstructionError::AccountDataSizeChanged => "AccountDataSizeChanged".to_string(),
-                InstructionError::AccountNotExecutable => "AccountNotExecutable".to_string(),
-                InstructionError::AccountBorrowFailed => "AccountBorrowFailed".to_string(),
-                InstructionError::AccountBorrowOutstanding => "AccountBorrowOutstanding".to_string(),
-                InstructionError::DuplicateAccountOutOfSync => "DuplicateAccountOutOfSync".to_string(),
-                InstructionError::Custom(_) => "Custom".to_string(),
-                InstructionError::InvalidError => "InvalidError".to_string(),
-                InstructionError::ExecutableDataModified => "ExecutableDataModified".to_string(),
-                InstructionError::ExecutableLamportChange => "ExecutableLamportChange".to_string(),
-                InstructionError::ExecutableAccountNotRentExempt => "ExecutableAccountNotRentExempt".to_string(),
-                InstructionError::UnsupportedProgramId => "UnsupportedProgramId".to_string(),
-                InstructionError::CallDepth => "CallDepth".to_string(),
-                InstructionError::MissingAccount => "MissingAccount".to_string(),
-                InstructionError::ReentrancyNotAllowed => "ReentrancyNotAllowed".to_string(),
-                InstructionError::MaxSeedLengthExceeded => "MaxSeedLengthExceeded".to_string(),
-                InstructionError::InvalidSeeds => "InvalidSeeds".to_string(),
-                InstructionError::InvalidRealloc => "InvalidRealloc".to_string(),
-                InstructionError::ComputationalBudgetExceeded => "ComputationalBudgetExceeded".to_string(),
-                InstructionError::PrivilegeEscalation => "PrivilegeEscalation".to_string(),
-                InstructionError::ProgramEnvironmentSetupFailure => "ProgramEnvironmentSetupFailure".to_string(),
-                InstructionError::ProgramFailedToComplete => "ProgramFailedToComplete".to_string(),
-                InstructionError::ProgramFailedToCompile => "ProgramFailedToCompile".to_string(),
-                InstructionError::Immutable => "Immutable".to_string(),
-                InstructionError::IncorrectAuthority => "IncorrectAuthority".to_string(),
-                InstructionError::BorshIoError(_) => "BorshIoError".to_string(),
-                InstructionError::AccountNotRentExempt => "AccountNotRentExempt".to_string(),
-                InstructionError::InvalidAccountOwner => "InvalidAccountOwner".to_string(),
-                InstructionError::ArithmeticOverflow => "ArithmeticOverflow".to_string(),
-                InstructionError::UnsupportedSysvar => "UnsupportedSysvar".to_string(),
-                InstructionError::IllegalOwner => "IllegalOwner".to_string()
-            };
+            let decode_error = DecodeError::from_instruction_error(&instruction.program, &err);
 
             error!("{}{}", "[spi-wrapper/programs/native_config] Unable to deserialize the config \
-            keys due to: ", err_msg);
+            keys due to: ", decode_error);
+
+            Err(decode_error)
+        }
+    }
+}
+
+/// Decodes the data of a config account. The stake config and validator info accounts are the
+/// ones whose layout is known.
+pub async fn fragment_account(
+    // The record holding the account's data
+    account: AccountRecord,
+) -> Result<InstructionSet, DecodeError> {
+    let pubkey = Pubkey::from_str(&account.pubkey).unwrap_or_default();
+
+    match parse_config(account.data.as_slice(), &pubkey) {
+        Ok(ConfigAccountType::StakeConfig(stake_config)) => Ok(InstructionSet {
+            function: account.function("stake-config"),
+            properties: vec![
+                account.property("", "warmup_cooldown_rate",
+                                 stake_config.warmup_cooldown_rate.to_string(), ValueType::Decimal),
+                account.property("", "slash_penalty", stake_config.slash_penalty.to_string(),
+                                 ValueType::U64),
+            ],
+        }),
+        Ok(ConfigAccountType::ValidatorInfo(validator_info)) => {
+            let mut properties = Vec::new();
+            for (idx, key) in validator_info.keys.iter().enumerate() {
+                let parent_key = path::join(&["keys", &idx.to_string()]);
+                properties.push(account.property(&parent_key, "pubkey", key.pubkey.clone(),
+                                                 ValueType::Pubkey));
+                properties.push(account.property(&parent_key, "signer", key.signer.to_string(),
+                                                 ValueType::Bool));
+            }
+
+            // The validator's name, website, details and keybase username.
+            if let Value::Object(info) = validator_info.config_data {
+                for (key, value) in info {
+                    let value = match value {
+                        Value::String(value) => value,
+                        value => value.to_string(),
+                    };
+                    properties.push(account.property("config_data", &key, value,
+                                                     ValueType::String));
+                }
+            }
+
+            Ok(InstructionSet {
+                function: account.function("validator-info"),
+                properties,
+            })
+        }
+        Err(err) => {
+            let decode_error = DecodeError::deserialization(
+                &account.owner, "ParseAccountError", &err.to_string());
+            error!("[spi-wrapper/programs/native_config] Attempt to parse account {} failed due \
+            to {}.", account.pubkey, err);
 
-            None
+            Err(decode_error)
         }
     }
-}
\ No newline at end of file
+}
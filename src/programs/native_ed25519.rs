@@ -0,0 +1,291 @@
+use std::convert::TryFrom;
+
+use serde::Deserialize;
+use solana_program::instruction::CompiledInstruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use tracing::info;
+
+use crate::error::DecodeError;
+use crate::path;
+use crate::programs::signature_check::{
+    instruction_failure, status_properties, Failure, SignatureCheck,
+};
+use crate::{InstructionProperty, Instruction, InstructionSet, Record, ValueType};
+
+pub const PROGRAM_ADDRESS: &str = "Ed25519SigVerify111111111111111111111111111";
+
+pub const PUBKEY_SERIALIZED_SIZE: usize = 32;
+pub const SIGNATURE_SERIALIZED_SIZE: usize = 64;
+pub const SIGNATURE_OFFSETS_SERIALIZED_SIZE: usize = 14;
+// The signature count is followed by a byte of padding, so the offsets are aligned.
+pub const SIGNATURE_OFFSETS_START: usize = 2;
+
+/// An instruction index pointing at the signature verification instruction itself.
+const CURRENT_INSTRUCTION_INDEX: u16 = u16::MAX;
+
+/// Where the parts of a signature sit, as laid out by `ed25519_instruction`.
+#[derive(Debug, Default, Deserialize)]
+pub struct Ed25519SignatureOffsets {
+    pub signature_offset: u16,
+    pub signature_instruction_index: u16,
+    pub public_key_offset: u16,
+    pub public_key_instruction_index: u16,
+    pub message_data_offset: u16,
+    pub message_data_size: u16,
+    pub message_instruction_index: u16,
+}
+
+/// The errors the program fails with, named as in `PrecompileError`.
+#[derive(Debug)]
+pub enum Ed25519Error {
+    InvalidPublicKey,
+    InvalidSignature,
+    InvalidDataOffsets,
+    InvalidInstructionDataSize,
+}
+
+/// Extracts the contents of an instruction into small bits and pieces, or what we would call,
+/// instruction_properties.
+///
+/// Works like `native_secp256k1::fragment_instruction`: each signature is listed under
+/// `signatures` along with the public key and message it signs and whether it verifies.
+pub async fn fragment_instruction(
+    // The instruction
+    instruction: Instruction,
+    // The instructions that were part of the transaction, in order, if known.
+    instructions: Option<&[CompiledInstruction]>,
+) -> Result<InstructionSet, DecodeError> {
+    let instruction_datas: Option<Vec<&[u8]>> = instructions.map(|instructions| {
+        instructions.iter()
+            .map(|instruction| instruction.data.as_slice())
+            .collect()
+    });
+    let datas = InstructionDatas {
+        current: instruction.data.as_slice(),
+        current_index: instruction.tx_instruction_id as usize,
+        all: instruction_datas.as_deref(),
+    };
+
+    let mut properties = vec![];
+
+    let count = instruction.data.first().cloned().unwrap_or_default() as usize;
+    let expected_data_size = SIGNATURE_OFFSETS_START + count * SIGNATURE_OFFSETS_SERIALIZED_SIZE;
+    if instruction.data.len() < SIGNATURE_OFFSETS_START
+        || instruction.data.len() < expected_data_size
+        || (count == 0 && instruction.data.len() > SIGNATURE_OFFSETS_START) {
+        info!("[spi-wrapper/programs/native_ed25519] INFO: On-chain failed instruction found -> \
+        Ed25519Error::InvalidInstructionDataSize");
+
+        properties.push(instruction.property("", "count", count.to_string(), ValueType::U64));
+        properties.extend(status_properties(
+            &instruction, "", Some(&Failure::Invalid(Ed25519Error::InvalidInstructionDataSize))));
+
+        return Ok(InstructionSet {
+            function: instruction.function("verify-signatures"),
+            properties,
+        });
+    }
+
+    let mut failures = vec![];
+    for i in 0..count {
+        let start = SIGNATURE_OFFSETS_START + i * SIGNATURE_OFFSETS_SERIALIZED_SIZE;
+        let end = start + SIGNATURE_OFFSETS_SERIALIZED_SIZE;
+        let parent_key = path::join(&["signatures", &i.to_string()]);
+
+        let check = match bincode::deserialize::<Ed25519SignatureOffsets>(
+            &instruction.data[start..end]) {
+            Ok(offsets) => {
+                properties.extend(offsets_properties(
+                    &instruction, &path::join(&[&parent_key, "offsets"]), &offsets));
+
+                check_signature(&offsets, &datas)
+            }
+            Err(_) => SignatureCheck::invalid(Ed25519Error::InvalidDataOffsets),
+        };
+
+        if let Some(Failure::Invalid(ref err)) = check.failure {
+            info!("[spi-wrapper/programs/native_ed25519] INFO: On-chain failed signature {} found \
+            in transaction {} -> Ed25519Error::{:?}", i, instruction.transaction_hash, err);
+        }
+
+        let parts = &check.parts;
+        properties.extend(vec![
+            instruction.optional_property(
+                &parent_key, "public_key",
+                parts.public_key.map(|public_key| Pubkey::new(public_key).to_string()),
+                ValueType::Pubkey),
+            instruction.optional_property(
+                &parent_key, "signature",
+                parts.signature.map(|signature| Signature::new(signature).to_string()),
+                ValueType::String),
+            instruction.optional_property(&parent_key, "message_hex",
+                                          parts.message.map(hex::encode), ValueType::String),
+        ]);
+        properties.extend(status_properties(&instruction, &parent_key, check.failure.as_ref()));
+        failures.push(check.failure);
+    }
+
+    let failure = instruction_failure(&failures);
+
+    properties.insert(0, instruction.property("", "count", count.to_string(), ValueType::U64));
+    properties.extend(status_properties(&instruction, "", failure));
+
+    Ok(InstructionSet {
+        function: instruction.function("verify-signatures"),
+        properties,
+    })
+}
+
+/// The data of the instructions a signature may point into. Pointing at the instruction itself
+/// works with or without the rest of the transaction's instructions.
+struct InstructionDatas<'a> {
+    current: &'a [u8],
+    current_index: usize,
+    all: Option<&'a [&'a [u8]]>,
+}
+
+/// The parts of a signature, as far as they could be found.
+#[derive(Default)]
+struct SignatureParts<'a> {
+    public_key: Option<&'a [u8]>,
+    signature: Option<&'a [u8]>,
+    message: Option<&'a [u8]>,
+}
+
+fn check_signature<'a>(
+    offsets: &Ed25519SignatureOffsets,
+    datas: &InstructionDatas<'a>,
+) -> SignatureCheck<SignatureParts<'a>, Ed25519Error> {
+    SignatureCheck::run(|parts| verify_signature(parts, offsets, datas))
+}
+
+fn verify_signature<'a>(
+    parts: &mut SignatureParts<'a>,
+    offsets: &Ed25519SignatureOffsets,
+    datas: &InstructionDatas<'a>,
+) -> Result<(), Failure<Ed25519Error>> {
+    let signature = get_data_slice(
+        datas,
+        offsets.signature_instruction_index,
+        offsets.signature_offset,
+        SIGNATURE_SERIALIZED_SIZE,
+    )?;
+    parts.signature = Some(signature);
+
+    let public_key = get_data_slice(
+        datas,
+        offsets.public_key_instruction_index,
+        offsets.public_key_offset,
+        PUBKEY_SERIALIZED_SIZE,
+    )?;
+    parts.public_key = Some(public_key);
+
+    let message = get_data_slice(
+        datas,
+        offsets.message_instruction_index,
+        offsets.message_data_offset,
+        offsets.message_data_size as usize,
+    )?;
+    parts.message = Some(message);
+
+    // The program verifies strictly, rejecting small order keys and non canonical signatures a
+    // plain verification would accept.
+    let signature = ed25519_dalek::Signature::try_from(signature)
+        .map_err(|_| Ed25519Error::InvalidSignature)?;
+    let public_key = ed25519_dalek::PublicKey::from_bytes(public_key)
+        .map_err(|_| Ed25519Error::InvalidPublicKey)?;
+    public_key.verify_strict(message, &signature)
+        .map_err(|_| Ed25519Error::InvalidSignature)?;
+
+    Ok(())
+}
+
+fn get_data_slice<'a>(
+    datas: &InstructionDatas<'a>,
+    instruction_index: u16,
+    offset_start: u16,
+    size: usize,
+) -> Result<&'a [u8], Failure<Ed25519Error>> {
+    let instruction = if instruction_index == CURRENT_INSTRUCTION_INDEX {
+        datas.current
+    } else {
+        let idx = instruction_index as usize;
+        match datas.all {
+            Some(all) => *all.get(idx).ok_or(Ed25519Error::InvalidDataOffsets)?,
+            None if idx == datas.current_index => datas.current,
+            None => return Err(Failure::MissingInstruction),
+        }
+    };
+
+    let start = offset_start as usize;
+    let end = start + size;
+    if end > instruction.len() {
+        return Err(Ed25519Error::InvalidDataOffsets.into());
+    }
+
+    Ok(&instruction[start..end])
+}
+
+fn offsets_properties(
+    instruction: &Instruction,
+    parent_key: &str,
+    offsets: &Ed25519SignatureOffsets,
+) -> Vec<InstructionProperty> {
+    vec![
+        instruction.property(parent_key, "signature_offset",
+                             offsets.signature_offset.to_string(), ValueType::U64),
+        instruction.property(parent_key, "signature_instruction_index",
+                             offsets.signature_instruction_index.to_string(), ValueType::U64),
+        instruction.property(parent_key, "public_key_offset",
+                             offsets.public_key_offset.to_string(), ValueType::U64),
+        instruction.property(parent_key, "public_key_instruction_index",
+                             offsets.public_key_instruction_index.to_string(), ValueType::U64),
+        instruction.property(parent_key, "message_data_offset",
+                             offsets.message_data_offset.to_string(), ValueType::U64),
+        instruction.property(parent_key, "message_data_size",
+                             offsets.message_data_size.to_string(), ValueType::U64),
+        instruction.property(parent_key, "message_instruction_index",
+                             offsets.message_instruction_index.to_string(), ValueType::U64),
+    ]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{block_on, instruction};
+    use crate::process;
+    use solana_sdk::signature::{Keypair, Signer};
+
+    #[test]
+    fn ed25519_signatures_are_resolved_and_verified() {
+        let keypair = Keypair::new();
+        let message = b"hello";
+        let signature = keypair.sign_message(message);
+
+        // The public key, signature and message follow the offsets, in this very instruction.
+        let offsets: Vec<u16> = vec![48, u16::MAX, 16, u16::MAX, 112, message.len() as u16,
+                                     u16::MAX];
+        let mut data = vec![1, 0];
+        data.extend(offsets.iter().flat_map(|offset| offset.to_le_bytes().to_vec()));
+        data.extend(keypair.pubkey().as_ref());
+        data.extend(signature.as_ref());
+        data.extend(message);
+
+        let mut tampered = data.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+
+        let outcomes = block_on(process(vec![
+            instruction(PROGRAM_ADDRESS, &[], data),
+            instruction(PROGRAM_ADDRESS, &[], tampered),
+        ], None));
+
+        let json = |idx: usize| outcomes[idx].clone().instruction_set().unwrap().to_json();
+        let verified = json(0)["properties"].clone();
+        assert_eq!(verified["status"], "Verified");
+        assert!(verified["failure"].is_null());
+        assert_eq!(verified["signatures"][0]["public_key"], keypair.pubkey().to_string());
+        assert_eq!(verified["signatures"][0]["message_hex"], "68656c6c6f");
+        assert_eq!(json(1)["properties"]["signatures"][0]["failure"], "InvalidSignature");
+    }
+}
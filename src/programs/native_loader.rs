@@ -1,3 +1,4 @@
+use crate::error::DecodeError;
 use crate::{Instruction, InstructionSet};
 
 pub const PROGRAM_ADDRESS: &str = "NativeLoader1111111111111111111111111111111";
@@ -9,7 +10,8 @@ pub const PROGRAM_ADDRESS: &str = "NativeLoader1111111111111111111111111111111";
 pub async fn fragment_instruction(
     // The instruction
     _instruction: Instruction,
-) -> Option<InstructionSet> {
+) -> Result<InstructionSet, DecodeError> {
     // We don't have anything to work with
-    None
+    Err(DecodeError::deserialization(&_instruction.program, "Unsupported",
+                                     "Native loader instructions carry nothing to decode."))
 }
\ No newline at end of file
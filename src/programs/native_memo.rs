@@ -0,0 +1,48 @@
+use crate::error::DecodeError;
+use crate::{Instruction, InstructionSet, ValueType};
+
+pub const PROGRAM_ADDRESS_V1: &str = "Memo1UhkJRfHyvLMcVucJwxXeuD728EqVDDwQDxFMNo";
+pub const PROGRAM_ADDRESS_V2: &str = "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr";
+
+/// Extracts the contents of an instruction into small bits and pieces, or what we would call,
+/// instruction_properties.
+///
+/// The whole instruction data is the memo. It is emitted as text when it is valid UTF-8, which
+/// the program enforces, and base64 encoded otherwise (e.g. for memos of failed transactions).
+pub async fn fragment_instruction(
+    // The instruction
+    instruction: Instruction,
+) -> Result<InstructionSet, DecodeError> {
+    let memo = match std::str::from_utf8(instruction.data.as_slice()) {
+        Ok(memo) => instruction.property("", "memo", memo.to_string(), ValueType::String),
+        Err(_) => instruction.property("", "memo", base64::encode(&instruction.data),
+                                       ValueType::Bytes),
+    };
+
+    let mut properties = vec![memo];
+    // v1 takes no accounts, every account handed to v2 must have signed the transaction.
+    properties.extend(instruction.account_properties(&["signers*"]));
+
+    Ok(InstructionSet {
+        function: instruction.function("memo"),
+        properties,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{decode, instruction};
+
+    #[test]
+    fn memos_fall_back_to_base64() {
+        let utf8 = decode(instruction(PROGRAM_ADDRESS_V1, &[], b"gm".to_vec())).to_json();
+        assert_eq!(utf8["properties"]["memo"], "gm");
+
+        let binary = decode(instruction(
+            PROGRAM_ADDRESS_V2, &["signer_a", "signer_b"], vec![0xff, 0xfe])).to_json();
+        assert_eq!(binary["properties"]["memo"], "//4=");
+        assert_eq!(binary["properties"]["accounts"]["signers"],
+                   serde_json::json!(["signer_a", "signer_b"]));
+    }
+}
@@ -1,218 +1,333 @@
 use sha3::Digest;
-use libsecp256k1::PublicKey;
 use solana_program::instruction::CompiledInstruction;
 use solana_sdk::secp256k1_instruction::{
     construct_eth_pubkey, Secp256k1Error, SecpSignatureOffsets, HASHED_PUBKEY_SERIALIZED_SIZE,
     SIGNATURE_OFFSETS_SERIALIZED_SIZE, SIGNATURE_SERIALIZED_SIZE,
 };
-use tracing::{error, info};
+use tracing::info;
 
-use crate::{InstructionProperty, Instruction, InstructionSet, InstructionFunction};
+use crate::error::DecodeError;
+use crate::path;
+use crate::programs::signature_check::{
+    instruction_failure, status_properties, Failure, SignatureCheck,
+};
+use crate::{InstructionProperty, Instruction, InstructionSet, Record, ValueType};
 
 pub const PROGRAM_ADDRESS: &str = "KeccakSecp256k11111111111111111111111111111";
 
 /// Extracts the contents of an instruction into small bits and pieces, or what we would call,
 /// instruction_properties.
 ///
-/// The function should return a list of instruction properties extracted from an instruction.
+/// Every signature of the instruction is verified the way the program does, and is listed under
+/// `signatures` with what it was checked against and whether it passed. The slices a signature
+/// is made of may sit in other instructions of the transaction, so the transaction's
+/// instructions are needed to verify it, unless it only refers to the instruction itself.
 pub async fn fragment_instruction(
     // The instruction
     instruction: Instruction,
-    // The instructions that were part of the transaction, in order.
-    instructions: &[CompiledInstruction]
-) -> Option<InstructionSet> {
-    // The first element within data slice tells us the number of signatures.
-    let count = instruction.data[0] as usize;
-    let expected_data_size = 1 + count * SIGNATURE_OFFSETS_SERIALIZED_SIZE;
-
-    // Create the instruction
-    let mut interpreted_instruction_set = InstructionSet {
-        function: InstructionFunction {
-            tx_instruction_id: instruction.tx_instruction_id.clone(),
-            transaction_hash: instruction.transaction_hash.clone(),
-            parent_index: instruction.parent_index.clone(),
-            program: instruction.program.clone(),
-            function_name: "".to_string(),
-            timestamp: instruction.timestamp.clone(),
-        },
-        properties: vec![],
+    // The instructions that were part of the transaction, in order, if known.
+    instructions: Option<&[CompiledInstruction]>,
+) -> Result<InstructionSet, DecodeError> {
+    // Adapted from: https://github.com/solana-labs/solana/blob/cab30e2356b1badd78e2285d10e95adaceb7ceed/sdk/src/transaction.rs#L398
+    let instruction_datas: Option<Vec<&[u8]>> = instructions.map(|instructions| {
+        instructions.iter()
+            .map(|instruction| instruction.data.as_slice())
+            .collect()
+    });
+    let datas = InstructionDatas {
+        current: instruction.data.as_slice(),
+        current_index: instruction.tx_instruction_id as usize,
+        all: instruction_datas.as_deref(),
     };
 
-    // On-chain failed instruction detected, safe anyways.
+    let mut properties = vec![];
+
+    // The first element within data slice tells us the number of signatures. On-chain failed
+    // instructions may not even have that, which is safe anyways.
+    let count = instruction.data.first().cloned().unwrap_or_default() as usize;
+    let expected_data_size = 1 + count * SIGNATURE_OFFSETS_SERIALIZED_SIZE;
     if instruction.data.len() < expected_data_size {
-        info!(
-            "[spi-wrapper/programs/native_secp256k1] INFO: On-chain failed instruction \
-        found -> Secp256k1Error::InvalidInstructionDataSize"
-        );
+        info!("[spi-wrapper/programs/native_secp256k1] INFO: On-chain failed instruction found \
+        -> Secp256k1Error::InvalidInstructionDataSize");
 
-        return Some(interpreted_instruction_set)
-    }
+        properties.push(instruction.property("", "count", count.to_string(), ValueType::U64));
+        properties.extend(status_properties(
+            &instruction, "", Some(&Failure::Invalid(Secp256k1Error::InvalidInstructionDataSize))));
 
-    // Gather every instruction's data.
-    // Adapted from: https://github.com/solana-labs/solana/blob/cab30e2356b1badd78e2285d10e95adaceb7ceed/sdk/src/transaction.rs#L398
-    let instruction_datas: Vec<&[u8]> = instructions
-        .iter()
-        .map(|instruction| instruction.data.as_ref())
-        .collect();
+        return Ok(InstructionSet {
+            function: instruction.function("verify-signatures"),
+            properties,
+        });
+    }
 
+    let mut failures = vec![];
     for i in 0..count {
         let start = 1 + i * SIGNATURE_OFFSETS_SERIALIZED_SIZE;
         let end = start + SIGNATURE_OFFSETS_SERIALIZED_SIZE;
+        let parent_key = path::join(&["signatures", &i.to_string()]);
 
-        let offsets_result = bincode::deserialize::<SecpSignatureOffsets>(
-            &instruction.data[start..end]);
-
-        if let Ok(offsets) = offsets_result {
-            // Parse out signature
-            let signature_index = offsets.signature_instruction_index as usize;
-            if signature_index >= instruction_datas.len() {
-                info!(
-                    "[spi-wrapper/programs/native_secp256k1] INFO: On-chain failed instruction \
-        found because the signature_index was greater than the data size. -> \
-        Secp256k1Error::InvalidInstructionDataSize"
-                );
-                return Some(interpreted_instruction_set)
-            }
-            let signature_instruction = instruction_datas[signature_index];
-            let sig_start = offsets.signature_offset as usize;
-            let sig_end = sig_start + SIGNATURE_SERIALIZED_SIZE;
-            if sig_end >= signature_instruction.len() {
-                info!(
-                    "[spi-wrapper/programs/native_secp256k1] INFO: On-chain failed instruction \
-        found because the last_signature_index was greater than the data size. -> \
-        Secp256k1Error::InvalidSignature"
-                );
-                return Some(interpreted_instruction_set)
-            }
-            let signature_result = libsecp256k1::Signature::parse_standard_slice(
-                &signature_instruction[sig_start..sig_end],
-            )
-                .map_err(|_| Secp256k1Error::InvalidSignature);
-            if let Err(_) = signature_result {
-                info!(
-                    "[spi-wrapper/programs/native_secp256k1] INFO: On-chain failed instruction \
-        found because the signature's slice cannot be parsed. -> Secp256k1Error::InvalidSignature"
-                );
-                return Some(interpreted_instruction_set)
-            }
-            let signature = signature_result.unwrap();
-
-            let recovery_id_result =
-                libsecp256k1::RecoveryId::parse(signature_instruction[sig_end])
-                    .map_err(|_| Secp256k1Error::InvalidRecoveryId);
-            if let Err(_) = recovery_id_result {
-                info!(
-                    "[spi-wrapper/programs/native_secp256k1] INFO: On-chain failed instruction \
-        found because the signature's slice cannot be parsed. -> Secp256k1Error::InvalidSignature"
-                );
-                return Some(interpreted_instruction_set)
-            }
-            let recovery_id = recovery_id_result.unwrap();
-
-            // Parse out pubkey
-            let eth_address_slice_result = get_data_slice(
-                &instruction_datas,
-                offsets.eth_address_instruction_index,
-                offsets.eth_address_offset,
-                HASHED_PUBKEY_SERIALIZED_SIZE,
-            );
-            if let Err(_) = eth_address_slice_result {
-                info!(
-                    "[spi-wrapper/programs/native_secp256k1] INFO: On-chain failed instruction \
-        found because the eth address slice cannot be parsed."
-                );
-                return Some(interpreted_instruction_set)
-            }
-            let eth_address_slice = eth_address_slice_result.unwrap();
-
-            // Parse out message
-            let message_slice_result = get_data_slice(
-                &instruction_datas,
-                offsets.message_instruction_index,
-                offsets.message_data_offset,
-                offsets.message_data_size as usize,
-            );
-            if let Err(_) = message_slice_result {
-                info!(
-                    "[spi-wrapper/programs/native_secp256k1] INFO: On-chain failed instruction \
-        found because the message slice cannot be parsed."
-                );
-                return Some(interpreted_instruction_set)
-            }
-            let message_slice = message_slice_result.unwrap();
-
-            let mut hasher = sha3::Keccak256::new();
-            hasher.update(message_slice);
-            let message_hash = hasher.finalize();
-
-            let pubkey_result: Result<PublicKey, Secp256k1Error> = libsecp256k1::recover(
-                &libsecp256k1::Message::parse_slice(&message_hash).unwrap(),
-                &signature,
-                &recovery_id,
-            )
-                .map_err(|_| Secp256k1Error::InvalidSignature);
-            if let Err(_) = pubkey_result {
-                info!(
-                    "[spi-wrapper/programs/native_secp256k1] INFO: On-chain failed instruction \
-        found because the pubkey's slice cannot be parsed. -> Secp256k1Error::InvalidSignature"
-                );
-                return Some(interpreted_instruction_set)
-            }
-            let pubkey: libsecp256k1::PublicKey = pubkey_result.unwrap();
-
-            let eth_address = construct_eth_pubkey(&pubkey);
-            let eth_address_str = "0x".to_string() + &*hex::encode(eth_address);
-            interpreted_instruction_set.properties
-                .push(InstructionProperty {
-                    tx_instruction_id: instruction.tx_instruction_id.clone(),
-                    transaction_hash: instruction.transaction_hash.clone(),
-                    parent_index: instruction.parent_index.clone(),
-                    key: "eth_address".to_string(),
-                    value: eth_address_str.to_string(),
-                    parent_key: "".to_string(),
-                    timestamp: instruction.timestamp.clone(),
-                });
-
-            if eth_address_slice != eth_address {
-                info!(
-                    "[spi-wrapper/programs/native_secp256k1] INFO: On-chain failed instruction \
-        found because there was an eth address slice mismatch v.s. the actual eth address. -> \
-        Secp256k1Error::InvalidSignature"
-                );
-                return Some(interpreted_instruction_set)
+        let check = match bincode::deserialize::<SecpSignatureOffsets>(
+            &instruction.data[start..end]) {
+            Ok(offsets) => {
+                properties.extend(offsets_properties(
+                    &instruction, &path::join(&[&parent_key, "offsets"]), &offsets));
+
+                check_signature(&offsets, &datas)
             }
+            Err(_) => SignatureCheck::invalid(Secp256k1Error::InvalidInstructionDataSize),
+        };
 
-            return Some(interpreted_instruction_set)
+        if let Some(Failure::Invalid(ref err)) = check.failure {
+            info!("[spi-wrapper/programs/native_secp256k1] INFO: On-chain failed signature {} \
+            found in transaction {} -> Secp256k1Error::{:?}", i, instruction.transaction_hash, err);
         }
+
+        let parts = &check.parts;
+        let prefixed_hex = |bytes: &[u8]| "0x".to_string() + &hex::encode(bytes);
+        properties.extend(vec![
+            instruction.optional_property(
+                &parent_key, "eth_address",
+                parts.eth_address.as_ref().map(|eth_address| prefixed_hex(eth_address)),
+                ValueType::String),
+            instruction.optional_property(&parent_key, "expected_eth_address",
+                                          parts.expected_eth_address.map(prefixed_hex),
+                                          ValueType::String),
+            instruction.optional_property(
+                &parent_key, "message_hash",
+                parts.message_hash.as_ref().map(|message_hash| prefixed_hex(message_hash)),
+                ValueType::String),
+            instruction.optional_property(&parent_key, "message_hex",
+                                          parts.message.map(hex::encode), ValueType::String),
+        ]);
+        properties.extend(status_properties(&instruction, &parent_key, check.failure.as_ref()));
+        failures.push(check.failure);
     }
 
-    error!("{}",
-        "[spi-wrapper/programs/native_secp256k1] FATAL: The instruction interpretation \
-    terminated unknowingly."
-            .to_string(),
-    );
+    let failure = instruction_failure(&failures);
+
+    properties.insert(0, instruction.property("", "count", count.to_string(), ValueType::U64));
+    properties.extend(status_properties(&instruction, "", failure));
+
+    Ok(InstructionSet {
+        function: instruction.function("verify-signatures"),
+        properties,
+    })
+}
+
+/// The data of the instructions a signature may point into. Without the rest of the
+/// transaction's instructions, only the instruction itself is known, at its position in the
+/// transaction.
+struct InstructionDatas<'a> {
+    current: &'a [u8],
+    current_index: usize,
+    all: Option<&'a [&'a [u8]]>,
+}
+
+/// What a signature was checked against, as far as the check went.
+#[derive(Default)]
+struct SignatureParts<'a> {
+    eth_address: Option<[u8; HASHED_PUBKEY_SERIALIZED_SIZE]>,
+    expected_eth_address: Option<&'a [u8]>,
+    message: Option<&'a [u8]>,
+    message_hash: Option<Vec<u8>>,
+}
 
-    None
+fn check_signature<'a>(
+    offsets: &SecpSignatureOffsets,
+    datas: &InstructionDatas<'a>,
+) -> SignatureCheck<SignatureParts<'a>, Secp256k1Error> {
+    SignatureCheck::run(|parts| verify_signature(parts, offsets, datas))
+}
+
+// Adapted from secp256k1_instruction
+// https://github.com/solana-labs/solana/blob/d269ca510cc9961be9bdc7ae09574e44cfd713a3/sdk/src/secp256k1_instruction.rs#L104
+fn verify_signature<'a>(
+    parts: &mut SignatureParts<'a>,
+    offsets: &SecpSignatureOffsets,
+    datas: &InstructionDatas<'a>,
+) -> Result<(), Failure<Secp256k1Error>> {
+    // Parse out pubkey
+    let eth_address_slice = get_data_slice(
+        datas,
+        offsets.eth_address_instruction_index,
+        offsets.eth_address_offset,
+        HASHED_PUBKEY_SERIALIZED_SIZE,
+    )?;
+    parts.expected_eth_address = Some(eth_address_slice);
+
+    // Parse out message
+    let message_slice = get_data_slice(
+        datas,
+        offsets.message_instruction_index,
+        offsets.message_data_offset,
+        offsets.message_data_size as usize,
+    )?;
+    parts.message = Some(message_slice);
+
+    let mut hasher = sha3::Keccak256::new();
+    hasher.update(message_slice);
+    let message_hash = hasher.finalize();
+    parts.message_hash = Some(message_hash.to_vec());
+
+    // Parse out signature, followed by its recovery id
+    let signature_slice = get_data_slice(
+        datas,
+        offsets.signature_instruction_index,
+        offsets.signature_offset,
+        SIGNATURE_SERIALIZED_SIZE + 1,
+    )?;
+    let signature = libsecp256k1::Signature::parse_standard_slice(
+        &signature_slice[..SIGNATURE_SERIALIZED_SIZE],
+    )
+        .map_err(|_| Secp256k1Error::InvalidSignature)?;
+    let recovery_id = libsecp256k1::RecoveryId::parse(signature_slice[SIGNATURE_SERIALIZED_SIZE])
+        .map_err(|_| Secp256k1Error::InvalidRecoveryId)?;
+
+    let message = libsecp256k1::Message::parse_slice(&message_hash)
+        .map_err(|_| Secp256k1Error::InvalidSignature)?;
+    let pubkey = libsecp256k1::recover(&message, &signature, &recovery_id)
+        .map_err(|_| Secp256k1Error::InvalidSignature)?;
+
+    let eth_address = construct_eth_pubkey(&pubkey);
+    parts.eth_address = Some(eth_address);
+    if eth_address_slice != eth_address {
+        return Err(Secp256k1Error::InvalidSignature.into());
+    }
+
+    Ok(())
 }
 
 // Adapted from secp256k1_instruction
 // https://github.com/solana-labs/solana/blob/d269ca510cc9961be9bdc7ae09574e44cfd713a3/sdk/src/secp256k1_instruction.rs#L176
 fn get_data_slice<'a>(
-    instruction_datas: &'a [&[u8]],
+    datas: &InstructionDatas<'a>,
     instruction_index: u8,
     offset_start: u16,
     size: usize,
-) -> Result<&'a [u8], Secp256k1Error> {
+) -> Result<&'a [u8], Failure<Secp256k1Error>> {
     let signature_index = instruction_index as usize;
-    if signature_index >= instruction_datas.len() {
-        return Err(Secp256k1Error::InvalidDataOffsets);
-    }
-    let signature_instruction = &instruction_datas[signature_index];
+    let signature_instruction = match datas.all {
+        Some(all) => *all.get(signature_index).ok_or(Secp256k1Error::InvalidDataOffsets)?,
+        None if signature_index == datas.current_index => datas.current,
+        None => return Err(Failure::MissingInstruction),
+    };
     let start = offset_start as usize;
     let end = start + size;
     if end > signature_instruction.len() {
-        return Err(Secp256k1Error::InvalidSignature);
+        return Err(Secp256k1Error::InvalidSignature.into());
+    }
+
+    Ok(&signature_instruction[start..end])
+}
+
+fn offsets_properties(
+    instruction: &Instruction,
+    parent_key: &str,
+    offsets: &SecpSignatureOffsets,
+) -> Vec<InstructionProperty> {
+    vec![
+        instruction.property(parent_key, "signature_offset",
+                             offsets.signature_offset.to_string(), ValueType::U64),
+        instruction.property(parent_key, "signature_instruction_index",
+                             offsets.signature_instruction_index.to_string(), ValueType::U64),
+        instruction.property(parent_key, "eth_address_offset",
+                             offsets.eth_address_offset.to_string(), ValueType::U64),
+        instruction.property(parent_key, "eth_address_instruction_index",
+                             offsets.eth_address_instruction_index.to_string(), ValueType::U64),
+        instruction.property(parent_key, "message_data_offset",
+                             offsets.message_data_offset.to_string(), ValueType::U64),
+        instruction.property(parent_key, "message_data_size",
+                             offsets.message_data_size.to_string(), ValueType::U64),
+        instruction.property(parent_key, "message_instruction_index",
+                             offsets.message_instruction_index.to_string(), ValueType::U64),
+    ]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{block_on, instruction};
+    use crate::process;
+    use solana_sdk::secp256k1_instruction::new_secp256k1_instruction;
+
+    #[test]
+    fn every_secp256k1_signature_is_verified() {
+        let secret_key = libsecp256k1::SecretKey::parse(&[7; 32]).unwrap();
+        let signed = new_secp256k1_instruction(&secret_key, b"hello").data;
+
+        // The same signature twice, with the offsets moved past the second set of offsets.
+        let mut offsets: SecpSignatureOffsets = bincode::deserialize(
+            &signed[1..1 + SIGNATURE_OFFSETS_SERIALIZED_SIZE]).unwrap();
+        let shift = SIGNATURE_OFFSETS_SERIALIZED_SIZE as u16;
+        offsets.signature_offset += shift;
+        offsets.eth_address_offset += shift;
+        offsets.message_data_offset += shift;
+        let offsets = bincode::serialize(&offsets).unwrap();
+        let mut data = vec![2];
+        data.extend(&offsets);
+        data.extend(&offsets);
+        data.extend(&signed[1 + SIGNATURE_OFFSETS_SERIALIZED_SIZE..]);
+
+        let mut tampered = data.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+
+        let outcomes = block_on(process(vec![
+            instruction(PROGRAM_ADDRESS, &[], data),
+            instruction(PROGRAM_ADDRESS, &[], tampered),
+            instruction(PROGRAM_ADDRESS, &[], vec![]),
+        ], None));
+
+        let json = |idx: usize| outcomes[idx].clone().instruction_set().unwrap().to_json();
+        let verified = json(0)["properties"].clone();
+        assert_eq!(verified["count"], 2);
+        assert_eq!(verified["status"], "Verified");
+        assert_eq!(verified["signatures"].as_array().unwrap().len(), 2);
+        assert_eq!(verified["signatures"][1]["eth_address"],
+                   verified["signatures"][1]["expected_eth_address"]);
+
+        let tampered = json(1)["properties"].clone();
+        assert_eq!(tampered["status"], "Failed");
+        assert_eq!(tampered["signatures"][1]["failure"], "InvalidSignature");
+
+        assert_eq!(json(2)["properties"]["failure"], "InvalidInstructionDataSize");
     }
 
-    Ok(&instruction_datas[signature_index][start..end])
-}
\ No newline at end of file
+    #[test]
+    fn signatures_in_other_instructions_need_the_transaction() {
+        let secret_key = libsecp256k1::SecretKey::parse(&[7; 32]).unwrap();
+        let signed = new_secp256k1_instruction(&secret_key, b"hello").data;
+
+        // The offsets point into the next instruction, which holds the signature as signed.
+        let mut offsets: SecpSignatureOffsets = bincode::deserialize(
+            &signed[1..1 + SIGNATURE_OFFSETS_SERIALIZED_SIZE]).unwrap();
+        offsets.signature_instruction_index = 1;
+        offsets.eth_address_instruction_index = 1;
+        offsets.message_instruction_index = 1;
+        let mut data = vec![1];
+        data.extend(bincode::serialize(&offsets).unwrap());
+
+        let compiled = |data: &[u8]| CompiledInstruction {
+            program_id_index: 0,
+            accounts: vec![],
+            data: data.to_vec(),
+        };
+        let json = |og_instructions: Option<Vec<CompiledInstruction>>| {
+            let outcomes = block_on(process(
+                vec![instruction(PROGRAM_ADDRESS, &[], data.clone())], og_instructions));
+            outcomes[0].clone().instruction_set().unwrap().to_json()["properties"].clone()
+        };
+
+        let unknown = json(None);
+        assert_eq!(unknown["status"], "Unknown");
+        assert_eq!(unknown["failure"], "MissingInstruction");
+        assert_eq!(unknown["signatures"][0]["failure"], "MissingInstruction");
+
+        let verified = json(Some(vec![compiled(&data), compiled(&signed)]));
+        assert_eq!(verified["status"], "Verified");
+        assert!(verified["failure"].is_null());
+
+        let out_of_range = json(Some(vec![compiled(&data)]));
+        assert_eq!(out_of_range["status"], "Failed");
+        assert_eq!(out_of_range["failure"], "InvalidDataOffsets");
+    }
+}
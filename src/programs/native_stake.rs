@@ -1,10 +1,13 @@
-use solana_program::instruction::InstructionError;
 use solana_sdk::program_utils::limited_deserialize;
 use solana_program::stake::instruction::StakeInstruction;
-use solana_program::stake::state::StakeAuthorize;
+use solana_program::stake::state::{Meta, StakeAuthorize, StakeState};
 use tracing::error;
 
-use crate::{InstructionProperty, Instruction, InstructionSet, InstructionFunction};
+use crate::error::DecodeError;
+use crate::{
+    AccountRecord, InstructionProperty, Instruction, InstructionSet, InstructionFunction, Record,
+    ValueType,
+};
 
 pub const PROGRAM_ADDRESS: &str = "Stake11111111111111111111111111111111111111";
 
@@ -15,21 +18,22 @@ pub const PROGRAM_ADDRESS: &str = "Stake11111111111111111111111111111111111111";
 pub async fn fragment_instruction(
     // The instruction
     instruction: Instruction,
-) -> Option<InstructionSet> {
+) -> Result<InstructionSet, DecodeError> {
     let dsr = limited_deserialize::<StakeInstruction>(
         instruction.data.as_slice());
 
     match dsr {
         Ok(ref si) => {
             let stake_result = si.clone();
-            match stake_result {
+            let account_properties = instruction.account_properties(account_roles(&stake_result));
+            let instruction_set = match stake_result {
                 StakeInstruction::Initialize(authorized, lockup) => {
                     // me.initialize(
                     //     &authorized,
                     //     &lockup,
                     //     &from_keyed_account::<Rent>(next_keyed_account(keyed_accounts)?)?,
                     // )
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -37,6 +41,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "initialize".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![
                             InstructionProperty {
@@ -45,8 +51,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "staker".to_string(),
                                 value: authorized.staker.to_string(),
+                                value_type: ValueType::Pubkey,
                                 parent_key: "authorized".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -54,8 +63,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "withdrawer".to_string(),
                                 value: authorized.withdrawer.to_string(),
+                                value_type: ValueType::Pubkey,
                                 parent_key: "authorized".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -63,8 +75,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "epoch".to_string(),
                                 value: lockup.epoch.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "lockup".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -72,8 +87,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "custodian".to_string(),
                                 value: lockup.custodian.to_string(),
+                                value_type: ValueType::Pubkey,
                                 parent_key: "lockup".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -81,14 +99,17 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "unix_timestamp".to_string(),
                                 value: lockup.unix_timestamp.to_string(),
+                                value_type: ValueType::I64,
                                 parent_key: "lockup".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                         ],
                     })
                 }
                 StakeInstruction::InitializeChecked => {
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -96,6 +117,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "initialize-checked".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![],
                     })
@@ -132,7 +155,7 @@ pub async fn fragment_instruction(
                     //         None,
                     //     )
                     // }
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -140,6 +163,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "authorize".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![
                             InstructionProperty {
@@ -148,8 +173,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "authorized_pubkey".to_string(),
                                 value: authorized_pubkey.to_string(),
+                                value_type: ValueType::Pubkey,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -160,15 +188,18 @@ pub async fn fragment_instruction(
                                     StakeAuthorize::Staker => "staker".to_string(),
                                     StakeAuthorize::Withdrawer => "withdrawer".to_string()
                                 },
+                                value_type: ValueType::EnumName,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                         ],
                     })
                 }
                 StakeInstruction::AuthorizeChecked(stake_authorize) => {
                     // stake_authorize
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -176,6 +207,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "authorize-checked".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![
                             InstructionProperty {
@@ -187,15 +220,18 @@ pub async fn fragment_instruction(
                                     StakeAuthorize::Staker => "staker".to_string(),
                                     StakeAuthorize::Withdrawer => "withdrawer".to_string()
                                 },
+                                value_type: ValueType::EnumName,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             }
                         ],
                     })
                 }
                 StakeInstruction::AuthorizeCheckedWithSeed(authorize_checked_with_seed_args) => {
                     // stake_authorize
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -203,6 +239,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "authorize-checked-with-seed".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![
                             InstructionProperty {
@@ -211,8 +249,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "authority_seed".to_string(),
                                 value: authorize_checked_with_seed_args.authority_seed.to_string(),
+                                value_type: ValueType::String,
                                 parent_key: "authorize_checked_with_seed_args".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -220,8 +261,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "authority_owner".to_string(),
                                 value: authorize_checked_with_seed_args.authority_owner.to_string(),
+                                value_type: ValueType::Pubkey,
                                 parent_key: "authorize_checked_with_seed_args".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -232,8 +276,11 @@ pub async fn fragment_instruction(
                                     StakeAuthorize::Staker => "staker".to_string(),
                                     StakeAuthorize::Withdrawer => "withdrawer".to_string()
                                 },
+                                value_type: ValueType::EnumName,
                                 parent_key: "authorize_checked_with_seed_args".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                         ],
                     })
@@ -274,7 +321,7 @@ pub async fn fragment_instruction(
                     //         None,
                     //     )
                     // }
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -282,6 +329,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "authorize-with-seed".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![
                             InstructionProperty {
@@ -290,8 +339,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "authority_seed".to_string(),
                                 value: authorize_with_seed_args.authority_seed.to_string(),
+                                value_type: ValueType::String,
                                 parent_key: "authorize_with_seed_args".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -299,8 +351,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "authority_owner".to_string(),
                                 value: authorize_with_seed_args.authority_owner.to_string(),
+                                value_type: ValueType::Pubkey,
                                 parent_key: "authorize_with_seed_args".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -311,8 +366,11 @@ pub async fn fragment_instruction(
                                     StakeAuthorize::Staker => "staker".to_string(),
                                     StakeAuthorize::Withdrawer => "withdrawer".to_string()
                                 },
-                                parent_key: "authorize_checked_with_seed_args".to_string(),
+                                value_type: ValueType::EnumName,
+                                parent_key: "authorize_with_seed_args".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -320,8 +378,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "new_authorized_pubkey".to_string(),
                                 value: authorize_with_seed_args.new_authorized_pubkey.to_string(),
-                                parent_key: "authorize_checked_with_seed_args".to_string(),
+                                value_type: ValueType::Pubkey,
+                                parent_key: "authorize_with_seed_args".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                         ],
                     })
@@ -341,7 +402,7 @@ pub async fn fragment_instruction(
                     //     &signers,
                     //     can_reverse_deactivation,
                     // )
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -349,6 +410,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "delegate-stake".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![],
                     })
@@ -356,7 +419,7 @@ pub async fn fragment_instruction(
                 StakeInstruction::Split(lamports) => {
                     // let split_stake = &next_keyed_account(keyed_accounts)?;
                     // me.split(lamports, split_stake, &signers)
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -364,6 +427,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "split".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![
                             InstructionProperty {
@@ -372,8 +437,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "lamports".to_string(),
                                 value: lamports.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             }
                         ],
                     })
@@ -392,7 +460,7 @@ pub async fn fragment_instruction(
                     //     &signers,
                     //     can_merge_expired_lockups,
                     // )
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -400,6 +468,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "merge".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![],
                     })
@@ -418,7 +488,7 @@ pub async fn fragment_instruction(
                     //     invoke_context
                     //         .is_feature_active(&feature_set::stake_program_v4::id()),
                     // )
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -426,6 +496,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "withdraw".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![
                             InstructionProperty {
@@ -434,8 +506,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "lamports".to_string(),
                                 value: lamports.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             }
                         ],
                     })
@@ -445,7 +520,7 @@ pub async fn fragment_instruction(
                     //     &from_keyed_account::<Clock>(next_keyed_account(keyed_accounts)?)?,
                     //     &signers,
                     // )
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -453,6 +528,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "deactivate".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![],
                     })
@@ -466,7 +543,7 @@ pub async fn fragment_instruction(
                     //     None
                     // };
                     // me.set_lockup(&lockup, &signers, clock.as_ref())
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -474,53 +551,34 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "set-lockup".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![
-                            InstructionProperty {
-                                tx_instruction_id: instruction.tx_instruction_id.clone(),
-                                transaction_hash: instruction.transaction_hash.clone(),
-                                parent_index: instruction.parent_index.clone(),
-                                key: "epoch".to_string(),
-                                value: if let Some(epoch) = lockup_args.epoch {
-                                    epoch.to_string()
-                                } else {
-                                    "".to_string()
-                                },
-                                parent_key: "lockup_args".to_string(),
-                                timestamp: instruction.timestamp.clone(),
-                            },
-                            InstructionProperty {
-                                tx_instruction_id: instruction.tx_instruction_id.clone(),
-                                transaction_hash: instruction.transaction_hash.clone(),
-                                parent_index: instruction.parent_index.clone(),
-                                key: "unix_timestamp".to_string(),
-                                value: if let Some(unix_timestamp) = lockup_args.unix_timestamp {
-                                    unix_timestamp.to_string()
-                                } else {
-                                    "".to_string()
-                                },
-                                parent_key: "lockup_args".to_string(),
-                                timestamp: instruction.timestamp.clone(),
-                            },
-                            InstructionProperty {
-                                tx_instruction_id: instruction.tx_instruction_id.clone(),
-                                transaction_hash: instruction.transaction_hash.clone(),
-                                parent_index: instruction.parent_index.clone(),
-                                key: "custodian".to_string(),
-                                value: if let Some(custodian) = lockup_args.custodian {
-                                    custodian.to_string()
-                                } else {
-                                    "".to_string()
-                                },
-                                parent_key: "lockup_args".to_string(),
-                                timestamp: instruction.timestamp.clone(),
-                            },
+                            instruction.optional_property(
+                                "lockup_args",
+                                "epoch",
+                                lockup_args.epoch.map(|epoch| epoch.to_string()),
+                                ValueType::U64,
+                            ),
+                            instruction.optional_property(
+                                "lockup_args",
+                                "unix_timestamp",
+                                lockup_args.unix_timestamp.map(|timestamp| timestamp.to_string()),
+                                ValueType::I64,
+                            ),
+                            instruction.optional_property(
+                                "lockup_args",
+                                "custodian",
+                                lockup_args.custodian.map(|custodian| custodian.to_string()),
+                                ValueType::Pubkey,
+                            ),
                         ],
                     })
                 }
                 StakeInstruction::SetLockupChecked(lockup_checked_args) => {
                     // lockup_checked
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -528,98 +586,141 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "set-lockup-checked".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![
-                            InstructionProperty {
-                                tx_instruction_id: instruction.tx_instruction_id.clone(),
-                                transaction_hash: instruction.transaction_hash.clone(),
-                                parent_index: instruction.parent_index.clone(),
-                                key: "epoch".to_string(),
-                                value: if let Some(epoch) = lockup_checked_args.epoch {
-                                    epoch.to_string()
-                                } else {
-                                    "".to_string()
-                                },
-                                parent_key: "lockup_checked_args".to_string(),
-                                timestamp: instruction.timestamp.clone(),
-                            },
-                            InstructionProperty {
-                                tx_instruction_id: instruction.tx_instruction_id.clone(),
-                                transaction_hash: instruction.transaction_hash.clone(),
-                                parent_index: instruction.parent_index.clone(),
-                                key: "unix_timestamp".to_string(),
-                                value: if let Some(unix_timestamp) =
-                                lockup_checked_args.unix_timestamp {
-                                    unix_timestamp.to_string()
-                                } else {
-                                    "".to_string()
-                                },
-                                parent_key: "lockup_checked_args".to_string(),
-                                timestamp: instruction.timestamp.clone(),
-                            },
+                            instruction.optional_property(
+                                "lockup_checked_args",
+                                "epoch",
+                                lockup_checked_args.epoch.map(|epoch| epoch.to_string()),
+                                ValueType::U64,
+                            ),
+                            instruction.optional_property(
+                                "lockup_checked_args",
+                                "unix_timestamp",
+                                lockup_checked_args.unix_timestamp.map(|time| time.to_string()),
+                                ValueType::I64,
+                            ),
                         ],
                     })
                 }
-            }
+            };
+
+            instruction_set.map(|mut set| {
+                set.properties.extend(account_properties);
+                set
+            })
         }
         Err(err) => {
-            let err_msg = match err {
-                InstructionError::GenericError => "GenericError".to_string(),
-                InstructionError::InvalidArgument => "InvalidArgument".to_string(),
-                InstructionError::InvalidInstructionData => "InvalidInstructionData".to_string(),
-                InstructionError::InvalidAccountData => "InvalidAccountData".to_string(),
-                InstructionError::AccountDataTooSmall => "AccountDataTooSmall".to_string(),
-                InstructionError::InsufficientFunds => "InsufficientFunds".to_string(),
-                InstructionError::IncorrectProgramId => "IncorrectProgramId".to_string(),
-                InstructionError::MissingRequiredSignature => "MissingRequiredSignature".to_string(),
-                InstructionError::AccountAlreadyInitialized => "AccountAlreadyInitialized".to_string(),
-                InstructionError::UninitializedAccount => "UninitializedAccount".to_string(),
-                InstructionError::UnbalancedInstruction => "UnbalancedInstruction".to_string(),
-                InstructionError::ModifiedProgramId => "ModifiedProgramId".to_string(),
-                InstructionError::ExternalAccountLamportSpend => "ExternalAccountLamportSpend".to_string(),
-                InstructionError::ExternalAccountDataModified => "ExternalAccountDataModified".to_string(),
-                InstructionError::ReadonlyLamportChange => "ReadonlyLamportChange".to_string(),
-                InstructionError::ReadonlyDataModified => "ReadonlyDataModified".to_string(),
-                InstructionError::DuplicateAccountIndex => "DuplicateAccountIndex".to_string(),
-                InstructionError::ExecutableModified => "ExecutableModified".to_string(),
-                InstructionError::RentEpochModified => "RentEpochModified".to_string(),
-                InstructionError::NotEnoughAccountKeys => "NotEnoughAccountKeys".to_string(),
-                InstructionError::AccountDataSizeChanged => "AccountDataSizeChanged".to_string(),
-                InstructionError::AccountNotExecutable => "AccountNotExecutable".to_string(),
-                InstructionError::AccountBorrowFailed => "AccountBorrowFailed".to_string(),
-                InstructionError::AccountBorrowOutstanding => "AccountBorrowOutstanding".to_string(),
-                InstructionError::DuplicateAccountOutOfSync => "DuplicateAccountOutOfSync".to_string(),
-                InstructionError::Custom(_) => "Custom".to_string(),
-                InstructionError::InvalidError => "InvalidError".to_string(),
-                InstructionError::ExecutableDataModified => "ExecutableDataModified".to_string(),
-                InstructionError::ExecutableLamportChange => "ExecutableLamportChange".to_string(),
-                InstructionError::ExecutableAccountNotRentExempt => "ExecutableAccountNotRentExempt".to_string(),
-                InstructionError::UnsupportedProgramId => "UnsupportedProgramId".to_string(),
-                InstructionError::CallDepth => "CallDepth".to_string(),
-                InstructionError::MissingAccount => "MissingAccount".to_string(),
-                InstructionError::ReentrancyNotAllowed => "ReentrancyNotAllowed".to_string(),
-                InstructionError::MaxSeedLengthExceeded => "MaxSeedLengthExceeded".to_string(),
-                InstructionError::InvalidSeeds => "InvalidSeeds".to_string(),
-                InstructionError::InvalidRealloc => "InvalidRealloc".to_string(),
-                InstructionError::ComputationalBudgetExceeded => "ComputationalBudgetExceeded".to_string(),
-                InstructionError::PrivilegeEscalation => "PrivilegeEscalation".to_string(),
-                InstructionError::ProgramEnvironmentSetupFailure => "ProgramEnvironmentSetupFailure".to_string(),
-                InstructionError::ProgramFailedToComplete => "ProgramFailedToComplete".to_string(),
-                InstructionError::ProgramFailedToCompile => "ProgramFailedToCompile".to_string(),
-                InstructionError::Immutable => "Immutable".to_string(),
-                InstructionError::IncorrectAuthority => "IncorrectAuthority".to_string(),
-                InstructionError::BorshIoError(_) => "BorshIoError".to_string(),
-                InstructionError::AccountNotRentExempt => "AccountNotRentExempt".to_string(),
-                InstructionError::InvalidAccountOwner => "InvalidAccountOwner".to_string(),
-                InstructionError::ArithmeticOverflow => "ArithmeticOverflow".to_string(),
-                InstructionError::UnsupportedSysvar => "UnsupportedSysvar".to_string(),
-                InstructionError::IllegalOwner => "IllegalOwner".to_string()
-            };
+            let decode_error = DecodeError::from_instruction_error(&instruction.program, &err);
 
             error!("{} Reason: {}", "[spi-wrapper/programs/native_stake] \
-        This stake instruction not yet supported!".to_string(), err_msg);
+        This stake instruction not yet supported!".to_string(), decode_error);
+
+            Err(decode_error)
+        }
+    }
+}
 
-            None
+/// Decodes the data of a stake account.
+pub async fn fragment_account(
+    // The record holding the account's data
+    account: AccountRecord,
+) -> Result<InstructionSet, DecodeError> {
+    let meta_properties = |meta: &Meta| vec![
+        account.property("meta", "rent_exempt_reserve", meta.rent_exempt_reserve.to_string(),
+                         ValueType::U64),
+        account.property("meta/authorized", "staker", meta.authorized.staker.to_string(),
+                         ValueType::Pubkey),
+        account.property("meta/authorized", "withdrawer", meta.authorized.withdrawer.to_string(),
+                         ValueType::Pubkey),
+        account.property("meta/lockup", "unix_timestamp", meta.lockup.unix_timestamp.to_string(),
+                         ValueType::I64),
+        account.property("meta/lockup", "epoch", meta.lockup.epoch.to_string(), ValueType::U64),
+        account.property("meta/lockup", "custodian", meta.lockup.custodian.to_string(),
+                         ValueType::Pubkey),
+    ];
+
+    match limited_deserialize::<StakeState>(account.data.as_slice()) {
+        Ok(StakeState::Uninitialized) => Ok(InstructionSet {
+            function: account.function("uninitialized"),
+            properties: vec![],
+        }),
+        Ok(StakeState::Initialized(meta)) => Ok(InstructionSet {
+            function: account.function("initialized"),
+            properties: meta_properties(&meta),
+        }),
+        Ok(StakeState::Stake(meta, stake)) => {
+            let delegation = stake.delegation;
+            let mut properties = meta_properties(&meta);
+            properties.extend(vec![
+                account.property("stake/delegation", "voter", delegation.voter_pubkey.to_string(),
+                                 ValueType::Pubkey),
+                account.property("stake/delegation", "stake", delegation.stake.to_string(),
+                                 ValueType::U64),
+                account.property("stake/delegation", "activation_epoch",
+                                 delegation.activation_epoch.to_string(), ValueType::U64),
+                account.property("stake/delegation", "deactivation_epoch",
+                                 delegation.deactivation_epoch.to_string(), ValueType::U64),
+                account.property("stake/delegation", "warmup_cooldown_rate",
+                                 delegation.warmup_cooldown_rate.to_string(), ValueType::String),
+                account.property("stake", "credits_observed", stake.credits_observed.to_string(),
+                                 ValueType::U64),
+            ]);
+
+            Ok(InstructionSet {
+                function: account.function("delegated"),
+                properties,
+            })
         }
+        Ok(StakeState::RewardsPool) => Ok(InstructionSet {
+            function: account.function("rewards-pool"),
+            properties: vec![],
+        }),
+        Err(err) => {
+            let decode_error = DecodeError::from_instruction_error(&account.owner, &err);
+            error!("[spi-wrapper/programs/native_stake] Attempt to parse account {} failed due \
+            to {}.", account.pubkey, decode_error);
+
+            Err(decode_error)
+        }
+    }
+}
+
+/// The roles of the accounts each instruction expects, in order.
+fn account_roles(instruction: &StakeInstruction) -> &'static [&'static str] {
+    match instruction {
+        StakeInstruction::Initialize(..) => &["stake_account", "rent_sysvar"],
+        StakeInstruction::Authorize(..) => &[
+            "stake_account", "clock_sysvar", "authority", "custodian",
+        ],
+        StakeInstruction::DelegateStake => &[
+            "stake_account", "vote_account", "clock_sysvar", "stake_history_sysvar",
+            "stake_config_account", "stake_authority",
+        ],
+        StakeInstruction::Split(_) => &["stake_account", "new_split_account", "stake_authority"],
+        StakeInstruction::Withdraw(_) => &[
+            "stake_account", "destination", "clock_sysvar", "stake_history_sysvar",
+            "withdraw_authority", "custodian",
+        ],
+        StakeInstruction::Deactivate => &["stake_account", "clock_sysvar", "stake_authority"],
+        StakeInstruction::SetLockup(_) => &["stake_account", "custodian"],
+        StakeInstruction::Merge => &[
+            "destination", "source", "clock_sysvar", "stake_history_sysvar", "stake_authority",
+        ],
+        StakeInstruction::AuthorizeWithSeed(_) => &[
+            "stake_account", "authority_base", "clock_sysvar", "custodian",
+        ],
+        StakeInstruction::InitializeChecked => &[
+            "stake_account", "rent_sysvar", "staker", "withdrawer",
+        ],
+        StakeInstruction::AuthorizeChecked(_) => &[
+            "stake_account", "clock_sysvar", "authority", "new_authority", "custodian",
+        ],
+        StakeInstruction::AuthorizeCheckedWithSeed(_) => &[
+            "stake_account", "authority_base", "clock_sysvar", "new_authority", "custodian",
+        ],
+        StakeInstruction::SetLockupChecked(_) => &["stake_account", "custodian", "new_custodian"],
     }
-}
\ No newline at end of file
+}
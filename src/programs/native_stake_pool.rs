@@ -0,0 +1,600 @@
+use borsh::BorshDeserialize;
+use solana_program::pubkey::Pubkey;
+use tracing::error;
+
+use crate::error::DecodeError;
+use crate::path;
+use crate::{AccountRecord, Instruction, InstructionProperty, InstructionSet, Record, ValueType};
+
+pub const PROGRAM_ADDRESS: &str = "SPoo1Ku8WFXoNDMHPsrGSTSG1Y47rzgn41SLUNakuHy";
+
+// The types below mirror the Borsh layout of the stake pool program
+// (https://github.com/solana-labs/solana-program-library/tree/master/stake-pool/program),
+// whose crate requires a newer sdk than ours.
+
+/// A fee, as the fraction `numerator / denominator` of the amount it applies to.
+#[derive(BorshDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct Fee {
+    pub denominator: u64,
+    pub numerator: u64,
+}
+
+#[derive(BorshDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum PreferredValidatorType {
+    Deposit,
+    Withdraw,
+}
+
+#[derive(BorshDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum FeeType {
+    SolReferral(u8),
+    StakeReferral(u8),
+    Epoch(Fee),
+    StakeWithdrawal(Fee),
+    SolDeposit(Fee),
+    StakeDeposit(Fee),
+    SolWithdrawal(Fee),
+}
+
+#[derive(BorshDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum FundingType {
+    StakeDeposit,
+    SolDeposit,
+    SolWithdraw,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub enum StakePoolInstruction {
+    Initialize {
+        fee: Fee,
+        withdrawal_fee: Fee,
+        deposit_fee: Fee,
+        referral_fee: u8,
+        max_validators: u32,
+    },
+    AddValidatorToPool,
+    RemoveValidatorFromPool,
+    DecreaseValidatorStake { lamports: u64, transient_stake_seed: u64 },
+    IncreaseValidatorStake { lamports: u64, transient_stake_seed: u64 },
+    SetPreferredValidator {
+        validator_type: PreferredValidatorType,
+        validator_vote_address: Option<Pubkey>,
+    },
+    UpdateValidatorListBalance { start_index: u32, no_merge: bool },
+    UpdateStakePoolBalance,
+    CleanupRemovedValidatorEntries,
+    DepositStake,
+    WithdrawStake(u64),
+    SetManager,
+    SetFee { fee: FeeType },
+    SetStaker,
+    DepositSol(u64),
+    SetFundingAuthority(FundingType),
+    WithdrawSol(u64),
+    CreateTokenMetadata { name: String, symbol: String, uri: String },
+    UpdateTokenMetadata { name: String, symbol: String, uri: String },
+}
+
+#[derive(BorshDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum AccountType {
+    Uninitialized,
+    StakePool,
+    ValidatorList,
+}
+
+/// The lockup of the stake accounts of a pool, as in `stake::state::Lockup`.
+#[derive(BorshDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct Lockup {
+    pub unix_timestamp: i64,
+    pub epoch: u64,
+    pub custodian: Pubkey,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct StakePool {
+    pub account_type: AccountType,
+    pub manager: Pubkey,
+    pub staker: Pubkey,
+    pub stake_deposit_authority: Pubkey,
+    pub stake_withdraw_bump_seed: u8,
+    pub validator_list: Pubkey,
+    pub reserve_stake: Pubkey,
+    pub pool_mint: Pubkey,
+    pub manager_fee_account: Pubkey,
+    pub token_program_id: Pubkey,
+    // The lamports held by the pool as of its last update.
+    pub total_lamports: u64,
+    pub pool_token_supply: u64,
+    pub last_update_epoch: u64,
+    pub lockup: Lockup,
+    pub epoch_fee: Fee,
+    pub next_epoch_fee: Option<Fee>,
+    pub preferred_deposit_validator_vote_address: Option<Pubkey>,
+    pub preferred_withdraw_validator_vote_address: Option<Pubkey>,
+    pub stake_deposit_fee: Fee,
+    pub stake_withdrawal_fee: Fee,
+    pub next_stake_withdrawal_fee: Option<Fee>,
+    pub stake_referral_fee: u8,
+    // Anyone may deposit sol when there is no authority.
+    pub sol_deposit_authority: Option<Pubkey>,
+    pub sol_deposit_fee: Fee,
+    pub sol_referral_fee: u8,
+    pub sol_withdraw_authority: Option<Pubkey>,
+    pub sol_withdrawal_fee: Fee,
+    pub next_sol_withdrawal_fee: Option<Fee>,
+    pub last_epoch_pool_token_supply: u64,
+    pub last_epoch_total_lamports: u64,
+}
+
+#[derive(BorshDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum StakeStatus {
+    Active,
+    DeactivatingTransient,
+    ReadyForRemoval,
+}
+
+/// A validator of a pool, whose stake accounts are derived from its vote account.
+#[derive(BorshDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct ValidatorStakeInfo {
+    pub active_stake_lamports: u64,
+    pub transient_stake_lamports: u64,
+    pub last_update_epoch: u64,
+    pub transient_seed_suffix_start: u64,
+    pub transient_seed_suffix_end: u64,
+    pub status: StakeStatus,
+    pub vote_account_address: Pubkey,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct ValidatorList {
+    pub account_type: AccountType,
+    pub max_validators: u32,
+    pub validators: Vec<ValidatorStakeInfo>,
+}
+
+/// Extracts the contents of an instruction into small bits and pieces, or what we would call,
+/// instruction_properties.
+///
+/// The function should return a list of instruction properties extracted from an instruction.
+pub async fn fragment_instruction(
+    // The instruction
+    instruction: Instruction,
+) -> Result<InstructionSet, DecodeError> {
+    let stake_pool_instruction = match StakePoolInstruction::try_from_slice(
+        instruction.data.as_slice()) {
+        Ok(stake_pool_instruction) => stake_pool_instruction,
+        Err(err) => {
+            error!("[spi-wrapper/programs/native_stake_pool] Attempt to parse instruction from \
+            program {} failed due to {}.", instruction.program, err);
+
+            return Err(DecodeError::deserialization(
+                &instruction.program, "InvalidInstructionData", &err.to_string()));
+        }
+    };
+
+    let account_properties = instruction.account_properties(
+        account_roles(&stake_pool_instruction));
+    let (function_name, mut properties): (&str, Vec<InstructionProperty>) =
+        match stake_pool_instruction {
+            StakePoolInstruction::Initialize {
+                fee, withdrawal_fee, deposit_fee, referral_fee, max_validators,
+            } => {
+                let mut properties = vec![
+                    instruction.property("", "referral_fee", referral_fee.to_string(),
+                                         ValueType::U64),
+                    instruction.property("", "max_validators", max_validators.to_string(),
+                                         ValueType::U64),
+                ];
+                properties.extend(fee_properties(&instruction, "fee", &fee));
+                properties.extend(fee_properties(&instruction, "withdrawal_fee",
+                                                 &withdrawal_fee));
+                properties.extend(fee_properties(&instruction, "deposit_fee", &deposit_fee));
+                ("initialize", properties)
+            }
+            StakePoolInstruction::AddValidatorToPool => ("add-validator-to-pool", vec![]),
+            StakePoolInstruction::RemoveValidatorFromPool => {
+                ("remove-validator-from-pool", vec![])
+            }
+            StakePoolInstruction::DecreaseValidatorStake { lamports, transient_stake_seed } => {
+                ("decrease-validator-stake", vec![
+                    instruction.property("", "lamports", lamports.to_string(), ValueType::U64),
+                    instruction.property("", "transient_stake_seed",
+                                         transient_stake_seed.to_string(), ValueType::U64),
+                ])
+            }
+            StakePoolInstruction::IncreaseValidatorStake { lamports, transient_stake_seed } => {
+                ("increase-validator-stake", vec![
+                    instruction.property("", "lamports", lamports.to_string(), ValueType::U64),
+                    instruction.property("", "transient_stake_seed",
+                                         transient_stake_seed.to_string(), ValueType::U64),
+                ])
+            }
+            StakePoolInstruction::SetPreferredValidator {
+                validator_type, validator_vote_address,
+            } => {
+                ("set-preferred-validator", vec![
+                    instruction.property("", "validator_type", format!("{:?}", validator_type),
+                                         ValueType::EnumName),
+                    // No vote address unsets the preferred validator.
+                    instruction.optional_property("", "validator_vote_address",
+                                                  validator_vote_address.map(|address| {
+                                                      address.to_string()
+                                                  }), ValueType::Pubkey),
+                ])
+            }
+            StakePoolInstruction::UpdateValidatorListBalance { start_index, no_merge } => {
+                ("update-validator-list-balance", vec![
+                    instruction.property("", "start_index", start_index.to_string(),
+                                         ValueType::U64),
+                    instruction.property("", "no_merge", no_merge.to_string(), ValueType::Bool),
+                ])
+            }
+            StakePoolInstruction::UpdateStakePoolBalance => ("update-stake-pool-balance", vec![]),
+            StakePoolInstruction::CleanupRemovedValidatorEntries => {
+                ("cleanup-removed-validator-entries", vec![])
+            }
+            StakePoolInstruction::DepositStake => ("deposit-stake", vec![]),
+            StakePoolInstruction::WithdrawStake(pool_tokens) => {
+                ("withdraw-stake", vec![
+                    instruction.property("", "pool_tokens", pool_tokens.to_string(),
+                                         ValueType::U64),
+                ])
+            }
+            StakePoolInstruction::SetManager => ("set-manager", vec![]),
+            StakePoolInstruction::SetFee { fee } => {
+                // Referral fees are a percentage, the others a fraction.
+                let (fee_type, mut properties) = match fee {
+                    FeeType::SolReferral(referral_fee) => ("SolReferral", vec![
+                        instruction.property("", "referral_fee", referral_fee.to_string(),
+                                             ValueType::U64),
+                    ]),
+                    FeeType::StakeReferral(referral_fee) => ("StakeReferral", vec![
+                        instruction.property("", "referral_fee", referral_fee.to_string(),
+                                             ValueType::U64),
+                    ]),
+                    FeeType::Epoch(fee) => ("Epoch", fee_properties(&instruction, "fee", &fee)),
+                    FeeType::StakeWithdrawal(fee) => {
+                        ("StakeWithdrawal", fee_properties(&instruction, "fee", &fee))
+                    }
+                    FeeType::SolDeposit(fee) => {
+                        ("SolDeposit", fee_properties(&instruction, "fee", &fee))
+                    }
+                    FeeType::StakeDeposit(fee) => {
+                        ("StakeDeposit", fee_properties(&instruction, "fee", &fee))
+                    }
+                    FeeType::SolWithdrawal(fee) => {
+                        ("SolWithdrawal", fee_properties(&instruction, "fee", &fee))
+                    }
+                };
+                properties.insert(0, instruction.property("", "fee_type", fee_type.to_string(),
+                                                          ValueType::EnumName));
+                ("set-fee", properties)
+            }
+            StakePoolInstruction::SetStaker => ("set-staker", vec![]),
+            StakePoolInstruction::DepositSol(lamports) => {
+                ("deposit-sol", vec![
+                    instruction.property("", "lamports", lamports.to_string(), ValueType::U64),
+                ])
+            }
+            StakePoolInstruction::SetFundingAuthority(funding_type) => {
+                ("set-funding-authority", vec![
+                    instruction.property("", "funding_type", format!("{:?}", funding_type),
+                                         ValueType::EnumName),
+                ])
+            }
+            StakePoolInstruction::WithdrawSol(pool_tokens) => {
+                ("withdraw-sol", vec![
+                    instruction.property("", "pool_tokens", pool_tokens.to_string(),
+                                         ValueType::U64),
+                ])
+            }
+            StakePoolInstruction::CreateTokenMetadata { name, symbol, uri } => {
+                ("create-token-metadata",
+                 token_metadata_properties(&instruction, &name, &symbol, &uri))
+            }
+            StakePoolInstruction::UpdateTokenMetadata { name, symbol, uri } => {
+                ("update-token-metadata",
+                 token_metadata_properties(&instruction, &name, &symbol, &uri))
+            }
+        };
+
+    properties.extend(account_properties);
+    Ok(InstructionSet {
+        function: instruction.function(function_name),
+        properties,
+    })
+}
+
+/// Decodes the data of a stake pool or validator list account.
+pub async fn fragment_account(
+    // The record holding the account's data
+    account: AccountRecord,
+) -> Result<InstructionSet, DecodeError> {
+    let account_type = account.data.first().cloned()
+        .and_then(|account_type| AccountType::try_from_slice(&[account_type]).ok());
+
+    // Both accounts are allocated ahead of their data, which leaves zeroes past it.
+    let decoded = match account_type {
+        Some(AccountType::StakePool) => StakePool::deserialize(&mut account.data.as_slice())
+            .map(|stake_pool| InstructionSet {
+                function: account.function("stake-pool"),
+                properties: stake_pool_properties(&account, &stake_pool),
+            }),
+        Some(AccountType::ValidatorList) =>
+            ValidatorList::deserialize(&mut account.data.as_slice())
+                .map(|validator_list| InstructionSet {
+                    function: account.function("validator-list"),
+                    properties: validator_list_properties(&account, &validator_list),
+                }),
+        account_type => Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("Unsupported account type {:?}.", account_type))),
+    };
+
+    decoded.map_err(|err| {
+        error!("[spi-wrapper/programs/native_stake_pool] Attempt to parse account {} failed due \
+        to {}.", account.pubkey, err);
+
+        DecodeError::deserialization(&account.owner, "ParseAccountError", &err.to_string())
+    })
+}
+
+fn stake_pool_properties(
+    account: &AccountRecord,
+    stake_pool: &StakePool,
+) -> Vec<InstructionProperty> {
+    let optional_pubkey = |pubkey: Option<Pubkey>| pubkey.map(|pubkey| pubkey.to_string());
+
+    let mut properties = vec![
+        account.property("", "manager", stake_pool.manager.to_string(), ValueType::Pubkey),
+        account.property("", "staker", stake_pool.staker.to_string(), ValueType::Pubkey),
+        account.property("", "stake_deposit_authority",
+                         stake_pool.stake_deposit_authority.to_string(), ValueType::Pubkey),
+        account.property("", "stake_withdraw_bump_seed",
+                         stake_pool.stake_withdraw_bump_seed.to_string(), ValueType::U64),
+        account.property("", "validator_list", stake_pool.validator_list.to_string(),
+                         ValueType::Pubkey),
+        account.property("", "reserve_stake", stake_pool.reserve_stake.to_string(),
+                         ValueType::Pubkey),
+        account.property("", "pool_mint", stake_pool.pool_mint.to_string(), ValueType::Pubkey),
+        account.property("", "manager_fee_account", stake_pool.manager_fee_account.to_string(),
+                         ValueType::Pubkey),
+        account.property("", "token_program_id", stake_pool.token_program_id.to_string(),
+                         ValueType::Pubkey),
+        account.property("", "total_lamports", stake_pool.total_lamports.to_string(),
+                         ValueType::U64),
+        account.property("", "pool_token_supply", stake_pool.pool_token_supply.to_string(),
+                         ValueType::U64),
+        account.property("", "last_update_epoch", stake_pool.last_update_epoch.to_string(),
+                         ValueType::U64),
+        account.property("lockup", "unix_timestamp",
+                         stake_pool.lockup.unix_timestamp.to_string(), ValueType::I64),
+        account.property("lockup", "epoch", stake_pool.lockup.epoch.to_string(),
+                         ValueType::U64),
+        account.property("lockup", "custodian", stake_pool.lockup.custodian.to_string(),
+                         ValueType::Pubkey),
+        account.optional_property(
+            "", "preferred_deposit_validator_vote_address",
+            optional_pubkey(stake_pool.preferred_deposit_validator_vote_address),
+            ValueType::Pubkey),
+        account.optional_property(
+            "", "preferred_withdraw_validator_vote_address",
+            optional_pubkey(stake_pool.preferred_withdraw_validator_vote_address),
+            ValueType::Pubkey),
+        account.property("", "stake_referral_fee", stake_pool.stake_referral_fee.to_string(),
+                         ValueType::U64),
+        account.optional_property("", "sol_deposit_authority",
+                                  optional_pubkey(stake_pool.sol_deposit_authority),
+                                  ValueType::Pubkey),
+        account.property("", "sol_referral_fee", stake_pool.sol_referral_fee.to_string(),
+                         ValueType::U64),
+        account.optional_property("", "sol_withdraw_authority",
+                                  optional_pubkey(stake_pool.sol_withdraw_authority),
+                                  ValueType::Pubkey),
+        account.property("", "last_epoch_pool_token_supply",
+                         stake_pool.last_epoch_pool_token_supply.to_string(), ValueType::U64),
+        account.property("", "last_epoch_total_lamports",
+                         stake_pool.last_epoch_total_lamports.to_string(), ValueType::U64),
+    ];
+
+    // Fees scheduled to take over at the next epoch are only listed while there are some.
+    let fees = [
+        ("epoch_fee", Some(stake_pool.epoch_fee)),
+        ("next_epoch_fee", stake_pool.next_epoch_fee),
+        ("stake_deposit_fee", Some(stake_pool.stake_deposit_fee)),
+        ("stake_withdrawal_fee", Some(stake_pool.stake_withdrawal_fee)),
+        ("next_stake_withdrawal_fee", stake_pool.next_stake_withdrawal_fee),
+        ("sol_deposit_fee", Some(stake_pool.sol_deposit_fee)),
+        ("sol_withdrawal_fee", Some(stake_pool.sol_withdrawal_fee)),
+        ("next_sol_withdrawal_fee", stake_pool.next_sol_withdrawal_fee),
+    ];
+    for (parent_key, fee) in fees.iter() {
+        if let Some(fee) = fee {
+            properties.extend(fee_properties(account, parent_key, fee));
+        }
+    }
+
+    properties
+}
+
+/// Validators are listed by their vote account, which joins them with the stake they delegate.
+fn validator_list_properties(
+    account: &AccountRecord,
+    validator_list: &ValidatorList,
+) -> Vec<InstructionProperty> {
+    let mut properties = vec![
+        account.property("", "max_validators", validator_list.max_validators.to_string(),
+                         ValueType::U64),
+    ];
+
+    for (idx, validator) in validator_list.validators.iter().enumerate() {
+        let parent_key = path::join(&["validators", &idx.to_string()]);
+        properties.extend(vec![
+            account.property(&parent_key, "vote_account_address",
+                             validator.vote_account_address.to_string(), ValueType::Pubkey),
+            account.property(&parent_key, "active_stake_lamports",
+                             validator.active_stake_lamports.to_string(), ValueType::U64),
+            account.property(&parent_key, "transient_stake_lamports",
+                             validator.transient_stake_lamports.to_string(), ValueType::U64),
+            account.property(&parent_key, "last_update_epoch",
+                             validator.last_update_epoch.to_string(), ValueType::U64),
+            account.property(&parent_key, "transient_seed_suffix_start",
+                             validator.transient_seed_suffix_start.to_string(), ValueType::U64),
+            account.property(&parent_key, "transient_seed_suffix_end",
+                             validator.transient_seed_suffix_end.to_string(), ValueType::U64),
+            account.property(&parent_key, "status", format!("{:?}", validator.status),
+                             ValueType::EnumName),
+        ]);
+    }
+
+    properties
+}
+
+fn fee_properties<R: Record>(
+    record: &R,
+    parent_key: &str,
+    fee: &Fee,
+) -> Vec<InstructionProperty> {
+    vec![
+        record.property(parent_key, "numerator", fee.numerator.to_string(), ValueType::U64),
+        record.property(parent_key, "denominator", fee.denominator.to_string(), ValueType::U64),
+    ]
+}
+
+fn token_metadata_properties(
+    instruction: &Instruction,
+    name: &str,
+    symbol: &str,
+    uri: &str,
+) -> Vec<InstructionProperty> {
+    vec![
+        instruction.property("", "name", name.to_string(), ValueType::String),
+        instruction.property("", "symbol", symbol.to_string(), ValueType::String),
+        instruction.property("", "uri", uri.to_string(), ValueType::String),
+    ]
+}
+
+/// The roles of the accounts each instruction expects, in order.
+fn account_roles(instruction: &StakePoolInstruction) -> &'static [&'static str] {
+    match instruction {
+        StakePoolInstruction::Initialize { .. } => &[
+            "stake_pool", "manager", "staker", "withdraw_authority", "validator_list",
+            "reserve_stake", "pool_mint", "manager_fee_account", "token_program",
+            "deposit_authority",
+        ],
+        StakePoolInstruction::AddValidatorToPool => &[
+            "stake_pool", "staker", "funder", "withdraw_authority", "validator_list",
+            "validator_stake", "validator_vote", "rent_sysvar", "clock_sysvar",
+            "stake_history_sysvar", "stake_config", "system_program", "stake_program",
+        ],
+        StakePoolInstruction::RemoveValidatorFromPool => &[
+            "stake_pool", "staker", "withdraw_authority", "new_stake_authority",
+            "validator_list", "validator_stake", "transient_stake", "destination_stake",
+            "clock_sysvar", "stake_program",
+        ],
+        StakePoolInstruction::DecreaseValidatorStake { .. } => &[
+            "stake_pool", "staker", "withdraw_authority", "validator_list", "validator_stake",
+            "transient_stake", "clock_sysvar", "rent_sysvar", "system_program", "stake_program",
+        ],
+        StakePoolInstruction::IncreaseValidatorStake { .. } => &[
+            "stake_pool", "staker", "withdraw_authority", "validator_list", "reserve_stake",
+            "transient_stake", "validator_vote", "clock_sysvar", "rent_sysvar",
+            "stake_history_sysvar", "stake_config", "system_program", "stake_program",
+        ],
+        StakePoolInstruction::SetPreferredValidator { .. } => &[
+            "stake_pool", "staker", "validator_list",
+        ],
+        // Followed by the validator and transient stake account of each validator updated.
+        StakePoolInstruction::UpdateValidatorListBalance { .. } => &[
+            "stake_pool", "withdraw_authority", "validator_list", "reserve_stake", "clock_sysvar",
+            "stake_history_sysvar", "stake_program", "stake_accounts*",
+        ],
+        StakePoolInstruction::UpdateStakePoolBalance => &[
+            "stake_pool", "withdraw_authority", "validator_list", "reserve_stake",
+            "manager_fee_account", "pool_mint", "token_program",
+        ],
+        StakePoolInstruction::CleanupRemovedValidatorEntries => &[
+            "stake_pool", "validator_list",
+        ],
+        StakePoolInstruction::DepositStake => &[
+            "stake_pool", "validator_list", "deposit_authority", "withdraw_authority",
+            "deposit_stake", "validator_stake", "reserve_stake", "destination_pool_account",
+            "manager_fee_account", "referral_pool_account", "pool_mint", "clock_sysvar",
+            "stake_history_sysvar", "token_program", "stake_program",
+        ],
+        StakePoolInstruction::WithdrawStake(_) => &[
+            "stake_pool", "validator_list", "withdraw_authority", "source_stake",
+            "destination_stake", "user_stake_authority", "user_transfer_authority",
+            "source_pool_account", "manager_fee_account", "pool_mint", "clock_sysvar",
+            "token_program", "stake_program",
+        ],
+        StakePoolInstruction::SetManager => &[
+            "stake_pool", "manager", "new_manager", "new_manager_fee_account",
+        ],
+        StakePoolInstruction::SetFee { .. } => &["stake_pool", "manager"],
+        StakePoolInstruction::SetStaker => &["stake_pool", "authority", "new_staker"],
+        StakePoolInstruction::DepositSol(_) => &[
+            "stake_pool", "withdraw_authority", "reserve_stake", "funder",
+            "destination_pool_account", "manager_fee_account", "referral_pool_account",
+            "pool_mint", "system_program", "token_program", "deposit_authority",
+        ],
+        StakePoolInstruction::SetFundingAuthority(_) => &[
+            "stake_pool", "manager", "new_authority",
+        ],
+        StakePoolInstruction::WithdrawSol(_) => &[
+            "stake_pool", "withdraw_authority", "user_transfer_authority", "source_pool_account",
+            "reserve_stake", "destination", "manager_fee_account", "pool_mint", "clock_sysvar",
+            "stake_history_sysvar", "stake_program", "token_program", "sol_withdraw_authority",
+        ],
+        StakePoolInstruction::CreateTokenMetadata { .. } => &[
+            "stake_pool", "manager", "withdraw_authority", "pool_mint", "payer",
+            "token_metadata", "metadata_program", "system_program", "rent_sysvar",
+        ],
+        StakePoolInstruction::UpdateTokenMetadata { .. } => &[
+            "stake_pool", "manager", "withdraw_authority", "token_metadata", "metadata_program",
+        ],
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{decode, decode_account, instruction};
+
+    #[test]
+    fn stake_pools_are_decoded() {
+        let mut set_fee = vec![12, 2];
+        set_fee.extend(&100u64.to_le_bytes());
+        set_fee.extend(&3u64.to_le_bytes());
+
+        let set_fee = decode(instruction(PROGRAM_ADDRESS, &["pool", "manager"], set_fee))
+            .to_json();
+        assert_eq!(set_fee["function_name"], "set-fee");
+        assert_eq!(set_fee["properties"]["fee_type"], "Epoch");
+        assert_eq!(set_fee["properties"]["fee"]["numerator"], 3);
+        assert_eq!(set_fee["properties"]["fee"]["denominator"], 100);
+        assert_eq!(set_fee["properties"]["accounts"]["manager"], "manager");
+
+        // A validator list with room for two validators, one of them taken.
+        let vote_account = Pubkey::new_unique();
+        let mut validator_list = vec![2];
+        validator_list.extend(&2u32.to_le_bytes());
+        validator_list.extend(&1u32.to_le_bytes());
+        validator_list.extend(&5_000_000_000u64.to_le_bytes());
+        validator_list.extend(&0u64.to_le_bytes());
+        validator_list.extend(&280u64.to_le_bytes());
+        validator_list.extend(&[0; 16]);
+        validator_list.push(1);
+        validator_list.extend(vote_account.as_ref());
+        validator_list.resize(9 + 2 * 73, 0);
+
+        let validator_list = decode_account(PROGRAM_ADDRESS, validator_list).to_json();
+        assert_eq!(validator_list["function_name"], "validator-list");
+        assert_eq!(validator_list["properties"]["max_validators"], 2);
+        let validator = &validator_list["properties"]["validators"][0];
+        assert_eq!(validator["vote_account_address"], vote_account.to_string());
+        assert_eq!(validator["active_stake_lamports"], 5_000_000_000u64);
+        assert_eq!(validator["status"], "DeactivatingTransient");
+        assert!(validator_list["properties"]["validators"].get(1).is_none());
+    }
+}
@@ -1,8 +1,12 @@
 use bincode::{deserialize};
 use solana_program::system_instruction::SystemInstruction;
+use solana_sdk::nonce::state::{State as NonceState, Versions as NonceVersions};
 use tracing::error;
 
-use crate::{InstructionProperty, Instruction, InstructionSet, InstructionFunction};
+use crate::error::DecodeError;
+use crate::{
+    AccountRecord, InstructionProperty, Instruction, InstructionSet, InstructionFunction, ValueType,
+};
 
 pub const PROGRAM_ADDRESS: &str = "11111111111111111111111111111111";
 
@@ -13,14 +17,15 @@ pub const PROGRAM_ADDRESS: &str = "11111111111111111111111111111111";
 pub async fn fragment_instruction(
     // The instruction
     instruction: Instruction
-) -> Option<InstructionSet> {
+) -> Result<InstructionSet, DecodeError> {
     let sdr = deserialize::<SystemInstruction>(
         &instruction.data.as_slice());
 
     return match sdr {
         Ok(ref sir) => {
             let si = sir.clone();
-            match si {
+            let account_properties = instruction.account_properties(account_roles(&si));
+            let instruction_set = match si {
                 SystemInstruction::CreateAccount {
                     lamports,
                     space,
@@ -37,7 +42,7 @@ pub async fn fragment_instruction(
                     //         "owner": owner.to_string(),
                     //     }),
                     // })
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -45,6 +50,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "create-account".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![
                             InstructionProperty {
@@ -53,8 +60,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "lamports".to_string(),
                                 value: lamports.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -62,8 +72,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "owner".to_string(),
                                 value: owner.to_string(),
+                                value_type: ValueType::Pubkey,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -71,8 +84,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "space".to_string(),
                                 value: space.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                         ],
                     })
@@ -86,7 +102,7 @@ pub async fn fragment_instruction(
                     //         "owner": owner.to_string(),
                     //     }),
                     // })
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -94,6 +110,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "assign".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![
                             InstructionProperty {
@@ -102,8 +120,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "owner".to_string(),
                                 value: owner.to_string(),
+                                value_type: ValueType::Pubkey,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             }
                         ],
                     })
@@ -119,7 +140,7 @@ pub async fn fragment_instruction(
                     //     }),
                     // })
                     // check_num_system_accounts(&instruction.accounts, 2)?;
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -127,6 +148,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "transfer".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![
                             InstructionProperty {
@@ -135,8 +158,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "lamports".to_string(),
                                 value: lamports.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             }
                         ],
                     })
@@ -161,7 +187,7 @@ pub async fn fragment_instruction(
                     //         "owner": owner.to_string(),
                     //     }),
                     // })
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -169,6 +195,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "create-account-with-seed".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![
                             InstructionProperty {
@@ -177,8 +205,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "base".to_string(),
                                 value: base.to_string(),
+                                value_type: ValueType::Pubkey,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -186,8 +217,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "seed".to_string(),
                                 value: seed.to_string(),
+                                value_type: ValueType::String,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -195,8 +229,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "lamports".to_string(),
                                 value: lamports.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -204,8 +241,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "space".to_string(),
                                 value: space.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -213,8 +253,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "owner".to_string(),
                                 value: owner.to_string(),
+                                value_type: ValueType::Pubkey,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                         ],
                     })
@@ -229,7 +272,7 @@ pub async fn fragment_instruction(
                     //         "nonceAuthority": account_keys[instruction.accounts[2] as usize].to_string(),
                     //     }),
                     // })
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -237,6 +280,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "advance-nonce-account".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![],
                     })
@@ -254,7 +299,7 @@ pub async fn fragment_instruction(
                     //         "lamports": lamports,
                     //     }),
                     // })
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -262,6 +307,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "withdraw-nonce-account".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![
                             InstructionProperty {
@@ -270,8 +317,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "lamports".to_string(),
                                 value: lamports.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             }
                         ],
                     })
@@ -287,7 +337,7 @@ pub async fn fragment_instruction(
                     //         "nonceAuthority": authority.to_string(),
                     //     }),
                     // })
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -295,6 +345,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "initialize-nonce-account".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![
                             InstructionProperty {
@@ -303,8 +355,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "authority".to_string(),
                                 value: authority.to_string(),
+                                value_type: ValueType::Pubkey,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             }
                         ],
                     })
@@ -319,7 +374,7 @@ pub async fn fragment_instruction(
                     //         "newAuthorized": authority.to_string(),
                     //     }),
                     // })
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -327,6 +382,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "authorize-nonce-account".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![
                             InstructionProperty {
@@ -335,8 +392,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "authority".to_string(),
                                 value: authority.to_string(),
+                                value_type: ValueType::Pubkey,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             }
                         ],
                     })
@@ -350,7 +410,7 @@ pub async fn fragment_instruction(
                     //         "space": space,
                     //     }),
                     // })
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -358,6 +418,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "allocate".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![
                             InstructionProperty {
@@ -366,8 +428,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "space".to_string(),
                                 value: space.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             }
                         ],
                     })
@@ -389,7 +454,7 @@ pub async fn fragment_instruction(
                     //         "owner": owner.to_string(),
                     //     }),
                     // })
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -397,6 +462,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "allocate-with-seed".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![
                             InstructionProperty {
@@ -405,8 +472,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "base".to_string(),
                                 value: base.to_string(),
+                                value_type: ValueType::Pubkey,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -414,8 +484,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "seed".to_string(),
                                 value: seed.to_string(),
+                                value_type: ValueType::String,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -423,8 +496,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "space".to_string(),
                                 value: space.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -432,8 +508,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "owner".to_string(),
                                 value: owner.to_string(),
+                                value_type: ValueType::Pubkey,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                         ],
                     })
@@ -449,7 +528,7 @@ pub async fn fragment_instruction(
                     //         "owner": owner.to_string(),
                     //     }),
                     // })
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -457,6 +536,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "assign-with-seed".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![
                             InstructionProperty {
@@ -465,8 +546,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "base".to_string(),
                                 value: base.to_string(),
+                                value_type: ValueType::Pubkey,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -474,8 +558,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "seed".to_string(),
                                 value: seed.to_string(),
+                                value_type: ValueType::String,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -483,8 +570,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "owner".to_string(),
                                 value: owner.to_string(),
+                                value_type: ValueType::Pubkey,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                         ],
                     })
@@ -506,7 +596,7 @@ pub async fn fragment_instruction(
                     //         "sourceOwner": from_owner.to_string(),
                     //     }),
                     // })
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -514,6 +604,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "transfer-with-seed".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![
                             InstructionProperty {
@@ -522,8 +614,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "lamports".to_string(),
                                 value: lamports.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -531,8 +626,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "from_seed".to_string(),
                                 value: from_seed.to_string(),
+                                value_type: ValueType::String,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -540,19 +638,100 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "from_owner".to_string(),
                                 value: from_owner.to_string(),
+                                value_type: ValueType::Pubkey,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                         ],
                     })
                 }
-            }
+            };
+
+            instruction_set.map(|mut set| {
+                set.properties.extend(account_properties);
+                set
+            })
         }
-        Err(_) => { // Error provided has no utility at the moment.
-            error!("{}", "[spi-wrapper/programs/native_system] Error deserializing this system \
-        instruction!".to_string());
+        Err(err) => {
+            error!("{} Reason: {}", "[spi-wrapper/programs/native_system] Error deserializing this \
+        system instruction!".to_string(), err);
 
-            None
+            Err(DecodeError::from_bincode_error(&instruction.program, &err))
         }
     }
-}
\ No newline at end of file
+}
+
+/// Decodes the data of an account owned by the system program. Only nonce accounts hold data,
+/// an account without any is a plain system (wallet) account.
+pub async fn fragment_account(
+    // The record holding the account's data
+    account: AccountRecord,
+) -> Result<InstructionSet, DecodeError> {
+    if account.data.is_empty() {
+        return Ok(InstructionSet {
+            function: account.function("system-account"),
+            properties: vec![],
+        });
+    }
+
+    match deserialize::<NonceVersions>(account.data.as_slice()) {
+        Ok(versions) => match versions.convert_to_current() {
+            NonceState::Uninitialized => Ok(InstructionSet {
+                function: account.function("nonce"),
+                properties: vec![
+                    account.property("", "state", "Uninitialized".to_string(),
+                                     ValueType::EnumName),
+                ],
+            }),
+            NonceState::Initialized(nonce_data) => Ok(InstructionSet {
+                function: account.function("nonce"),
+                properties: vec![
+                    account.property("", "state", "Initialized".to_string(),
+                                     ValueType::EnumName),
+                    account.property("", "authority", nonce_data.authority.to_string(),
+                                     ValueType::Pubkey),
+                    account.property("", "blockhash", nonce_data.blockhash.to_string(),
+                                     ValueType::String),
+                    account.property("fee_calculator", "lamports_per_signature",
+                                     nonce_data.fee_calculator.lamports_per_signature.to_string(),
+                                     ValueType::U64),
+                ],
+            }),
+        },
+        Err(err) => {
+            error!("[spi-wrapper/programs/native_system] Attempt to parse account {} failed due \
+            to {}.", account.pubkey, err);
+
+            Err(DecodeError::from_bincode_error(&account.owner, &err))
+        }
+    }
+}
+
+/// The roles of the accounts each instruction expects, in order.
+fn account_roles(instruction: &SystemInstruction) -> &'static [&'static str] {
+    match instruction {
+        SystemInstruction::CreateAccount { .. } => &["source", "new_account"],
+        SystemInstruction::Assign { .. } => &["account"],
+        SystemInstruction::Transfer { .. } => &["source", "destination"],
+        SystemInstruction::CreateAccountWithSeed { .. } => &[
+            "source", "new_account", "base_account",
+        ],
+        SystemInstruction::AdvanceNonceAccount => &[
+            "nonce_account", "recent_blockhashes_sysvar", "nonce_authority",
+        ],
+        SystemInstruction::WithdrawNonceAccount(_) => &[
+            "nonce_account", "destination", "recent_blockhashes_sysvar", "rent_sysvar",
+            "nonce_authority",
+        ],
+        SystemInstruction::InitializeNonceAccount(_) => &[
+            "nonce_account", "recent_blockhashes_sysvar", "rent_sysvar",
+        ],
+        SystemInstruction::AuthorizeNonceAccount(_) => &["nonce_account", "nonce_authority"],
+        SystemInstruction::Allocate { .. } => &["account"],
+        SystemInstruction::AllocateWithSeed { .. } => &["account", "base_account"],
+        SystemInstruction::AssignWithSeed { .. } => &["account", "base_account"],
+        SystemInstruction::TransferWithSeed { .. } => &["source", "source_base", "destination"],
+    }
+}
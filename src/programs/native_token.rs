@@ -1,9 +1,16 @@
-use solana_program::program_error::ProgramError;
-use spl_token::instruction::TokenInstruction;
+use spl_token::instruction::{AuthorityType, TokenInstruction};
 use spl_token::solana_program::program_option::COption;
+use spl_token::solana_program::program_error::ProgramError;
+use spl_token::solana_program::program_pack::Pack;
+use spl_token::solana_program::pubkey::Pubkey;
+use spl_token::state::{Account, AccountState, Mint, Multisig};
 use tracing::error;
 
-use crate::{Instruction, InstructionFunction, InstructionProperty, InstructionSet};
+use crate::error::DecodeError;
+use crate::{
+    AccountRecord, Instruction, InstructionFunction, InstructionProperty, InstructionSet, Record,
+    ValueType,
+};
 
 pub const PROGRAM_ADDRESS: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
 
@@ -14,14 +21,15 @@ pub const PROGRAM_ADDRESS: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
 pub async fn fragment_instruction(
     // The instruction
     instruction: Instruction,
-) -> Option<InstructionSet> {
+) -> Result<InstructionSet, DecodeError> {
     // We don't have anything to work with
     let tdr = TokenInstruction::unpack(instruction.data.as_slice());
 
     return match tdr {
         Ok(ref tir) => {
             let dti = tir.clone();
-            match dti {
+            let account_properties = instruction.account_properties(account_roles(&dti));
+            let instruction_set = match dti {
                 TokenInstruction::InitializeMint {
                     decimals,
                     mint_authority,
@@ -35,14 +43,16 @@ pub async fn fragment_instruction(
                     //     mint_authority,
                     //     freeze_authority,
                     // )
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
                             parent_index: instruction.parent_index.clone(),
                             program: instruction.program.clone(),
                             function_name: "initialize-mint".to_string(),
-                            timestamp: instruction.timestamp.clone()
+                            timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone()
                         },
                         properties: vec![
                             InstructionProperty {
@@ -51,8 +61,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "decimals".to_string(),
                                 value: decimals.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -60,36 +73,34 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "mint_authority".to_string(),
                                 value: mint_authority.to_string(),
+                                value_type: ValueType::Pubkey,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
-                            InstructionProperty {
-                                tx_instruction_id: instruction.tx_instruction_id.clone(),
-                                transaction_hash: instruction.transaction_hash.clone(),
-                                parent_index: instruction.parent_index.clone(),
-                                key: "freeze_authority".to_string(),
-                                value: if let COption::Some(fa) = freeze_authority {
-                                    fa.to_string()
-                                } else {
-                                    "".to_string()
-                                },
-                                parent_key: "".to_string(),
-                                timestamp: instruction.timestamp.clone(),
-                            }
+                            instruction.optional_property(
+                                "",
+                                "freeze_authority",
+                                optional_pubkey(freeze_authority),
+                                ValueType::Pubkey,
+                            ),
                         ]
                     })
                 }
                 TokenInstruction::InitializeAccount => {
                     // msg!("Instruction: InitializeAccount");
                     // Self::process_initialize_account(accounts)
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
                             parent_index: instruction.parent_index.clone(),
                             program: instruction.program.clone(),
                             function_name: "initialize-account".to_string(),
-                            timestamp: instruction.timestamp.clone()
+                            timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone()
                         },
                         properties: vec![]
                     })
@@ -97,14 +108,16 @@ pub async fn fragment_instruction(
                 TokenInstruction::InitializeAccount2 { owner } => {
                     // msg!("Instruction: InitializeAccount2");
                     // Self::process_initialize_account2(accounts, owner)
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
                             parent_index: instruction.parent_index.clone(),
                             program: instruction.program.clone(),
                             function_name: "initialize-account-2".to_string(),
-                            timestamp: instruction.timestamp.clone()
+                            timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone()
                         },
                         properties: vec![
                             InstructionProperty {
@@ -113,8 +126,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "owner".to_string(),
                                 value: owner.to_string(),
+                                value_type: ValueType::Pubkey,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             }
                         ]
                     })
@@ -122,14 +138,16 @@ pub async fn fragment_instruction(
                 TokenInstruction::InitializeMultisig { m } => {
                     // msg!("Instruction: InitializeMultisig");
                     // Self::process_initialize_multisig(accounts, m)
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
                             parent_index: instruction.parent_index.clone(),
                             program: instruction.program.clone(),
                             function_name: "initialize-multisig".to_string(),
-                            timestamp: instruction.timestamp.clone()
+                            timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone()
                         },
                         properties: vec![
                             InstructionProperty {
@@ -138,8 +156,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "m".to_string(),
                                 value: m.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             }
                         ]
                     })
@@ -147,14 +168,16 @@ pub async fn fragment_instruction(
                 TokenInstruction::Transfer { amount } => {
                     // msg!("Instruction: Transfer");
                     // Self::process_transfer(program_id, accounts, amount, None)
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
                             parent_index: instruction.parent_index.clone(),
                             program: instruction.program.clone(),
                             function_name: "transfer".to_string(),
-                            timestamp: instruction.timestamp.clone()
+                            timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone()
                         },
                         properties: vec![
                             InstructionProperty {
@@ -163,8 +186,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "amount".to_string(),
                                 value: amount.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             }
                         ]
                     })
@@ -172,14 +198,16 @@ pub async fn fragment_instruction(
                 TokenInstruction::Approve { amount } => {
                     // msg!("Instruction: Approve");
                     // Self::process_approve(program_id, accounts, amount, None)
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
                             parent_index: instruction.parent_index.clone(),
                             program: instruction.program.clone(),
                             function_name: "approve".to_string(),
-                            timestamp: instruction.timestamp.clone()
+                            timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone()
                         },
                         properties: vec![
                             InstructionProperty {
@@ -188,8 +216,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "amount".to_string(),
                                 value: amount.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             }
                         ]
                     })
@@ -197,14 +228,16 @@ pub async fn fragment_instruction(
                 TokenInstruction::Revoke => {
                     // msg!("Instruction: Revoke");
                     // Self::process_revoke(program_id, accounts)
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
                             parent_index: instruction.parent_index.clone(),
                             program: instruction.program.clone(),
                             function_name: "revoke".to_string(),
-                            timestamp: instruction.timestamp.clone()
+                            timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone()
                         },
                         properties: vec![]
                     })
@@ -220,14 +253,16 @@ pub async fn fragment_instruction(
                     //     authority_type,
                     //     new_authority,
                     // )
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
                             parent_index: instruction.parent_index.clone(),
                             program: instruction.program.clone(),
                             function_name: "set-authority".to_string(),
-                            timestamp: instruction.timestamp.clone()
+                            timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone()
                         },
                         properties: vec![
                             InstructionProperty {
@@ -235,37 +270,40 @@ pub async fn fragment_instruction(
                                 transaction_hash: instruction.transaction_hash.clone(),
                                 parent_index: instruction.parent_index.clone(),
                                 key: "authority_type".to_string(),
-                                value: (authority_type as u8).to_string(),
-                                parent_key: "".to_string(),
-                                timestamp: instruction.timestamp.clone(),
-                            },
-                            InstructionProperty {
-                                tx_instruction_id: instruction.tx_instruction_id.clone(),
-                                transaction_hash: instruction.transaction_hash.clone(),
-                                parent_index: instruction.parent_index.clone(),
-                                key: "new_authority".to_string(),
-                                value: if let COption::Some(na) = new_authority {
-                                    na.to_string()
-                                } else {
-                                    "".to_string()
+                                value: match authority_type {
+                                    AuthorityType::MintTokens => "MintTokens".to_string(),
+                                    AuthorityType::FreezeAccount => "FreezeAccount".to_string(),
+                                    AuthorityType::AccountOwner => "AccountOwner".to_string(),
+                                    AuthorityType::CloseAccount => "CloseAccount".to_string(),
                                 },
+                                value_type: ValueType::EnumName,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
-                            }
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
+                            },
+                            instruction.optional_property(
+                                "",
+                                "new_authority",
+                                optional_pubkey(new_authority),
+                                ValueType::Pubkey,
+                            ),
                         ]
                     })
                 }
                 TokenInstruction::MintTo { amount } => {
                     // msg!("Instruction: MintTo");
                     // Self::process_mint_to(program_id, accounts, amount, None)
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
                             parent_index: instruction.parent_index.clone(),
                             program: instruction.program.clone(),
                             function_name: "mint-to".to_string(),
-                            timestamp: instruction.timestamp.clone()
+                            timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone()
                         },
                         properties: vec![
                             InstructionProperty {
@@ -274,8 +312,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "amount".to_string(),
                                 value: amount.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             }
                         ]
                     })
@@ -283,14 +324,16 @@ pub async fn fragment_instruction(
                 TokenInstruction::Burn { amount } => {
                     // msg!("Instruction: Burn");
                     // Self::process_burn(program_id, accounts, amount, None)
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
                             parent_index: instruction.parent_index.clone(),
                             program: instruction.program.clone(),
                             function_name: "burn".to_string(),
-                            timestamp: instruction.timestamp.clone()
+                            timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone()
                         },
                         properties: vec![
                             InstructionProperty {
@@ -299,8 +342,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "amount".to_string(),
                                 value: amount.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             }
                         ]
                     })
@@ -308,14 +354,16 @@ pub async fn fragment_instruction(
                 TokenInstruction::CloseAccount => {
                     // msg!("Instruction: CloseAccount");
                     // Self::process_close_account(program_id, accounts)
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
                             parent_index: instruction.parent_index.clone(),
                             program: instruction.program.clone(),
                             function_name: "close-account".to_string(),
-                            timestamp: instruction.timestamp.clone()
+                            timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone()
                         },
                         properties: vec![]
                     })
@@ -323,14 +371,16 @@ pub async fn fragment_instruction(
                 TokenInstruction::FreezeAccount => {
                     // msg!("Instruction: FreezeAccount");
                     // Self::process_toggle_freeze_account(program_id, accounts, true)
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
                             parent_index: instruction.parent_index.clone(),
                             program: instruction.program.clone(),
                             function_name: "freeze-account".to_string(),
-                            timestamp: instruction.timestamp.clone()
+                            timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone()
                         },
                         properties: vec![]
                     })
@@ -338,14 +388,16 @@ pub async fn fragment_instruction(
                 TokenInstruction::ThawAccount => {
                     // msg!("Instruction: ThawAccount");
                     // Self::process_toggle_freeze_account(program_id, accounts, false)
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
                             parent_index: instruction.parent_index.clone(),
                             program: instruction.program.clone(),
                             function_name: "thaw-account".to_string(),
-                            timestamp: instruction.timestamp.clone()
+                            timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone()
                         },
                         properties: vec![]
                     })
@@ -353,14 +405,16 @@ pub async fn fragment_instruction(
                 TokenInstruction::TransferChecked { amount, decimals } => {
                     // msg!("Instruction: TransferChecked");
                     // Self::process_transfer(program_id, accounts, amount, Some(decimals))
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
                             parent_index: instruction.parent_index.clone(),
                             program: instruction.program.clone(),
                             function_name: "transfer-checked".to_string(),
-                            timestamp: instruction.timestamp.clone()
+                            timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone()
                         },
                         properties: vec![
                             InstructionProperty {
@@ -369,8 +423,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "amount".to_string(),
                                 value: amount.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -378,8 +435,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "decimals".to_string(),
                                 value: decimals.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             }
                         ]
                     })
@@ -387,14 +447,16 @@ pub async fn fragment_instruction(
                 TokenInstruction::ApproveChecked { amount, decimals } => {
                     // msg!("Instruction: ApproveChecked");
                     // Self::process_approve(program_id, accounts, amount, Some(decimals))
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
                             parent_index: instruction.parent_index.clone(),
                             program: instruction.program.clone(),
                             function_name: "approve-checked".to_string(),
-                            timestamp: instruction.timestamp.clone()
+                            timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone()
                         },
                         properties: vec![
                             InstructionProperty {
@@ -403,8 +465,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "amount".to_string(),
                                 value: amount.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -412,8 +477,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "decimals".to_string(),
                                 value: decimals.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             }
                         ]
                     })
@@ -421,14 +489,16 @@ pub async fn fragment_instruction(
                 TokenInstruction::MintToChecked { amount, decimals } => {
                     // msg!("Instruction: MintToChecked");
                     // Self::process_mint_to(program_id, accounts, amount, Some(decimals))
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
                             parent_index: instruction.parent_index.clone(),
                             program: instruction.program.clone(),
                             function_name: "mint-to-checked".to_string(),
-                            timestamp: instruction.timestamp.clone()
+                            timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone()
                         },
                         properties: vec![
                             InstructionProperty {
@@ -437,8 +507,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "amount".to_string(),
                                 value: amount.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -446,8 +519,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "decimals".to_string(),
                                 value: decimals.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             }
                         ]
                     })
@@ -455,14 +531,16 @@ pub async fn fragment_instruction(
                 TokenInstruction::BurnChecked { amount, decimals } => {
                     // msg!("Instruction: BurnChecked");
                     // Self::process_burn(program_id, accounts, amount, Some(decimals))
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
                             parent_index: instruction.parent_index.clone(),
                             program: instruction.program.clone(),
                             function_name: "burn-checked".to_string(),
-                            timestamp: instruction.timestamp.clone()
+                            timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone()
                         },
                         properties: vec![
                             InstructionProperty {
@@ -471,8 +549,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "amount".to_string(),
                                 value: amount.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -480,52 +561,205 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "decimals".to_string(),
                                 value: decimals.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             }
                         ]
                     })
                 }
                 TokenInstruction::SyncNative => {
                     // msg!("Instruction: SyncNative");
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
                             parent_index: instruction.parent_index.clone(),
                             program: instruction.program.clone(),
                             function_name: "sync-native".to_string(),
-                            timestamp: instruction.timestamp.clone()
+                            timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone()
                         },
                         properties: vec![]
                     })
                 }
-            }
+            };
+
+            instruction_set.map(|mut set| {
+                set.properties.extend(account_properties);
+                set
+            })
         }
         Err(err) => {
-            let err_msg = match err {
-                ProgramError::Custom(_) => "Custom".to_string(),
-                ProgramError::InvalidArgument => "InvalidArgument".to_string(),
-                ProgramError::InvalidInstructionData => "InvalidInstructionData".to_string(),
-                ProgramError::InvalidAccountData => "InvalidAccountData".to_string(),
-                ProgramError::AccountDataTooSmall => "AccountDataTooSmall".to_string(),
-                ProgramError::InsufficientFunds => "InsufficientFunds".to_string(),
-                ProgramError::IncorrectProgramId => "IncorrectProgramId".to_string(),
-                ProgramError::MissingRequiredSignature => "MissingRequiredSignature".to_string(),
-                ProgramError::AccountAlreadyInitialized => "AccountAlreadyInitialized".to_string(),
-                ProgramError::UninitializedAccount => "UninitializedAccount".to_string(),
-                ProgramError::NotEnoughAccountKeys => "NotEnoughAccountKeys".to_string(),
-                ProgramError::AccountBorrowFailed => "AccountBorrowFailed".to_string(),
-                ProgramError::MaxSeedLengthExceeded => "MaxSeedLengthExceeded".to_string(),
-                ProgramError::InvalidSeeds => "InvalidSeeds".to_string(),
-                ProgramError::BorshIoError(_) => "BorshIoError".to_string(),
-                ProgramError::AccountNotRentExempt => "AccountNotRentExempt".to_string(),
-                ProgramError::UnsupportedSysvar => "UnsupportedSysvar".to_string(),
-                ProgramError::IllegalOwner => "IllegalOwner".to_string()
-            };
+            let decode_error = DecodeError::from_program_error(&instruction.program, &err);
 
-            error!("{} Reason: {}", "Invalid instruction for the token program.".to_string(), err_msg);
-            None
+            error!("{} Reason: {}", "Invalid instruction for the token program.".to_string(), decode_error);
+            Err(decode_error)
         }
     }
-}
\ No newline at end of file
+}
+
+/// Decodes the data of an account owned by the token program: a token account, a mint or a
+/// multisig, told apart by the length of their data.
+pub async fn fragment_account(
+    // The record holding the account's data
+    account: AccountRecord,
+) -> Result<InstructionSet, DecodeError> {
+    let data = account.data.as_slice();
+
+    let instruction_set = match data.len() {
+        Account::LEN => Account::unpack_unchecked(data).map(|token_account| InstructionSet {
+            function: account.function("token-account"),
+            properties: vec![
+                account.property("", "mint", token_account.mint.to_string(), ValueType::Pubkey),
+                account.property("", "owner", token_account.owner.to_string(), ValueType::Pubkey),
+                account.property("", "amount", token_account.amount.to_string(), ValueType::U64),
+                account.optional_property("", "delegate", optional_pubkey(token_account.delegate),
+                                          ValueType::Pubkey),
+                account.property("", "state", match token_account.state {
+                    AccountState::Uninitialized => "Uninitialized".to_string(),
+                    AccountState::Initialized => "Initialized".to_string(),
+                    AccountState::Frozen => "Frozen".to_string(),
+                }, ValueType::EnumName),
+                account.property("", "is_native", token_account.is_native.is_some().to_string(),
+                                 ValueType::Bool),
+                account.optional_property("", "rent_exempt_reserve", match token_account.is_native {
+                    COption::Some(reserve) => Some(reserve.to_string()),
+                    COption::None => None,
+                }, ValueType::U64),
+                account.property("", "delegated_amount",
+                                 token_account.delegated_amount.to_string(), ValueType::U64),
+                account.optional_property("", "close_authority",
+                                          optional_pubkey(token_account.close_authority),
+                                          ValueType::Pubkey),
+            ],
+        }),
+        Mint::LEN => Mint::unpack_unchecked(data).map(|mint| InstructionSet {
+            function: account.function("mint"),
+            properties: vec![
+                account.optional_property("", "mint_authority",
+                                          optional_pubkey(mint.mint_authority), ValueType::Pubkey),
+                account.property("", "supply", mint.supply.to_string(), ValueType::U64),
+                account.property("", "decimals", mint.decimals.to_string(), ValueType::U64),
+                account.property("", "is_initialized", mint.is_initialized.to_string(),
+                                 ValueType::Bool),
+                account.optional_property("", "freeze_authority",
+                                          optional_pubkey(mint.freeze_authority),
+                                          ValueType::Pubkey),
+            ],
+        }),
+        Multisig::LEN => Multisig::unpack_unchecked(data).map(|multisig| {
+            let mut properties = vec![
+                account.property("", "m", multisig.m.to_string(), ValueType::U64),
+                account.property("", "n", multisig.n.to_string(), ValueType::U64),
+                account.property("", "is_initialized", multisig.is_initialized.to_string(),
+                                 ValueType::Bool),
+            ];
+            properties.extend(multisig.signers.iter()
+                .take(multisig.n as usize)
+                .enumerate()
+                .map(|(idx, signer)| account.property(
+                    "signers", &idx.to_string(), signer.to_string(), ValueType::Pubkey)));
+
+            InstructionSet {
+                function: account.function("multisig"),
+                properties,
+            }
+        }),
+        _ => Err(ProgramError::InvalidAccountData),
+    };
+
+    instruction_set.map_err(|err| {
+        let decode_error = DecodeError::from_program_error(&account.owner, &err);
+        error!("[spi-wrapper/programs/native_token] Attempt to parse account {} failed due to \
+        {}.", account.pubkey, err);
+
+        decode_error
+    })
+}
+
+/// An optional public key, as the value of an optional property.
+fn optional_pubkey(key: COption<Pubkey>) -> Option<String> {
+    match key {
+        COption::Some(key) => Some(key.to_string()),
+        COption::None => None,
+    }
+}
+
+/// The roles of the accounts each instruction expects, in order.
+fn account_roles(instruction: &TokenInstruction) -> &'static [&'static str] {
+    match instruction {
+        TokenInstruction::InitializeMint { .. } => &["mint", "rent_sysvar"],
+        TokenInstruction::InitializeAccount => &["account", "mint", "owner", "rent_sysvar"],
+        TokenInstruction::InitializeAccount2 { .. } => &["account", "mint", "rent_sysvar"],
+        TokenInstruction::InitializeMultisig { .. } => &["multisig", "rent_sysvar", "signers*"],
+        TokenInstruction::Transfer { .. } => &["source", "destination", "authority", "signers*"],
+        TokenInstruction::Approve { .. } => &["source", "delegate", "owner", "signers*"],
+        TokenInstruction::Revoke => &["source", "owner", "signers*"],
+        TokenInstruction::SetAuthority { .. } => &["account", "authority", "signers*"],
+        TokenInstruction::MintTo { .. } => &["mint", "account", "mint_authority", "signers*"],
+        TokenInstruction::Burn { .. } => &["account", "mint", "authority", "signers*"],
+        TokenInstruction::CloseAccount => &["account", "destination", "owner", "signers*"],
+        TokenInstruction::FreezeAccount => &["account", "mint", "freeze_authority", "signers*"],
+        TokenInstruction::ThawAccount => &["account", "mint", "freeze_authority", "signers*"],
+        TokenInstruction::TransferChecked { .. } => &[
+            "source", "mint", "destination", "authority", "signers*",
+        ],
+        TokenInstruction::ApproveChecked { .. } => &[
+            "source", "mint", "delegate", "owner", "signers*",
+        ],
+        TokenInstruction::MintToChecked { .. } => &[
+            "mint", "account", "mint_authority", "signers*",
+        ],
+        TokenInstruction::BurnChecked { .. } => &["account", "mint", "authority", "signers*"],
+        TokenInstruction::SyncNative => &["account"],
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{decode, decode_account, instruction};
+    use crate::ACCOUNTS_PARENT_KEY;
+
+    #[test]
+    fn properties_carry_their_value_type() {
+        let data = TokenInstruction::SetAuthority {
+            authority_type: AuthorityType::CloseAccount,
+            new_authority: COption::None,
+        }.pack();
+        let set = decode(instruction(PROGRAM_ADDRESS, &["account", "owner"], data));
+
+        let authority_type = set.properties.iter()
+            .find(|property| property.key == "authority_type")
+            .unwrap();
+        assert_eq!(authority_type.value, "CloseAccount");
+        assert_eq!(authority_type.value_type, ValueType::EnumName);
+        assert!(set.properties.iter()
+            .filter(|property| property.parent_key == ACCOUNTS_PARENT_KEY)
+            .all(|property| property.value_type == ValueType::Pubkey));
+    }
+
+    #[test]
+    fn unset_authorities_are_null() {
+        let mut data = vec![0; Mint::LEN];
+        Mint::pack(Mint {
+            mint_authority: COption::Some(Pubkey::new_unique()),
+            supply: 42,
+            decimals: 6,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        }, &mut data).unwrap();
+
+        let mint = decode_account(PROGRAM_ADDRESS, data).state;
+        let property = |key: &str| mint.properties.iter()
+            .find(|property| property.key == key)
+            .unwrap();
+        assert_eq!(property("mint_authority").value_type, ValueType::Pubkey);
+        assert_eq!(property("freeze_authority").value_type, ValueType::Null);
+        assert!(mint.to_json()["properties"]["freeze_authority"].is_null());
+    }
+}
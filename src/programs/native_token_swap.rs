@@ -1,16 +1,23 @@
-use spl_token_swap::curve::base::CurveType;
+use arrayref::array_ref;
+use spl_token_swap::curve::base::{CurveType, SwapCurve};
+use spl_token_swap::curve::fees::Fees;
 use spl_token_swap::instruction::{unpack, SwapInstruction};
-use spl_token_swap::solana_program::program_error::ProgramError;
+use spl_token_swap::solana_program::program_pack::Pack;
+use spl_token_swap::state::SwapVersion;
 use tracing::error;
 
-use crate::{Instruction, InstructionFunction, InstructionProperty, InstructionSet};
+use crate::error::DecodeError;
+use crate::{
+    AccountRecord, Instruction, InstructionFunction, InstructionProperty, InstructionSet, Record,
+    ValueType,
+};
 
 pub const PROGRAM_ADDRESS: &str = "SwaPpA9LAaLfeLi3a68M4DjnLqgtticKg6CnyNwgAC8";
 
 pub async fn fragment_instruction(
     // The instruction in question.
     instruction: Instruction
-) -> Option<InstructionSet> {
+) -> Result<InstructionSet, DecodeError> {
     // Unpack the instruction via the spl_token_swap library
     let unpack_result = unpack::<SwapInstruction>(
         instruction.data.as_slice());
@@ -18,12 +25,12 @@ pub async fn fragment_instruction(
     return match unpack_result {
         Ok(ref tsi) => {
             let token_swap_instruction = tsi.clone();
-            match token_swap_instruction {
+            let account_properties = instruction.account_properties(
+                account_roles(&token_swap_instruction));
+            let instruction_set: Result<InstructionSet, DecodeError> =
+                match token_swap_instruction {
                 SwapInstruction::Initialize(initialize_instruction) => {
-                    // The actual calculator will not be indexed.
-                    // initialize_instruction.swap_curve.calculator
-
-                    Option::from(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -31,6 +38,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "initialize".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![
                             InstructionProperty {
@@ -39,8 +48,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "host_fee_numerator".to_string(),
                                 value: (&initialize_instruction.fees.host_fee_numerator).to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "fees".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -48,8 +60,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "owner_trade_fee_numerator".to_string(),
                                 value: (&initialize_instruction.fees.owner_trade_fee_numerator).to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "fees".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -59,8 +74,11 @@ pub async fn fragment_instruction(
                                 value:
                                 (&initialize_instruction.fees.owner_trade_fee_denominator)
                                     .to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "fees".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -70,8 +88,11 @@ pub async fn fragment_instruction(
                                 value:
                                 (&initialize_instruction.fees.owner_withdraw_fee_numerator)
                                     .to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "fees".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -81,8 +102,11 @@ pub async fn fragment_instruction(
                                 value:
                                 (&initialize_instruction.fees.owner_withdraw_fee_denominator)
                                     .to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "fees".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -91,8 +115,11 @@ pub async fn fragment_instruction(
                                 key: "trade_fee_numerator".to_string(),
                                 value:
                                 (&initialize_instruction.fees.trade_fee_numerator).to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "fees".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -100,8 +127,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "nonce".to_string(),
                                 value: (&initialize_instruction.nonce).to_string(),
-                                parent_key: "initialize_instruction".to_string(),
+                                value_type: ValueType::U64,
+                                parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -110,40 +140,33 @@ pub async fn fragment_instruction(
                                 key: "trade_fee_denominator".to_string(),
                                 value:
                                 (&initialize_instruction.fees.trade_fee_denominator).to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "fees".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
                                 transaction_hash: instruction.transaction_hash.clone(),
                                 parent_index: instruction.parent_index.clone(),
                                 key: "curve_type".to_string(),
-                                value: match initialize_instruction.swap_curve.curve_type {
-                                    CurveType::ConstantProduct => "ConstantProduct".to_string(),
-                                    // Flat line, always providing 1:1 from one token to another
-                                    CurveType::ConstantPrice => "ConstantPrice".to_string(),
-                                    // Stable, like uniswap, but with wide zone of 1:1 instead of one point
-                                    CurveType::Stable => "Stable".to_string(),
-                                    // Offset curve, like Uniswap, but the token B side has a faked offset
-                                    CurveType::Offset => "Offset".to_string(),
-                                },
+                                value: curve_type_name(
+                                    &initialize_instruction.swap_curve.curve_type),
+                                value_type: ValueType::EnumName,
                                 parent_key: "swap_curve".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
-                            // InstructionProperty {
-                            //     tx_instruction_id: instruction.tx_instruction_id.clone(),
-                            //     transaction_hash: instruction.transaction_hash.clone(),
-                            //     parent_index: instruction.parent_index.clone(),
-                            //     key: "calculator".to_string(),
-                            //     value: initialize_instruction.swap_curve.calculator.to_string(),
-                            //     parent_key: "swap_curve".to_string(),
-                            //     timestamp: instruction.timestamp.clone(),
-                            // },
-                        ],
+                        ].into_iter()
+                            .chain(calculator_properties(&instruction, "swap_curve/calculator",
+                                                         &initialize_instruction.swap_curve))
+                            .collect(),
                     })
                 }
                 SwapInstruction::Swap(swap) => {
-                    Option::from(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -151,6 +174,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "swap".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![
                             InstructionProperty {
@@ -159,8 +184,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "amount_in".to_string(),
                                 value: swap.amount_in.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -168,14 +196,17 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "minimum_amount_out".to_string(),
                                 value: swap.minimum_amount_out.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             }
                         ],
                     })
                 }
                 SwapInstruction::DepositAllTokenTypes(datt) => {
-                    Option::from(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -183,6 +214,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "deposit-all-token-types".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![
                             InstructionProperty {
@@ -191,8 +224,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "pool_token_amount".to_string(),
                                 value: datt.pool_token_amount.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -200,8 +236,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "maximum_token_a_amount".to_string(),
                                 value: datt.maximum_token_a_amount.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -209,21 +248,26 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "maximum_token_b_amount".to_string(),
                                 value: datt.maximum_token_b_amount.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                         ],
                     })
                 }
                 SwapInstruction::WithdrawAllTokenTypes(watt) => {
-                    Option::from(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
                             parent_index: instruction.parent_index.clone(),
                             program: instruction.program.clone(),
                             function_name: "withdraw-all-token-types".to_string(),
-                            timestamp: instruction.timestamp.clone()
+                            timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone()
                         },
                         properties: vec![
                             InstructionProperty {
@@ -232,8 +276,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "pool_token_amount".to_string(),
                                 value: watt.pool_token_amount.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -241,8 +288,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "minimum_token_a_amount".to_string(),
                                 value: watt.minimum_token_a_amount.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -250,21 +300,26 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "minimum_token_b_amount".to_string(),
                                 value: watt.minimum_token_b_amount.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                         ],
                     })
                 }
                 SwapInstruction::DepositSingleTokenTypeExactAmountIn(dstteai) => {
-                    Option::from(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
                             parent_index: instruction.parent_index.clone(),
                             program: instruction.program.clone(),
                             function_name: "deposit-single-token-type-exact-amount-in".to_string(),
-                            timestamp: instruction.timestamp.clone()
+                            timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone()
                         },
                         properties: vec![
                             InstructionProperty {
@@ -273,8 +328,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "minimum_pool_token_amount".to_string(),
                                 value: dstteai.minimum_pool_token_amount.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -282,21 +340,26 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "source_token_amount".to_string(),
                                 value: dstteai.source_token_amount.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                         ],
                     })
                 }
                 SwapInstruction::WithdrawSingleTokenTypeExactAmountOut(wstteao) => {
-                    Option::from(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
                             parent_index: instruction.parent_index.clone(),
                             program: instruction.program.clone(),
                             function_name: "withdraw-single-token-type-exact-amount-out".to_string(),
-                            timestamp: instruction.timestamp.clone()
+                            timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone()
                         },
                         properties: vec![
                             InstructionProperty {
@@ -305,8 +368,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "maximum_pool_token_amount".to_string(),
                                 value: wstteao.maximum_pool_token_amount.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -314,40 +380,209 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "destination_token_amount".to_string(),
                                 value: wstteao.destination_token_amount.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                         ],
                     })
                 }
-            }
+            };
+
+            instruction_set.map(|mut set| {
+                set.properties.extend(account_properties);
+                set
+            })
         }
         Err(err) => {
-            let err_msg = match err {
-                ProgramError::Custom(_) => "Custom".to_string(),
-                ProgramError::InvalidArgument => "InvalidArgument".to_string(),
-                ProgramError::InvalidInstructionData => "InvalidInstructionData".to_string(),
-                ProgramError::InvalidAccountData => "InvalidAccountData".to_string(),
-                ProgramError::AccountDataTooSmall => "AccountDataTooSmall".to_string(),
-                ProgramError::InsufficientFunds => "InsufficientFunds".to_string(),
-                ProgramError::IncorrectProgramId => "IncorrectProgramId".to_string(),
-                ProgramError::MissingRequiredSignature => "MissingRequiredSignature".to_string(),
-                ProgramError::AccountAlreadyInitialized => "AccountAlreadyInitialized".to_string(),
-                ProgramError::UninitializedAccount => "UninitializedAccount".to_string(),
-                ProgramError::NotEnoughAccountKeys => "NotEnoughAccountKeys".to_string(),
-                ProgramError::AccountBorrowFailed => "AccountBorrowFailed".to_string(),
-                ProgramError::MaxSeedLengthExceeded => "MaxSeedLengthExceeded".to_string(),
-                ProgramError::InvalidSeeds => "InvalidSeeds".to_string(),
-                ProgramError::BorshIoError(_) => "BorshIoError".to_string(),
-                ProgramError::AccountNotRentExempt => "AccountNotRentExempt".to_string(),
-                ProgramError::UnsupportedSysvar => "UnsupportedSysvar".to_string(),
-                ProgramError::IllegalOwner => "IllegalOwner".to_string()
-            };
+            let decode_error = DecodeError::from_program_error(&instruction.program, &err);
 
             error!("{} Reason: {}", "[processors/programs/native_token_swap] FATAL: Unrecognised instruction.".to_string(),
-            err_msg);
+            decode_error);
 
-            None
+            Err(decode_error)
         }
     }
-}
\ No newline at end of file
+}
+
+/// Decodes the data of a swap pool account.
+pub async fn fragment_account(
+    // The record holding the account's data
+    account: AccountRecord,
+) -> Result<InstructionSet, DecodeError> {
+    match SwapVersion::unpack(account.data.as_slice()) {
+        Ok(swap) => {
+            let mut properties = vec![
+                // Only `SwapV1` exists so far, it is tagged 1.
+                account.property("", "version", account.data[0].to_string(), ValueType::U64),
+                account.property("", "is_initialized", swap.is_initialized().to_string(),
+                                 ValueType::Bool),
+                account.property("", "nonce", swap.nonce().to_string(), ValueType::U64),
+                account.property("", "token_program_id", swap.token_program_id().to_string(),
+                                 ValueType::Pubkey),
+                account.property("", "token_a", swap.token_a_account().to_string(),
+                                 ValueType::Pubkey),
+                account.property("", "token_b", swap.token_b_account().to_string(),
+                                 ValueType::Pubkey),
+                account.property("", "pool_mint", swap.pool_mint().to_string(), ValueType::Pubkey),
+                account.property("", "token_a_mint", swap.token_a_mint().to_string(),
+                                 ValueType::Pubkey),
+                account.property("", "token_b_mint", swap.token_b_mint().to_string(),
+                                 ValueType::Pubkey),
+                account.property("", "pool_fee_account", swap.pool_fee_account().to_string(),
+                                 ValueType::Pubkey),
+            ];
+            properties.extend(fees_properties(&account, swap.fees()));
+            properties.push(account.property("swap_curve", "curve_type",
+                                             curve_type_name(&swap.swap_curve().curve_type),
+                                             ValueType::EnumName));
+            properties.extend(calculator_properties(&account, "swap_curve/calculator",
+                                                    swap.swap_curve()));
+
+            Ok(InstructionSet {
+                function: account.function("swap-v1"),
+                properties,
+            })
+        }
+        Err(err) => {
+            let decode_error = DecodeError::from_program_error(&account.owner, &err);
+            error!("[spi-wrapper/programs/native_token_swap] Attempt to parse account {} failed \
+            due to {}.", account.pubkey, decode_error);
+
+            Err(decode_error)
+        }
+    }
+}
+
+fn fees_properties(account: &AccountRecord, fees: &Fees) -> Vec<InstructionProperty> {
+    vec![
+        account.property("fees", "trade_fee_numerator", fees.trade_fee_numerator.to_string(),
+                         ValueType::U64),
+        account.property("fees", "trade_fee_denominator", fees.trade_fee_denominator.to_string(),
+                         ValueType::U64),
+        account.property("fees", "owner_trade_fee_numerator",
+                         fees.owner_trade_fee_numerator.to_string(), ValueType::U64),
+        account.property("fees", "owner_trade_fee_denominator",
+                         fees.owner_trade_fee_denominator.to_string(), ValueType::U64),
+        account.property("fees", "owner_withdraw_fee_numerator",
+                         fees.owner_withdraw_fee_numerator.to_string(), ValueType::U64),
+        account.property("fees", "owner_withdraw_fee_denominator",
+                         fees.owner_withdraw_fee_denominator.to_string(), ValueType::U64),
+        account.property("fees", "host_fee_numerator", fees.host_fee_numerator.to_string(),
+                         ValueType::U64),
+        account.property("fees", "host_fee_denominator", fees.host_fee_denominator.to_string(),
+                         ValueType::U64),
+    ]
+}
+
+/// The parameter of the curve's calculator, if it has one. The calculator is only reachable as a
+/// trait object, so its parameter is read back from its packed form, where it comes first.
+fn calculator_properties<R: Record>(
+    record: &R,
+    parent_key: &str,
+    swap_curve: &SwapCurve,
+) -> Vec<InstructionProperty> {
+    let key = match swap_curve.curve_type {
+        CurveType::ConstantProduct => return vec![],
+        // Flat line, always providing 1:1 from one token to another
+        CurveType::ConstantPrice => "token_b_price",
+        // Stable, like uniswap, but with wide zone of 1:1 instead of one point
+        CurveType::Stable => "amp",
+        // Offset curve, like Uniswap, but the token B side has a faked offset
+        CurveType::Offset => "token_b_offset",
+    };
+
+    // The curve type is packed first, followed by the calculator.
+    let mut packed = [0u8; SwapCurve::LEN];
+    swap_curve.pack_into_slice(&mut packed);
+    let value = u64::from_le_bytes(*array_ref![packed, 1, 8]);
+
+    vec![record.property(parent_key, key, value.to_string(), ValueType::U64)]
+}
+
+fn curve_type_name(curve_type: &CurveType) -> String {
+    match curve_type {
+        CurveType::ConstantProduct => "ConstantProduct".to_string(),
+        CurveType::ConstantPrice => "ConstantPrice".to_string(),
+        CurveType::Stable => "Stable".to_string(),
+        CurveType::Offset => "Offset".to_string(),
+    }
+}
+
+/// The roles of the accounts each instruction expects, in order.
+fn account_roles(instruction: &SwapInstruction) -> &'static [&'static str] {
+    match instruction {
+        SwapInstruction::Initialize(_) => &[
+            "swap", "authority", "token_a", "token_b", "pool_mint", "fee_account", "destination",
+            "token_program",
+        ],
+        SwapInstruction::Swap(_) => &[
+            "swap", "authority", "user_transfer_authority", "source", "swap_source",
+            "swap_destination", "destination", "pool_mint", "fee_account", "token_program",
+            "host_fee_account",
+        ],
+        SwapInstruction::DepositAllTokenTypes(_) => &[
+            "swap", "authority", "user_transfer_authority", "source_a", "source_b", "token_a",
+            "token_b", "pool_mint", "destination", "token_program",
+        ],
+        SwapInstruction::WithdrawAllTokenTypes(_) => &[
+            "swap", "authority", "user_transfer_authority", "pool_mint", "source", "token_a",
+            "token_b", "destination_a", "destination_b", "fee_account", "token_program",
+        ],
+        SwapInstruction::DepositSingleTokenTypeExactAmountIn(_) => &[
+            "swap", "authority", "user_transfer_authority", "source", "swap_token_a",
+            "swap_token_b", "pool_mint", "destination", "token_program",
+        ],
+        SwapInstruction::WithdrawSingleTokenTypeExactAmountOut(_) => &[
+            "swap", "authority", "user_transfer_authority", "pool_mint", "source", "swap_token_a",
+            "swap_token_b", "destination", "fee_account", "token_program",
+        ],
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use spl_token_swap::curve::constant_price::ConstantPriceCurve;
+    use spl_token_swap::solana_program::pubkey::Pubkey;
+    use spl_token_swap::state::SwapV1;
+
+    use super::*;
+    use crate::test_utils::decode_account;
+
+    #[test]
+    fn swaps_are_decoded_with_their_curve_parameter() {
+        let pool_mint = Pubkey::new_unique();
+        let mut data = vec![0; SwapVersion::LATEST_LEN];
+        SwapVersion::pack(SwapVersion::SwapV1(SwapV1 {
+            is_initialized: true,
+            nonce: 255,
+            token_program_id: Pubkey::new_unique(),
+            token_a: Pubkey::new_unique(),
+            token_b: Pubkey::new_unique(),
+            pool_mint,
+            token_a_mint: Pubkey::new_unique(),
+            token_b_mint: Pubkey::new_unique(),
+            pool_fee_account: Pubkey::new_unique(),
+            fees: Fees {
+                trade_fee_numerator: 25,
+                trade_fee_denominator: 10_000,
+                ..Fees::default()
+            },
+            swap_curve: SwapCurve {
+                curve_type: CurveType::ConstantPrice,
+                calculator: Box::new(ConstantPriceCurve { token_b_price: 1_500 }),
+            },
+        }), &mut data).unwrap();
+
+        let swap = decode_account(PROGRAM_ADDRESS, data).to_json();
+        assert_eq!(swap["function_name"], "swap-v1");
+        assert_eq!(swap["properties"]["version"], 1);
+        assert_eq!(swap["properties"]["pool_mint"], pool_mint.to_string());
+        assert_eq!(swap["properties"]["fees"]["trade_fee_numerator"], 25);
+        let swap_curve = &swap["properties"]["swap_curve"];
+        assert_eq!(swap_curve["curve_type"], "ConstantPrice");
+        assert_eq!(swap_curve["calculator"]["token_b_price"], 1_500);
+    }
+}
@@ -1,10 +1,15 @@
 use solana_program::instruction::InstructionError;
 use solana_sdk::program_utils::limited_deserialize;
 use solana_vote_program::vote_instruction::VoteInstruction;
-use solana_vote_program::vote_state::VoteAuthorize;
+use solana_vote_program::vote_state::{VoteAuthorize, VoteState};
 use tracing::error;
 
-use crate::{Instruction, InstructionFunction, InstructionProperty, InstructionSet};
+use crate::error::DecodeError;
+use crate::path;
+use crate::{
+    AccountRecord, Instruction, InstructionFunction, InstructionProperty, InstructionSet, Record,
+    ValueType,
+};
 
 pub const PROGRAM_ADDRESS: &str = "Vote111111111111111111111111111111111111111";
 
@@ -15,7 +20,7 @@ pub const PROGRAM_ADDRESS: &str = "Vote111111111111111111111111111111111111111";
 pub async fn fragment_instruction(
     // The instruction
     instruction: Instruction,
-) -> Option<InstructionSet> {
+) -> Result<InstructionSet, DecodeError> {
     // Deserialize the instruction
     let vdr: Result<VoteInstruction, InstructionError> = limited_deserialize(
         instruction.data.as_slice());
@@ -23,7 +28,9 @@ pub async fn fragment_instruction(
     return match vdr {
         Ok(ref di) => {
             let deserialized_instruction = di.clone();
-            match deserialized_instruction {
+            let account_properties = instruction.account_properties(
+                account_roles(&deserialized_instruction));
+            let instruction_set = match deserialized_instruction {
                 VoteInstruction::InitializeAccount(vote_init) => {
                     // Source code
                     // verify_rent_exemption(me, next_keyed_account(keyed_accounts)?)?;
@@ -35,7 +42,7 @@ pub async fn fragment_instruction(
                     //     invoke_context
                     //         .is_feature_active(&feature_set::check_init_vote_data::id()),
                     // );
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -43,6 +50,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "initialize-account".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![
                             InstructionProperty {
@@ -51,8 +60,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "node_pubkey".to_string(),
                                 value: vote_init.node_pubkey.to_string(),
+                                value_type: ValueType::Pubkey,
                                 parent_key: "vote_init".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -60,8 +72,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "commission".to_string(),
                                 value: vote_init.commission.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "vote_init".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -69,8 +84,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "authorized_withdrawer".to_string(),
                                 value: vote_init.authorized_withdrawer.to_string(),
+                                value_type: ValueType::Pubkey,
                                 parent_key: "vote_init".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -78,8 +96,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "authorized_voter".to_string(),
                                 value: vote_init.authorized_voter.to_string(),
+                                value_type: ValueType::Pubkey,
                                 parent_key: "vote_init".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                         ],
                     })
@@ -92,7 +113,7 @@ pub async fn fragment_instruction(
                     //     &signers,
                     //     &from_keyed_account::<Clock>(next_keyed_account(keyed_accounts)?)?,
                     // )
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -100,6 +121,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "authorize".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![
                             InstructionProperty {
@@ -108,8 +131,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "voter_pubkey".to_string(),
                                 value: voter_pubkey.to_string(),
+                                value_type: ValueType::Pubkey,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -120,14 +146,17 @@ pub async fn fragment_instruction(
                                     VoteAuthorize::Voter => "voter".to_string(),
                                     VoteAuthorize::Withdrawer => "withdrawer".to_string()
                                 },
+                                value_type: ValueType::EnumName,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             }
                         ],
                     })
                 }
                 VoteInstruction::AuthorizeChecked(vote_authorize) => {
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -135,6 +164,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "vote-authorize".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![
                             InstructionProperty {
@@ -146,8 +177,11 @@ pub async fn fragment_instruction(
                                     VoteAuthorize::Voter => "voter".to_string(),
                                     VoteAuthorize::Withdrawer => "withdrawer".to_string()
                                 },
+                                value_type: ValueType::EnumName,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             }
                         ],
                     })
@@ -158,7 +192,7 @@ pub async fn fragment_instruction(
                     //     next_keyed_account(keyed_accounts)?.unsigned_key(),
                     //     &signers,
                     // )
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -166,13 +200,15 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "update-validator-identity".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![],
                     })
                 }
                 VoteInstruction::UpdateCommission(commission) => {
                     // vote_state::update_commission(me, commission, &signers)
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -180,6 +216,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "update-commission".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![
                             InstructionProperty {
@@ -188,14 +226,17 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "commission".to_string(),
                                 value: commission.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             }
                         ],
                     })
                 }
                 VoteInstruction::VoteSwitch(vote, hash) => {
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -203,6 +244,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "vote-switch".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![
                             InstructionProperty {
@@ -211,8 +254,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "hash".to_string(),
                                 value: bs58::encode(vote.hash.0).into_string(),
+                                value_type: ValueType::String,
                                 parent_key: "vote".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -220,8 +266,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "slots".to_string(),
                                 value: serde_json::to_string(vote.slots.as_slice()).unwrap(),
+                                value_type: ValueType::String,
                                 parent_key: "vote".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             // InstructionProperty {
                             //     tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -242,8 +291,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "hash".to_string(),
                                 value: bs58::encode(hash.0).into_string(),
+                                value_type: ValueType::String,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             }
                         ],
                     })
@@ -257,7 +309,7 @@ pub async fn fragment_instruction(
                     //     &vote,
                     //     &signers,
                     // )
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -265,6 +317,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "vote".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![
                             InstructionProperty {
@@ -273,8 +327,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "hash".to_string(),
                                 value: bs58::encode(vote.hash.0).into_string(),
+                                value_type: ValueType::String,
                                 parent_key: "vote".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -282,8 +339,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "slots".to_string(),
                                 value: serde_json::to_string(vote.slots.as_slice()).unwrap(),
+                                value_type: ValueType::String,
                                 parent_key: "vote".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             // InstructionProperty {
                             //     tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -305,7 +365,7 @@ pub async fn fragment_instruction(
                     // let to = next_keyed_account(keyed_accounts)?;
                     // vote_state::withdraw(me, lamports, to, &signers)
                     // vote_state::update_commission(me, commission, &signers)
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -313,6 +373,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "withdraw".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![
                             InstructionProperty {
@@ -321,70 +383,116 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "lamports".to_string(),
                                 value: lamports.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             }
                         ],
                     })
                 }
+            };
+
+            instruction_set.map(|mut set| {
+                set.properties.extend(account_properties);
+                set
+            })
+        }
+        Err(err) => {
+            let decode_error = DecodeError::from_instruction_error(&instruction.program, &err);
+
+            error!("{} Reason: {}", "Invalid instruction for the vote program.".to_string(), decode_error);
+            Err(decode_error)
+        }
+    }
+}
+
+/// Decodes the data of a vote account.
+pub async fn fragment_account(
+    // The record holding the account's data
+    account: AccountRecord,
+) -> Result<InstructionSet, DecodeError> {
+    match VoteState::deserialize(account.data.as_slice()) {
+        Ok(vote_state) => {
+            let mut properties = vec![
+                account.property("", "node_pubkey", vote_state.node_pubkey.to_string(),
+                                 ValueType::Pubkey),
+                account.property("", "authorized_withdrawer",
+                                 vote_state.authorized_withdrawer.to_string(), ValueType::Pubkey),
+                account.property("", "commission", vote_state.commission.to_string(),
+                                 ValueType::U64),
+                account.optional_property("", "root_slot",
+                                          vote_state.root_slot.map(|slot| slot.to_string()),
+                                          ValueType::U64),
+                account.property("last_timestamp", "slot",
+                                 vote_state.last_timestamp.slot.to_string(), ValueType::U64),
+                account.property("last_timestamp", "timestamp",
+                                 vote_state.last_timestamp.timestamp.to_string(), ValueType::I64),
+            ];
+
+            for (idx, lockout) in vote_state.votes.iter().enumerate() {
+                let parent_key = path::join(&["votes", &idx.to_string()]);
+                properties.push(account.property(&parent_key, "slot", lockout.slot.to_string(),
+                                                 ValueType::U64));
+                properties.push(account.property(&parent_key, "confirmation_count",
+                                                 lockout.confirmation_count.to_string(),
+                                                 ValueType::U64));
             }
+
+            for (idx, (epoch, authorized_voter)) in vote_state.authorized_voters().iter()
+                .enumerate() {
+                let parent_key = path::join(&["authorized_voters", &idx.to_string()]);
+                properties.push(account.property(&parent_key, "epoch", epoch.to_string(),
+                                                 ValueType::U64));
+                properties.push(account.property(&parent_key, "authorized_voter",
+                                                 authorized_voter.to_string(),
+                                                 ValueType::Pubkey));
+            }
+
+            for (idx, (epoch, credits, previous_credits)) in vote_state.epoch_credits().iter()
+                .enumerate() {
+                let parent_key = path::join(&["epoch_credits", &idx.to_string()]);
+                properties.push(account.property(&parent_key, "epoch", epoch.to_string(),
+                                                 ValueType::U64));
+                properties.push(account.property(&parent_key, "credits", credits.to_string(),
+                                                 ValueType::U64));
+                properties.push(account.property(&parent_key, "previous_credits",
+                                                 previous_credits.to_string(), ValueType::U64));
+            }
+
+            Ok(InstructionSet {
+                function: account.function("vote"),
+                properties,
+            })
         }
         Err(err) => {
-            let err_msg = match err {
-                InstructionError::GenericError => "GenericError".to_string(),
-                InstructionError::InvalidArgument => "InvalidArgument".to_string(),
-                InstructionError::InvalidInstructionData => "InvalidInstructionData".to_string(),
-                InstructionError::InvalidAccountData => "InvalidAccountData".to_string(),
-                InstructionError::AccountDataTooSmall => "AccountDataTooSmall".to_string(),
-                InstructionError::InsufficientFunds => "InsufficientFunds".to_string(),
-                InstructionError::IncorrectProgramId => "IncorrectProgramId".to_string(),
-                InstructionError::MissingRequiredSignature => "MissingRequiredSignature".to_string(),
-                InstructionError::AccountAlreadyInitialized => "AccountAlreadyInitialized".to_string(),
-                InstructionError::UninitializedAccount => "UninitializedAccount".to_string(),
-                InstructionError::UnbalancedInstruction => "UnbalancedInstruction".to_string(),
-                InstructionError::ModifiedProgramId => "ModifiedProgramId".to_string(),
-                InstructionError::ExternalAccountLamportSpend => "ExternalAccountLamportSpend".to_string(),
-                InstructionError::ExternalAccountDataModified => "ExternalAccountDataModified".to_string(),
-                InstructionError::ReadonlyLamportChange => "ReadonlyLamportChange".to_string(),
-                InstructionError::ReadonlyDataModified => "ReadonlyDataModified".to_string(),
-                InstructionError::DuplicateAccountIndex => "DuplicateAccountIndex".to_string(),
-                InstructionError::ExecutableModified => "ExecutableModified".to_string(),
-                InstructionError::RentEpochModified => "RentEpochModified".to_string(),
-                InstructionError::NotEnoughAccountKeys => "NotEnoughAccountKeys".to_string(),
-                InstructionError::AccountDataSizeChanged => "AccountDataSizeChanged".to_string(),
-                InstructionError::AccountNotExecutable => "AccountNotExecutable".to_string(),
-                InstructionError::AccountBorrowFailed => "AccountBorrowFailed".to_string(),
-                InstructionError::AccountBorrowOutstanding => "AccountBorrowOutstanding".to_string(),
-                InstructionError::DuplicateAccountOutOfSync => "DuplicateAccountOutOfSync".to_string(),
-                InstructionError::Custom(_) => "Custom".to_string(),
-                InstructionError::InvalidError => "InvalidError".to_string(),
-                InstructionError::ExecutableDataModified => "ExecutableDataModified".to_string(),
-                InstructionError::ExecutableLamportChange => "ExecutableLamportChange".to_string(),
-                InstructionError::ExecutableAccountNotRentExempt => "ExecutableAccountNotRentExempt".to_string(),
-                InstructionError::UnsupportedProgramId => "UnsupportedProgramId".to_string(),
-                InstructionError::CallDepth => "CallDepth".to_string(),
-                InstructionError::MissingAccount => "MissingAccount".to_string(),
-                InstructionError::ReentrancyNotAllowed => "ReentrancyNotAllowed".to_string(),
-                InstructionError::MaxSeedLengthExceeded => "MaxSeedLengthExceeded".to_string(),
-                InstructionError::InvalidSeeds => "InvalidSeeds".to_string(),
-                InstructionError::InvalidRealloc => "InvalidRealloc".to_string(),
-                InstructionError::ComputationalBudgetExceeded => "ComputationalBudgetExceeded".to_string(),
-                InstructionError::PrivilegeEscalation => "PrivilegeEscalation".to_string(),
-                InstructionError::ProgramEnvironmentSetupFailure => "ProgramEnvironmentSetupFailure".to_string(),
-                InstructionError::ProgramFailedToComplete => "ProgramFailedToComplete".to_string(),
-                InstructionError::ProgramFailedToCompile => "ProgramFailedToCompile".to_string(),
-                InstructionError::Immutable => "Immutable".to_string(),
-                InstructionError::IncorrectAuthority => "IncorrectAuthority".to_string(),
-                InstructionError::BorshIoError(_) => "BorshIoError".to_string(),
-                InstructionError::AccountNotRentExempt => "AccountNotRentExempt".to_string(),
-                InstructionError::InvalidAccountOwner => "InvalidAccountOwner".to_string(),
-                InstructionError::ArithmeticOverflow => "ArithmeticOverflow".to_string(),
-                InstructionError::UnsupportedSysvar => "UnsupportedSysvar".to_string(),
-                InstructionError::IllegalOwner => "IllegalOwner".to_string()
-            };
+            let decode_error = DecodeError::from_instruction_error(&account.owner, &err);
+            error!("[spi-wrapper/programs/native_vote] Attempt to parse account {} failed due \
+            to {}.", account.pubkey, decode_error);
 
-            error!("{} Reason: {}", "Invalid instruction for the vote program.".to_string(), err_msg);
-            None
+            Err(decode_error)
         }
     }
-}
\ No newline at end of file
+}
+
+/// The roles of the accounts each instruction expects, in order.
+fn account_roles(instruction: &VoteInstruction) -> &'static [&'static str] {
+    match instruction {
+        VoteInstruction::InitializeAccount(_) => &[
+            "vote_account", "rent_sysvar", "clock_sysvar", "node",
+        ],
+        VoteInstruction::Authorize(..) => &["vote_account", "clock_sysvar", "authority"],
+        VoteInstruction::Vote(_) | VoteInstruction::VoteSwitch(..) => &[
+            "vote_account", "slot_hashes_sysvar", "clock_sysvar", "vote_authority",
+        ],
+        VoteInstruction::Withdraw(_) => &["vote_account", "destination", "withdraw_authority"],
+        VoteInstruction::UpdateValidatorIdentity => &[
+            "vote_account", "new_validator_identity", "withdraw_authority",
+        ],
+        VoteInstruction::UpdateCommission(_) => &["vote_account", "withdraw_authority"],
+        VoteInstruction::AuthorizeChecked(_) => &[
+            "vote_account", "clock_sysvar", "authority", "new_authority",
+        ],
+    }
+}
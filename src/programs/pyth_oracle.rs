@@ -0,0 +1,298 @@
+use solana_sdk::pubkey::Pubkey;
+use tracing::error;
+
+use crate::error::DecodeError;
+use crate::path;
+use crate::programs::solend::pyth::{
+    self, AccKey, AccountType, Product, RawEma, RawPrice, RawPriceInfo, PROD_ATTR_SIZE,
+    PROD_HDR_SIZE,
+};
+use crate::{AccountRecord, InstructionProperty, InstructionSet, ValueType};
+
+pub const PROGRAM_ADDRESS: &str = "FsJ3A3u2vn5cTVofAjvy6y5kwABJAqYWpe4975bi2epH";
+pub const PROGRAM_ADDRESS_DEVNET: &str = "gSbePebfvPy7tRqimPoVecS2UsBvYv46ynrzWocc92s";
+pub const PROGRAM_ADDRESS_TESTNET: &str = "8tfDNiaEyrV6Q1U4DEXrEigs9DoDtkugzFbybENEbCDz";
+
+/// Decodes the data of a Pyth price or product account.
+pub async fn fragment_account(
+    // The record holding the account's data
+    account: AccountRecord,
+) -> Result<InstructionSet, DecodeError> {
+    let decoded = match account_type(account.data.as_slice()) {
+        Some(atype) if atype == AccountType::Price as u32 => {
+            pyth::load::<RawPrice>(account.data.as_slice())
+                .map(|price| InstructionSet {
+                    function: account.function("price"),
+                    properties: price_properties(&account, &price),
+                })
+                .map_err(|err| format!("{:?}", err))
+        }
+        Some(atype) if atype == AccountType::Product as u32 => {
+            pyth::load::<Product>(account.data.as_slice())
+                .map(|product| InstructionSet {
+                    function: account.function("product"),
+                    properties: product_properties(&account, &product),
+                })
+                .map_err(|err| format!("{:?}", err))
+        }
+        Some(atype) => Err(format!("Account type {} is not supported.", atype)),
+        None => Err("The account is not a Pyth account.".to_string()),
+    };
+
+    decoded.map_err(|err| {
+        let decode_error = DecodeError::deserialization(
+            &account.owner, "ParseAccountError", &err);
+        error!("[spi-wrapper/programs/pyth_oracle] Attempt to parse account {} failed due to \
+        {}.", account.pubkey, err);
+
+        decode_error
+    })
+}
+
+/// The account type in the header of a Pyth account, if the header is a valid one. It tells
+/// which layout the rest of the data follows.
+fn account_type(data: &[u8]) -> Option<u32> {
+    if data.len() < PROD_HDR_SIZE {
+        return None;
+    }
+
+    let word = |idx: usize| {
+        let mut bytes = [0u8; 4];
+        bytes.copy_from_slice(&data[idx * 4..idx * 4 + 4]);
+        u32::from_le_bytes(bytes)
+    };
+    if word(0) != pyth::MAGIC || word(1) != pyth::VERSION {
+        return None;
+    }
+
+    Some(word(2))
+}
+
+fn price_properties(account: &AccountRecord, price: &RawPrice) -> Vec<InstructionProperty> {
+    let mut properties = vec![
+        account.property("", "version", price.ver.to_string(), ValueType::U64),
+        account.property("", "price_type", enum_name(price.ptype, PRICE_TYPES),
+                         ValueType::EnumName),
+        account.property("", "exponent", price.expo.to_string(), ValueType::I64),
+        account.property("", "num_quoters", price.num_qt.to_string(), ValueType::U64),
+        account.property("", "last_slot", price.last_slot.to_string(), ValueType::U64),
+        account.property("", "valid_slot", price.valid_slot.to_string(), ValueType::U64),
+        account.property("", "product", key_string(&price.prod), ValueType::Pubkey),
+        account.property("", "next", key_string(&price.next), ValueType::Pubkey),
+        account.property("previous", "slot", price.prev_slot.to_string(), ValueType::U64),
+        account.property("previous", "price", price.prev_price.to_string(), ValueType::I64),
+        account.property("previous", "conf", price.prev_conf.to_string(), ValueType::U64),
+        account.property("previous", "ui_price", scale(price.prev_price, price.expo),
+                         ValueType::String),
+        account.property("previous", "ui_conf", scale(price.prev_conf as i128, price.expo),
+                         ValueType::String),
+    ];
+    properties.extend(ema_properties(account, "twap", &price.twap, price.expo));
+    properties.extend(ema_properties(account, "twac", &price.twac, price.expo));
+    properties.extend(price_info_properties(account, "aggregate", &price.agg, price.expo));
+
+    let num_components = (price.num as usize).min(price.comp.len());
+    for (idx, component) in price.comp[..num_components].iter().enumerate() {
+        let parent_key = path::join(&["components", &idx.to_string()]);
+        properties.push(account.property(&parent_key, "publisher",
+                                         key_string(&component.publisher), ValueType::Pubkey));
+        properties.extend(price_info_properties(
+            account, &path::join(&[&parent_key, "aggregate"]), &component.agg, price.expo));
+        properties.extend(price_info_properties(
+            account, &path::join(&[&parent_key, "latest"]), &component.latest, price.expo));
+    }
+
+    properties
+}
+
+/// A moving average, along with its value scaled by the exponent of the price account.
+fn ema_properties(
+    account: &AccountRecord,
+    parent_key: &str,
+    ema: &RawEma,
+    expo: i32,
+) -> Vec<InstructionProperty> {
+    vec![
+        account.property(parent_key, "val", ema.val.to_string(), ValueType::I64),
+        account.property(parent_key, "numer", ema.numer.to_string(), ValueType::I64),
+        account.property(parent_key, "denom", ema.denom.to_string(), ValueType::I64),
+        account.property(parent_key, "ui_val", scale(ema.val, expo), ValueType::String),
+    ]
+}
+
+/// A price as published, along with the price and confidence scaled by the exponent of the
+/// price account.
+fn price_info_properties(
+    account: &AccountRecord,
+    parent_key: &str,
+    info: &RawPriceInfo,
+    expo: i32,
+) -> Vec<InstructionProperty> {
+    vec![
+        account.property(parent_key, "price", info.price.to_string(), ValueType::I64),
+        account.property(parent_key, "conf", info.conf.to_string(), ValueType::U64),
+        account.property(parent_key, "ui_price", scale(info.price, expo), ValueType::String),
+        account.property(parent_key, "ui_conf", scale(info.conf as i128, expo),
+                         ValueType::String),
+        account.property(parent_key, "status", enum_name(info.status, PRICE_STATUSES),
+                         ValueType::EnumName),
+        account.property(parent_key, "corporate_action", enum_name(info.corp_act, CORP_ACTIONS),
+                         ValueType::EnumName),
+        account.property(parent_key, "pub_slot", info.pub_slot.to_string(), ValueType::U64),
+    ]
+}
+
+fn product_properties(account: &AccountRecord, product: &Product) -> Vec<InstructionProperty> {
+    let mut properties = vec![
+        account.property("", "version", product.ver.to_string(), ValueType::U64),
+        account.property("", "price_account", key_string(&product.px_acc), ValueType::Pubkey),
+    ];
+
+    // The attributes are length-prefixed strings, alternating between keys and values, over the
+    // part of the account that is in use.
+    let used = (product.size as usize).saturating_sub(PROD_HDR_SIZE).min(PROD_ATTR_SIZE);
+    let mut attributes = &product.attr[..used];
+    while let Some((key, rest)) = next_attribute(attributes) {
+        let (value, rest) = match next_attribute(rest) {
+            Some(value) => value,
+            None => break,
+        };
+        properties.push(account.property("attributes", &key, value, ValueType::String));
+        attributes = rest;
+    }
+
+    properties
+}
+
+fn next_attribute(data: &[u8]) -> Option<(String, &[u8])> {
+    let (len, rest) = data.split_first()?;
+    let len = *len as usize;
+    if len > rest.len() {
+        return None;
+    }
+
+    Some((String::from_utf8_lossy(&rest[..len]).to_string(), &rest[len..]))
+}
+
+// The variants of `PriceType`, `PriceStatus` and `CorpAction`, in the order of the values they
+// are stored as.
+const PRICE_TYPES: &[&str] = &["Unknown", "Price"];
+const PRICE_STATUSES: &[&str] = &["Unknown", "Trading", "Halted", "Auction", "Ignored"];
+const CORP_ACTIONS: &[&str] = &["NoCorpAct"];
+
+/// Names the value stored for an enum, out of the enum's `variants`. Values past its last
+/// variant are named after the value itself, e.g. `Unrecognised(7)`.
+fn enum_name(value: u32, variants: &[&str]) -> String {
+    variants.get(value as usize)
+        .map(|name| name.to_string())
+        .unwrap_or_else(|| format!("Unrecognised({})", value))
+}
+
+fn key_string(key: &AccKey) -> String {
+    Pubkey::new_from_array(key.val).to_string()
+}
+
+/// Writes `value * 10^expo` as a decimal, without going through floats.
+fn scale<V: Into<i128>>(value: V, expo: i32) -> String {
+    let value: i128 = value.into();
+    let digits = value.unsigned_abs().to_string();
+    let sign = if value < 0 { "-" } else { "" };
+
+    if expo >= 0 {
+        return format!("{}{}{}", sign, digits, "0".repeat(expo as usize));
+    }
+
+    let decimals = expo.unsigned_abs() as usize;
+    let digits = format!("{:0>width$}", digits, width = decimals + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - decimals);
+    format!("{}{}.{}", sign, whole, fraction)
+}
+
+#[cfg(test)]
+mod test {
+    use std::mem::size_of;
+
+    use super::*;
+    use crate::test_utils::decode_account;
+
+    /// A version 2 price account, written field by field at the offsets the program uses.
+    fn v2_price_account() -> Vec<u8> {
+        let mut data = vec![0; 3312];
+        let mut put = |offset: usize, bytes: &[u8]| {
+            data[offset..offset + bytes.len()].copy_from_slice(bytes)
+        };
+        put(0, &pyth::MAGIC.to_le_bytes());
+        put(4, &pyth::VERSION_2.to_le_bytes());
+        put(8, &(AccountType::Price as u32).to_le_bytes());
+        put(12, &3312u32.to_le_bytes());
+        put(16, &1u32.to_le_bytes());
+        put(20, &(-8i32).to_le_bytes());
+        put(24, &2u32.to_le_bytes());
+        put(28, &1u32.to_le_bytes());
+        put(32, &100_002u64.to_le_bytes());
+        put(40, &100_003u64.to_le_bytes());
+        put(48, &(-7i64).to_le_bytes());
+        put(56, &14i64.to_le_bytes());
+        put(64, &(-2i64).to_le_bytes());
+        put(72, &3i64.to_le_bytes());
+        put(112, &[1; 32]);
+        put(144, &[2; 32]);
+        put(176, &100_001u64.to_le_bytes());
+        put(184, &123_000_000i64.to_le_bytes());
+        put(192, &7u64.to_le_bytes());
+        put(208, &(-123_456_789i64).to_le_bytes());
+        put(216, &42u64.to_le_bytes());
+        put(224, &1u32.to_le_bytes());
+        put(232, &100_002u64.to_le_bytes());
+        // Components are 96 bytes each, from 240: the publisher, then its aggregate and latest
+        // price infos. Statuses the enum has no variant for, and garbage, are read all the same.
+        put(240, &[3; 32]);
+        put(272, &5i64.to_le_bytes());
+        put(288, &4u32.to_le_bytes());
+        put(336 + 64 + 16, &0xdead_beefu32.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn raw_prices_have_the_v2_layout() {
+        assert_eq!(size_of::<RawPrice>(), 3312);
+    }
+
+    #[test]
+    fn pyth_prices_are_scaled_by_their_exponent() {
+        let price = decode_account(PROGRAM_ADDRESS, v2_price_account()).state;
+        assert_eq!(price.function.function_name, "price");
+        let value = |parent_key: &str, key: &str| price.properties.iter()
+            .find(|property| property.parent_key == parent_key && property.key == key)
+            .map(|property| property.value.clone())
+            .unwrap();
+        assert_eq!(value("", "price_type"), "Price");
+        assert_eq!(value("", "num_quoters"), "1");
+        assert_eq!(value("", "last_slot"), "100002");
+        assert_eq!(value("", "valid_slot"), "100003");
+        assert_eq!(value("", "product"), Pubkey::new_from_array([1; 32]).to_string());
+        assert_eq!(value("", "next"), Pubkey::new_from_array([2; 32]).to_string());
+        assert_eq!(value("twap", "val"), "-7");
+        assert_eq!(value("twap", "numer"), "14");
+        assert_eq!(value("twap", "denom"), "-2");
+        assert_eq!(value("twap", "ui_val"), "-0.00000007");
+        assert_eq!(value("twac", "val"), "3");
+        assert_eq!(value("previous", "slot"), "100001");
+        assert_eq!(value("previous", "ui_price"), "1.23000000");
+        assert_eq!(value("previous", "conf"), "7");
+        assert_eq!(value("aggregate", "ui_price"), "-1.23456789");
+        assert_eq!(value("aggregate", "ui_conf"), "0.00000042");
+        assert_eq!(value("aggregate", "status"), "Trading");
+        assert_eq!(value("aggregate", "corporate_action"), "NoCorpAct");
+        assert_eq!(value("aggregate", "pub_slot"), "100002");
+        assert_eq!(value("components/0", "publisher"),
+                   Pubkey::new_from_array([3; 32]).to_string());
+        assert_eq!(value("components/0/aggregate", "ui_price"), "0.00000005");
+        assert_eq!(value("components/0/aggregate", "status"), "Ignored");
+        assert_eq!(value("components/1/latest", "status"), "Unrecognised(3735928559)");
+        assert!(price.properties.iter()
+            .all(|property| !property.parent_key.starts_with("components/2")));
+        assert!(price.properties.iter()
+            .all(|property| property.key != "avol" && property.key != "aggregate_publisher"));
+    }
+}
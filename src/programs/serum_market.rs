@@ -1,7 +1,22 @@
-use serum_dex::instruction::MarketInstruction;
+use std::mem::size_of;
+
+use bytemuck::{bytes_of_mut, Pod};
+use serum_dex::critbit::LeafNode;
+use serum_dex::instruction::{MarketInstruction, SelfTradeBehavior};
+use serum_dex::matching::{OrderType, Side};
+use serum_dex::state::{
+    AccountFlag, Event, EventView, MarketState, OpenOrders, Request, RequestView,
+};
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
 use tracing::error;
 
-use crate::{InstructionFunction, InstructionSet, InstructionProperty, Instruction};
+use crate::error::DecodeError;
+use crate::path;
+use crate::{
+    AccountRecord, InstructionFunction, InstructionSet, InstructionProperty, Instruction, Record,
+    ValueType,
+};
 
 pub const PROGRAM_ADDRESS_V1: &str = "BJ3jrUzddfuSrZHXSCxMUUQsjKEyLmuuyZebkcaFp2fg";
 pub const PROGRAM_ADDRESS_V2: &str = "EUqojwWA2rd19FZrzeBncJsm38Jm1hEhE3zsmX3bRc2o";
@@ -9,13 +24,15 @@ pub const PROGRAM_ADDRESS_V3: &str = "9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVF
 
 pub async fn fragment_instruction(
     instruction: Instruction
-) -> Option<InstructionSet> {
+) -> Result<InstructionSet, DecodeError> {
     // Unpack the instruction via the spl_token_swap library
     let unpack_result = MarketInstruction::unpack(
         instruction.data.as_slice());
 
     if let Some(market_instruction) = unpack_result {
-        return match market_instruction {
+        let account_properties = instruction.account_properties(
+            account_roles(&market_instruction));
+        let instruction_set = match market_instruction {
             MarketInstruction::InitializeMarket(imi) => {
                 // 0. `[writable]` the market to initialize
                 // 1. `[writable]` zeroed out request queue
@@ -27,7 +44,7 @@ pub async fn fragment_instruction(
                 // 7. `[]` coin currency Mint
                 // 8. `[]` price currency Mint
                 // 9. `[]` the rent sysvar
-                Some(InstructionSet {
+                Ok(InstructionSet {
                     function: InstructionFunction {
                         tx_instruction_id: instruction.tx_instruction_id.clone(),
                         transaction_hash: instruction.transaction_hash.clone(),
@@ -35,6 +52,8 @@ pub async fn fragment_instruction(
                         program: instruction.program.clone(),
                         function_name: "initialize-market".to_string(),
                         timestamp: instruction.timestamp.clone(),
+                        slot: instruction.slot.clone(),
+                        block_time: instruction.block_time.clone(),
                     },
                     properties: vec![
                         InstructionProperty {
@@ -43,8 +62,11 @@ pub async fn fragment_instruction(
                             parent_index: instruction.parent_index.clone(),
                             key: "coin_lot_size".to_string(),
                             value: imi.coin_lot_size.to_string(),
+                            value_type: ValueType::U64,
                             parent_key: "".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         InstructionProperty {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -52,8 +74,11 @@ pub async fn fragment_instruction(
                             parent_index: instruction.parent_index.clone(),
                             key: "fee_rate_bps".to_string(),
                             value: imi.fee_rate_bps.to_string(),
+                            value_type: ValueType::U64,
                             parent_key: "".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         InstructionProperty {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -61,8 +86,11 @@ pub async fn fragment_instruction(
                             parent_index: instruction.parent_index.clone(),
                             key: "pc_dust_threshold".to_string(),
                             value: imi.pc_dust_threshold.to_string(),
+                            value_type: ValueType::U64,
                             parent_key: "".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         InstructionProperty {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -70,8 +98,11 @@ pub async fn fragment_instruction(
                             parent_index: instruction.parent_index.clone(),
                             key: "pc_lot_size".to_string(),
                             value: imi.pc_lot_size.to_string(),
+                            value_type: ValueType::U64,
                             parent_key: "".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         InstructionProperty {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -79,8 +110,11 @@ pub async fn fragment_instruction(
                             parent_index: instruction.parent_index.clone(),
                             key: "vault_signer_nonce".to_string(),
                             value: imi.vault_signer_nonce.to_string(),
+                            value_type: ValueType::U64,
                             parent_key: "".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                     ],
                 })
@@ -96,7 +130,7 @@ pub async fn fragment_instruction(
                 // 7. `[]` spl token program
                 // 8. `[]` the rent sysvar
                 // 9. `[writable]` (optional) the (M)SRM account used for fee discounts
-                Some(InstructionSet {
+                Ok(InstructionSet {
                     function: InstructionFunction {
                         tx_instruction_id: instruction.tx_instruction_id.clone(),
                         transaction_hash: instruction.transaction_hash.clone(),
@@ -104,6 +138,8 @@ pub async fn fragment_instruction(
                         program: instruction.program.clone(),
                         function_name: "new-order".to_string(),
                         timestamp: instruction.timestamp.clone(),
+                        slot: instruction.slot.clone(),
+                        block_time: instruction.block_time.clone(),
                     },
                     properties: vec![
                         InstructionProperty {
@@ -112,8 +148,11 @@ pub async fn fragment_instruction(
                             parent_index: instruction.parent_index.clone(),
                             key: "client_id".to_string(),
                             value: noiv1.client_id.to_string(),
+                            value_type: ValueType::U64,
                             parent_key: "".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         InstructionProperty {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -121,8 +160,11 @@ pub async fn fragment_instruction(
                             parent_index: instruction.parent_index.clone(),
                             key: "limit_price".to_string(),
                             value: noiv1.limit_price.to_string(),
+                            value_type: ValueType::U64,
                             parent_key: "".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         InstructionProperty {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -130,26 +172,35 @@ pub async fn fragment_instruction(
                             parent_index: instruction.parent_index.clone(),
                             key: "max_qty".to_string(),
                             value: noiv1.max_qty.to_string(),
+                            value_type: ValueType::U64,
                             parent_key: "".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         InstructionProperty {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
                             parent_index: instruction.parent_index.clone(),
                             key: "order_type".to_string(),
-                            value: (noiv1.order_type as u8).to_string(),
+                            value: order_type_name(&noiv1.order_type),
+                            value_type: ValueType::EnumName,
                             parent_key: "".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         InstructionProperty {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
                             parent_index: instruction.parent_index.clone(),
                             key: "side".to_string(),
-                            value: (noiv1.side as u8).to_string(),
+                            value: side_name(&noiv1.side),
+                            value_type: ValueType::EnumName,
                             parent_key: "".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                     ],
                 })
@@ -162,7 +213,7 @@ pub async fn fragment_instruction(
                 // 4. `[writable]` asks
                 // 5. `[writable]` coin fee receivable account
                 // 6. `[writable]` pc fee receivable account
-                Some(InstructionSet {
+                Ok(InstructionSet {
                     function: InstructionFunction {
                         tx_instruction_id: instruction.tx_instruction_id.clone(),
                         transaction_hash: instruction.transaction_hash.clone(),
@@ -170,6 +221,8 @@ pub async fn fragment_instruction(
                         program: instruction.program.clone(),
                         function_name: "match-orders".to_string(),
                         timestamp: instruction.timestamp.clone(),
+                        slot: instruction.slot.clone(),
+                        block_time: instruction.block_time.clone(),
                     },
                     properties: vec![
                         InstructionProperty {
@@ -178,8 +231,11 @@ pub async fn fragment_instruction(
                             parent_index: instruction.parent_index.clone(),
                             key: "orders".to_string(),
                             value: orders.to_string(),
+                            value_type: ValueType::U64,
                             parent_key: "".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         }
                     ],
                 })
@@ -190,7 +246,7 @@ pub async fn fragment_instruction(
                 // accounts.len() - 3 `[writable]` event queue
                 // accounts.len() - 2 `[writable]` coin fee receivable account
                 // accounts.len() - 1 `[writable]` pc fee receivable account
-                Some(InstructionSet {
+                Ok(InstructionSet {
                     function: InstructionFunction {
                         tx_instruction_id: instruction.tx_instruction_id.clone(),
                         transaction_hash: instruction.transaction_hash.clone(),
@@ -198,6 +254,8 @@ pub async fn fragment_instruction(
                         program: instruction.program.clone(),
                         function_name: "consume-events".to_string(),
                         timestamp: instruction.timestamp.clone(),
+                        slot: instruction.slot.clone(),
+                        block_time: instruction.block_time.clone(),
                     },
                     properties: vec![
                         InstructionProperty {
@@ -206,8 +264,11 @@ pub async fn fragment_instruction(
                             parent_index: instruction.parent_index.clone(),
                             key: "events".to_string(),
                             value: count.to_string(),
+                            value_type: ValueType::U64,
                             parent_key: "".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         }
                     ],
                 })
@@ -228,7 +289,7 @@ pub async fn fragment_instruction(
                 //     pub owner_slot: u8,
                 // }
 
-                Some(InstructionSet {
+                Ok(InstructionSet {
                     function: InstructionFunction {
                         tx_instruction_id: instruction.tx_instruction_id.clone(),
                         transaction_hash: instruction.transaction_hash.clone(),
@@ -236,6 +297,8 @@ pub async fn fragment_instruction(
                         program: instruction.program.clone(),
                         function_name: "cancel-order".to_string(),
                         timestamp: instruction.timestamp.clone(),
+                        slot: instruction.slot.clone(),
+                        block_time: instruction.block_time.clone(),
                     },
                     properties: vec![
                         InstructionProperty {
@@ -243,9 +306,12 @@ pub async fn fragment_instruction(
                             transaction_hash: instruction.transaction_hash.clone(),
                             parent_index: instruction.parent_index.clone(),
                             key: "side".to_string(),
-                            value: (coi.side as u8).to_string(),
+                            value: side_name(&coi.side),
+                            value_type: ValueType::EnumName,
                             parent_key: "".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         InstructionProperty {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -253,8 +319,11 @@ pub async fn fragment_instruction(
                             parent_index: instruction.parent_index.clone(),
                             key: "order_id".to_string(),
                             value: coi.order_id.to_string(),
+                            value_type: ValueType::U128,
                             parent_key: "".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         InstructionProperty {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -262,8 +331,11 @@ pub async fn fragment_instruction(
                             parent_index: instruction.parent_index.clone(),
                             key: "owner_slot".to_string(),
                             value: coi.owner_slot.to_string(),
+                            value_type: ValueType::U64,
                             parent_key: "".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                     ],
                 })
@@ -279,7 +351,7 @@ pub async fn fragment_instruction(
                 // 7. `[]` vault signer
                 // 8. `[]` spl token program
                 // 9. `[writable]` (optional) referrer pc wallet
-                Some(InstructionSet {
+                Ok(InstructionSet {
                     function: InstructionFunction {
                         tx_instruction_id: instruction.tx_instruction_id.clone(),
                         transaction_hash: instruction.transaction_hash.clone(),
@@ -287,6 +359,8 @@ pub async fn fragment_instruction(
                         program: instruction.program.clone(),
                         function_name: "settle-funds".to_string(),
                         timestamp: instruction.timestamp.clone(),
+                        slot: instruction.slot.clone(),
+                        block_time: instruction.block_time.clone(),
                     },
                     properties: vec![],
                 })
@@ -296,7 +370,7 @@ pub async fn fragment_instruction(
                 // 1. `[writable]` OpenOrders
                 // 2. `[writable]` the request queue
                 // 3. `[signer]` the OpenOrders owner
-                Some(InstructionSet {
+                Ok(InstructionSet {
                     function: InstructionFunction {
                         tx_instruction_id: instruction.tx_instruction_id.clone(),
                         transaction_hash: instruction.transaction_hash.clone(),
@@ -304,6 +378,8 @@ pub async fn fragment_instruction(
                         program: instruction.program.clone(),
                         function_name: "cancel-order-by-client-id".to_string(),
                         timestamp: instruction.timestamp.clone(),
+                        slot: instruction.slot.clone(),
+                        block_time: instruction.block_time.clone(),
                     },
                     properties: vec![
                         InstructionProperty {
@@ -312,8 +388,11 @@ pub async fn fragment_instruction(
                             parent_index: instruction.parent_index.clone(),
                             key: "client_id".to_string(),
                             value: client_id.to_string(),
+                            value_type: ValueType::U64,
                             parent_key: "".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         }
                     ],
                 })
@@ -321,7 +400,7 @@ pub async fn fragment_instruction(
             MarketInstruction::DisableMarket => {
                 // 0. `[writable]` market
                 // 1. `[signer]` disable authority
-                Some(InstructionSet {
+                Ok(InstructionSet {
                     function: InstructionFunction {
                         tx_instruction_id: instruction.tx_instruction_id.clone(),
                         transaction_hash: instruction.transaction_hash.clone(),
@@ -329,6 +408,8 @@ pub async fn fragment_instruction(
                         program: instruction.program.clone(),
                         function_name: "disable-market".to_string(),
                         timestamp: instruction.timestamp.clone(),
+                        slot: instruction.slot.clone(),
+                        block_time: instruction.block_time.clone(),
                     },
                     properties: vec![],
                 })
@@ -340,7 +421,7 @@ pub async fn fragment_instruction(
                 // 3. `[writable]` fee receivable account
                 // 4. `[]` vault signer
                 // 5. `[]` spl token program
-                Some(InstructionSet {
+                Ok(InstructionSet {
                     function: InstructionFunction {
                         tx_instruction_id: instruction.tx_instruction_id.clone(),
                         transaction_hash: instruction.transaction_hash.clone(),
@@ -348,6 +429,8 @@ pub async fn fragment_instruction(
                         program: instruction.program.clone(),
                         function_name: "sweep-fees".to_string(),
                         timestamp: instruction.timestamp.clone(),
+                        slot: instruction.slot.clone(),
+                        block_time: instruction.block_time.clone(),
                     },
                     properties: vec![],
                 })
@@ -363,7 +446,7 @@ pub async fn fragment_instruction(
                 // 7. `[]` spl token program
                 // 8. `[]` the rent sysvar
                 // 9. `[writable]` (optional) the (M)SRM account used for fee discounts
-                Some(InstructionSet {
+                Ok(InstructionSet {
                     function: InstructionFunction {
                         tx_instruction_id: instruction.tx_instruction_id.clone(),
                         transaction_hash: instruction.transaction_hash.clone(),
@@ -371,6 +454,8 @@ pub async fn fragment_instruction(
                         program: instruction.program.clone(),
                         function_name: "new-order-v2".to_string(),
                         timestamp: instruction.timestamp.clone(),
+                        slot: instruction.slot.clone(),
+                        block_time: instruction.block_time.clone(),
                     },
                     properties: vec![
                         InstructionProperty {
@@ -379,8 +464,11 @@ pub async fn fragment_instruction(
                             parent_index: instruction.parent_index.clone(),
                             key: "client_id".to_string(),
                             value: order.client_id.to_string(),
+                            value_type: ValueType::U64,
                             parent_key: "".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         InstructionProperty {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -388,8 +476,11 @@ pub async fn fragment_instruction(
                             parent_index: instruction.parent_index.clone(),
                             key: "limit_price".to_string(),
                             value: order.limit_price.to_string(),
+                            value_type: ValueType::U64,
                             parent_key: "".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         InstructionProperty {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -397,8 +488,11 @@ pub async fn fragment_instruction(
                             parent_index: instruction.parent_index.clone(),
                             key: "max_qty".to_string(),
                             value: order.max_qty.to_string(),
+                            value_type: ValueType::U64,
                             parent_key: "".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         // pub enum SelfTradeBehavior {
                         //     DecrementTake = 0,
@@ -410,9 +504,12 @@ pub async fn fragment_instruction(
                             transaction_hash: instruction.transaction_hash.clone(),
                             parent_index: instruction.parent_index.clone(),
                             key: "self_trade_behavior".to_string(),
-                            value: (order.self_trade_behavior as u8).to_string(),
+                            value: self_trade_behavior_name(&order.self_trade_behavior),
+                            value_type: ValueType::EnumName,
                             parent_key: "".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         // pub enum OrderType {
                         //     Limit = 0,
@@ -424,9 +521,12 @@ pub async fn fragment_instruction(
                             transaction_hash: instruction.transaction_hash.clone(),
                             parent_index: instruction.parent_index.clone(),
                             key: "order_type".to_string(),
-                            value: (order.order_type as u8).to_string(),
+                            value: order_type_name(&order.order_type),
+                            value_type: ValueType::EnumName,
                             parent_key: "".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         // pub enum Side {
                         //     Bid = 0,
@@ -437,9 +537,12 @@ pub async fn fragment_instruction(
                             transaction_hash: instruction.transaction_hash.clone(),
                             parent_index: instruction.parent_index.clone(),
                             key: "side".to_string(),
-                            value: (order.side as u8).to_string(),
+                            value: side_name(&order.side),
+                            value_type: ValueType::EnumName,
                             parent_key: "".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                     ],
                 })
@@ -458,7 +561,7 @@ pub async fn fragment_instruction(
                 // 10. `[]` spl token program
                 // 11. `[]` the rent sysvar
                 // 12. `[writable]` (optional) the (M)SRM account used for fee discounts
-                Some(InstructionSet {
+                Ok(InstructionSet {
                     function: InstructionFunction {
                         tx_instruction_id: instruction.tx_instruction_id.clone(),
                         transaction_hash: instruction.transaction_hash.clone(),
@@ -466,6 +569,8 @@ pub async fn fragment_instruction(
                         program: instruction.program.clone(),
                         function_name: "new-order-v3".to_string(),
                         timestamp: instruction.timestamp.clone(),
+                        slot: instruction.slot.clone(),
+                        block_time: instruction.block_time.clone(),
                     },
                     properties: vec![
                         InstructionProperty {
@@ -474,8 +579,11 @@ pub async fn fragment_instruction(
                             parent_index: instruction.parent_index.clone(),
                             key: "client_order_id".to_string(),
                             value: order.client_order_id.to_string(),
+                            value_type: ValueType::U64,
                             parent_key: "".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         InstructionProperty {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -483,8 +591,11 @@ pub async fn fragment_instruction(
                             parent_index: instruction.parent_index.clone(),
                             key: "limit_price".to_string(),
                             value: order.limit_price.to_string(),
+                            value_type: ValueType::U64,
                             parent_key: "".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         InstructionProperty {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -492,8 +603,11 @@ pub async fn fragment_instruction(
                             parent_index: instruction.parent_index.clone(),
                             key: "limit".to_string(),
                             value: order.limit.to_string(),
+                            value_type: ValueType::U64,
                             parent_key: "".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         InstructionProperty {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -501,8 +615,11 @@ pub async fn fragment_instruction(
                             parent_index: instruction.parent_index.clone(),
                             key: "max_coin_qty".to_string(),
                             value: order.max_coin_qty.to_string(),
+                            value_type: ValueType::U64,
                             parent_key: "".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         // pub enum SelfTradeBehavior {
                         //     DecrementTake = 0,
@@ -514,9 +631,12 @@ pub async fn fragment_instruction(
                             transaction_hash: instruction.transaction_hash.clone(),
                             parent_index: instruction.parent_index.clone(),
                             key: "self_trade_behavior".to_string(),
-                            value: (order.self_trade_behavior as u8).to_string(),
+                            value: self_trade_behavior_name(&order.self_trade_behavior),
+                            value_type: ValueType::EnumName,
                             parent_key: "".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         // pub enum OrderType {
                         //     Limit = 0,
@@ -528,9 +648,12 @@ pub async fn fragment_instruction(
                             transaction_hash: instruction.transaction_hash.clone(),
                             parent_index: instruction.parent_index.clone(),
                             key: "order_type".to_string(),
-                            value: (order.order_type as u8).to_string(),
+                            value: order_type_name(&order.order_type),
+                            value_type: ValueType::EnumName,
                             parent_key: "".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         // pub enum Side {
                         //     Bid = 0,
@@ -541,9 +664,12 @@ pub async fn fragment_instruction(
                             transaction_hash: instruction.transaction_hash.clone(),
                             parent_index: instruction.parent_index.clone(),
                             key: "side".to_string(),
-                            value: (order.side as u8).to_string(),
+                            value: side_name(&order.side),
+                            value_type: ValueType::EnumName,
                             parent_key: "".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         InstructionProperty {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -551,8 +677,11 @@ pub async fn fragment_instruction(
                             parent_index: instruction.parent_index.clone(),
                             key: "max_native_pc_qty_including_fees".to_string(),
                             value: order.max_native_pc_qty_including_fees.to_string(),
+                            value_type: ValueType::U64,
                             parent_key: "".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                     ],
                 })
@@ -564,7 +693,7 @@ pub async fn fragment_instruction(
                 // 3. `[writable]` OpenOrders
                 // 4. `[signer]` the OpenOrders owner
                 // 5. `[writable]` event_q
-                Some(InstructionSet {
+                Ok(InstructionSet {
                     function: InstructionFunction {
                         tx_instruction_id: instruction.tx_instruction_id.clone(),
                         transaction_hash: instruction.transaction_hash.clone(),
@@ -572,6 +701,8 @@ pub async fn fragment_instruction(
                         program: instruction.program.clone(),
                         function_name: "cancel-order-v2".to_string(),
                         timestamp: instruction.timestamp.clone(),
+                        slot: instruction.slot.clone(),
+                        block_time: instruction.block_time.clone(),
                     },
                     properties: vec![
                         InstructionProperty {
@@ -580,8 +711,11 @@ pub async fn fragment_instruction(
                             parent_index: instruction.parent_index.clone(),
                             key: "order_id".to_string(),
                             value: order.order_id.to_string(),
+                            value_type: ValueType::U128,
                             parent_key: "".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         // pub enum Side {
                         //     Bid = 0,
@@ -592,9 +726,12 @@ pub async fn fragment_instruction(
                             transaction_hash: instruction.transaction_hash.clone(),
                             parent_index: instruction.parent_index.clone(),
                             key: "side".to_string(),
-                            value: (order.side as u8).to_string(),
+                            value: side_name(&order.side),
+                            value_type: ValueType::EnumName,
                             parent_key: "".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                     ],
                 })
@@ -606,7 +743,7 @@ pub async fn fragment_instruction(
                 // 3. `[writable]` OpenOrders
                 // 4. `[signer]` the OpenOrders owner
                 // 5. `[writable]` event_q
-                Some(InstructionSet {
+                Ok(InstructionSet {
                     function: InstructionFunction {
                         tx_instruction_id: instruction.tx_instruction_id.clone(),
                         transaction_hash: instruction.transaction_hash.clone(),
@@ -614,6 +751,8 @@ pub async fn fragment_instruction(
                         program: instruction.program.clone(),
                         function_name: "cancel-order-by-client-id-v2".to_string(),
                         timestamp: instruction.timestamp.clone(),
+                        slot: instruction.slot.clone(),
+                        block_time: instruction.block_time.clone(),
                     },
                     properties: vec![
                         InstructionProperty {
@@ -622,8 +761,11 @@ pub async fn fragment_instruction(
                             parent_index: instruction.parent_index.clone(),
                             key: "client_id".to_string(),
                             value: client_id.to_string(),
+                            value_type: ValueType::U64,
                             parent_key: "".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                     ],
                 })
@@ -634,7 +776,7 @@ pub async fn fragment_instruction(
                 // 2. `[writable]` asks
                 // 3. `[writable]` OpenOrders
                 // 4. `[]`
-                Some(InstructionSet {
+                Ok(InstructionSet {
                     function: InstructionFunction {
                         tx_instruction_id: instruction.tx_instruction_id.clone(),
                         transaction_hash: instruction.transaction_hash.clone(),
@@ -642,6 +784,8 @@ pub async fn fragment_instruction(
                         program: instruction.program.clone(),
                         function_name: "send-take".to_string(),
                         timestamp: instruction.timestamp.clone(),
+                        slot: instruction.slot.clone(),
+                        block_time: instruction.block_time.clone(),
                     },
                     properties: vec![
                         InstructionProperty {
@@ -649,9 +793,12 @@ pub async fn fragment_instruction(
                             transaction_hash: instruction.transaction_hash.clone(),
                             parent_index: instruction.parent_index.clone(),
                             key: "side".to_string(),
-                            value: (sti.side as u8).to_string(),
+                            value: side_name(&sti.side),
+                            value_type: ValueType::EnumName,
                             parent_key: "".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         InstructionProperty {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -659,8 +806,11 @@ pub async fn fragment_instruction(
                             parent_index: instruction.parent_index.clone(),
                             key: "max_native_pc_qty_including_fees".to_string(),
                             value: sti.max_native_pc_qty_including_fees.to_string(),
+                            value_type: ValueType::U64,
                             parent_key: "".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         InstructionProperty {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -668,8 +818,11 @@ pub async fn fragment_instruction(
                             parent_index: instruction.parent_index.clone(),
                             key: "max_coin_qty".to_string(),
                             value: sti.max_coin_qty.to_string(),
+                            value_type: ValueType::U64,
                             parent_key: "".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         InstructionProperty {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -677,8 +830,11 @@ pub async fn fragment_instruction(
                             parent_index: instruction.parent_index.clone(),
                             key: "limit".to_string(),
                             value: sti.limit.to_string(),
+                            value_type: ValueType::U64,
                             parent_key: "".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         InstructionProperty {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -686,8 +842,11 @@ pub async fn fragment_instruction(
                             parent_index: instruction.parent_index.clone(),
                             key: "limit_price".to_string(),
                             value: sti.limit_price.to_string(),
+                            value_type: ValueType::U64,
                             parent_key: "".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         InstructionProperty {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -695,8 +854,11 @@ pub async fn fragment_instruction(
                             parent_index: instruction.parent_index.clone(),
                             key: "min_coin_qty".to_string(),
                             value: sti.min_coin_qty.to_string(),
+                            value_type: ValueType::U64,
                             parent_key: "".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         InstructionProperty {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -704,8 +866,11 @@ pub async fn fragment_instruction(
                             parent_index: instruction.parent_index.clone(),
                             key: "min_native_pc_qty".to_string(),
                             value: sti.min_native_pc_qty.to_string(),
+                            value_type: ValueType::U64,
                             parent_key: "".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         }
                     ],
                 })
@@ -715,13 +880,15 @@ pub async fn fragment_instruction(
                 // 1. `[signer]` the OpenOrders owner
                 // 2. `[writable]` the destination account to send rent exemption SOL to
                 // 3. `[]` market
-                Some(InstructionSet {
+                Ok(InstructionSet {
                     function: InstructionFunction {
                         tx_instruction_id: instruction.tx_instruction_id.clone(),
                         transaction_hash: instruction.transaction_hash.clone(),
                         parent_index: instruction.parent_index.clone(),
                         program: instruction.program.clone(),
                         timestamp: instruction.timestamp.clone(),
+                        slot: instruction.slot.clone(),
+                        block_time: instruction.block_time.clone(),
                         function_name: "close-open-orders".to_string(),
                     },
                     properties: vec![],
@@ -732,26 +899,30 @@ pub async fn fragment_instruction(
                 // 1. `[signer]` the OpenOrders owner
                 // 2. `[writable]` the destination account to send rent exemption SOL to
                 // 3. `[]` market
-                Some(InstructionSet {
+                Ok(InstructionSet {
                     function: InstructionFunction {
                         tx_instruction_id: instruction.tx_instruction_id.clone(),
                         transaction_hash: instruction.transaction_hash.clone(),
                         parent_index: instruction.parent_index.clone(),
                         program: instruction.program.clone(),
                         timestamp: instruction.timestamp.clone(),
+                        slot: instruction.slot.clone(),
+                        block_time: instruction.block_time.clone(),
                         function_name: "init-open-orders".to_string(),
                     },
                     properties: vec![],
                 })
             }
             MarketInstruction::Prune(limit) => {
-                Some(InstructionSet {
+                Ok(InstructionSet {
                     function: InstructionFunction {
                         tx_instruction_id: instruction.tx_instruction_id.clone(),
                         transaction_hash: instruction.transaction_hash.clone(),
                         parent_index: instruction.parent_index.clone(),
                         program: instruction.program.clone(),
                         timestamp: instruction.timestamp.clone(),
+                        slot: instruction.slot.clone(),
+                        block_time: instruction.block_time.clone(),
                         function_name: "prune".to_string(),
                     },
                     properties: vec![
@@ -761,15 +932,576 @@ pub async fn fragment_instruction(
                             parent_index: instruction.parent_index.clone(),
                             key: "limit".to_string(),
                             value: limit.to_string(),
+                            value_type: ValueType::U64,
                             parent_key: "".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         }
                     ],
                 })
             }
         };
+
+        return instruction_set.map(|mut set| {
+            set.properties.extend(account_properties);
+            set
+        });
     }
 
     error!("{}", "[processors/programs/serum/market] FATAL: Unrecognised instruction.".to_string());
-    None
+    Err(DecodeError::from_program_error(&instruction.program,
+                                        &ProgramError::InvalidInstructionData))
+}
+
+/// Every account of the dex is wrapped between these two paddings.
+const ACCOUNT_HEAD_PADDING: &[u8; 5] = b"serum";
+const ACCOUNT_TAIL_PADDING: &[u8; 7] = b"padding";
+
+/// The header of the bids and asks slabs, after the account flags: the bump index, the free list
+/// length and head, the root node and the leaf count.
+const SLAB_HEADER_LEN: usize = 32;
+const SLAB_NODE_LEN: usize = 72;
+const SLAB_LEAF_TAG: u32 = 2;
+
+/// The header of the request and event queues, after the account flags: the head, count and
+/// sequence number.
+const QUEUE_HEADER_LEN: usize = 24;
+
+/// Decodes the data of a Serum market, open orders, bids, asks, request queue or event queue
+/// account.
+pub async fn fragment_account(
+    // The record holding the account's data
+    account: AccountRecord,
+) -> Result<InstructionSet, DecodeError> {
+    let decoded = match strip_padding(account.data.as_slice()) {
+        Some((flags, data)) => {
+            if flags & AccountFlag::Market as u64 != 0 {
+                read::<MarketState>(data).map(|market| InstructionSet {
+                    function: account.function("market"),
+                    properties: market_properties(&account, &market),
+                })
+            } else if flags & AccountFlag::OpenOrders as u64 != 0 {
+                read::<OpenOrders>(data).map(|open_orders| InstructionSet {
+                    function: account.function("open-orders"),
+                    properties: open_orders_properties(&account, &open_orders),
+                })
+            } else if flags & AccountFlag::Bids as u64 != 0 {
+                slab_properties(&account, data, Side::Bid).map(|properties| InstructionSet {
+                    function: account.function("bids"),
+                    properties,
+                })
+            } else if flags & AccountFlag::Asks as u64 != 0 {
+                slab_properties(&account, data, Side::Ask).map(|properties| InstructionSet {
+                    function: account.function("asks"),
+                    properties,
+                })
+            } else if flags & AccountFlag::RequestQueue as u64 != 0 {
+                request_queue_properties(&account, data).map(|properties| InstructionSet {
+                    function: account.function("request-queue"),
+                    properties,
+                })
+            } else if flags & AccountFlag::EventQueue as u64 != 0 {
+                event_queue_properties(&account, data).map(|properties| InstructionSet {
+                    function: account.function("event-queue"),
+                    properties,
+                })
+            } else {
+                None
+            }
+        }
+        None => None,
+    };
+
+    decoded.ok_or_else(|| {
+        error!("[spi-wrapper/programs/serum_market] Attempt to parse account {} failed.",
+               account.pubkey);
+
+        DecodeError::from_program_error(&account.owner, &ProgramError::InvalidAccountData)
+    })
+}
+
+/// Checks the paddings around the data of a dex account, returning its account flags and the
+/// data in between the paddings (account flags included).
+fn strip_padding(data: &[u8]) -> Option<(u64, &[u8])> {
+    let head = ACCOUNT_HEAD_PADDING.len();
+    let tail = ACCOUNT_TAIL_PADDING.len();
+    if data.len() < head + tail + 8
+        || &data[..head] != ACCOUNT_HEAD_PADDING
+        || &data[data.len() - tail..] != ACCOUNT_TAIL_PADDING {
+        return None;
+    }
+
+    let data = &data[head..data.len() - tail];
+    Some((read::<u64>(data)?, data))
+}
+
+/// Reads a dex struct from the start of the data. The data is copied rather than cast in place,
+/// as account data is not guaranteed to be aligned.
+fn read<T: Pod>(data: &[u8]) -> Option<T> {
+    let size = size_of::<T>();
+    if data.len() < size {
+        return None;
+    }
+
+    let mut value = T::zeroed();
+    bytes_of_mut(&mut value).copy_from_slice(&data[..size]);
+    Some(value)
+}
+
+fn key_string(key: [u64; 4]) -> String {
+    Pubkey::new_from_array(bytemuck::cast(key)).to_string()
+}
+
+fn market_properties(account: &AccountRecord, market: &MarketState) -> Vec<InstructionProperty> {
+    vec![
+        account.property("", "account_flags", { market.account_flags }.to_string(),
+                         ValueType::U64),
+        account.property("", "own_address", key_string(market.own_address), ValueType::Pubkey),
+        account.property("", "vault_signer_nonce", { market.vault_signer_nonce }.to_string(),
+                         ValueType::U64),
+        account.property("", "coin_mint", key_string(market.coin_mint), ValueType::Pubkey),
+        account.property("", "pc_mint", key_string(market.pc_mint), ValueType::Pubkey),
+        account.property("", "coin_vault", key_string(market.coin_vault), ValueType::Pubkey),
+        account.property("", "coin_deposits_total", { market.coin_deposits_total }.to_string(),
+                         ValueType::U64),
+        account.property("", "coin_fees_accrued", { market.coin_fees_accrued }.to_string(),
+                         ValueType::U64),
+        account.property("", "pc_vault", key_string(market.pc_vault), ValueType::Pubkey),
+        account.property("", "pc_deposits_total", { market.pc_deposits_total }.to_string(),
+                         ValueType::U64),
+        account.property("", "pc_fees_accrued", { market.pc_fees_accrued }.to_string(),
+                         ValueType::U64),
+        account.property("", "pc_dust_threshold", { market.pc_dust_threshold }.to_string(),
+                         ValueType::U64),
+        account.property("", "request_queue", key_string(market.req_q), ValueType::Pubkey),
+        account.property("", "event_queue", key_string(market.event_q), ValueType::Pubkey),
+        account.property("", "bids", key_string(market.bids), ValueType::Pubkey),
+        account.property("", "asks", key_string(market.asks), ValueType::Pubkey),
+        account.property("", "coin_lot_size", { market.coin_lot_size }.to_string(),
+                         ValueType::U64),
+        account.property("", "pc_lot_size", { market.pc_lot_size }.to_string(), ValueType::U64),
+        account.property("", "fee_rate_bps", { market.fee_rate_bps }.to_string(),
+                         ValueType::U64),
+        account.property("", "referrer_rebates_accrued",
+                         { market.referrer_rebates_accrued }.to_string(), ValueType::U64),
+    ]
+}
+
+fn open_orders_properties(
+    account: &AccountRecord,
+    open_orders: &OpenOrders,
+) -> Vec<InstructionProperty> {
+    let mut properties = vec![
+        account.property("", "account_flags", { open_orders.account_flags }.to_string(),
+                         ValueType::U64),
+        account.property("", "market", key_string(open_orders.market), ValueType::Pubkey),
+        account.property("", "owner", key_string(open_orders.owner), ValueType::Pubkey),
+        account.property("", "native_coin_free", { open_orders.native_coin_free }.to_string(),
+                         ValueType::U64),
+        account.property("", "native_coin_total", { open_orders.native_coin_total }.to_string(),
+                         ValueType::U64),
+        account.property("", "native_pc_free", { open_orders.native_pc_free }.to_string(),
+                         ValueType::U64),
+        account.property("", "native_pc_total", { open_orders.native_pc_total }.to_string(),
+                         ValueType::U64),
+        account.property("", "referrer_rebates_accrued",
+                         { open_orders.referrer_rebates_accrued }.to_string(), ValueType::U64),
+    ];
+
+    // Only the slots that are taken hold an order.
+    let free_slot_bits = open_orders.free_slot_bits;
+    let is_bid_bits = open_orders.is_bid_bits;
+    let orders = open_orders.orders;
+    let client_order_ids = open_orders.client_order_ids;
+    let taken_slots = (0..orders.len()).filter(|slot| free_slot_bits & (1u128 << slot) == 0);
+    for (idx, slot) in taken_slots.enumerate() {
+        let parent_key = path::join(&["orders", &idx.to_string()]);
+        let side = if is_bid_bits & (1u128 << slot) != 0 { Side::Bid } else { Side::Ask };
+        properties.extend(vec![
+            account.property(&parent_key, "slot", slot.to_string(), ValueType::U64),
+            account.property(&parent_key, "side", side_name(&side), ValueType::EnumName),
+            account.property(&parent_key, "order_id", orders[slot].to_string(), ValueType::U128),
+            account.property(&parent_key, "price", ((orders[slot] >> 64) as u64).to_string(),
+                             ValueType::U64),
+            account.property(&parent_key, "client_order_id", client_order_ids[slot].to_string(),
+                             ValueType::U64),
+        ]);
+    }
+
+    properties
+}
+
+/// The orders of a bids or asks slab, best price first, followed by the price levels they add up
+/// to. Prices and quantities are in lots.
+fn slab_properties(
+    account: &AccountRecord,
+    data: &[u8],
+    side: Side,
+) -> Option<Vec<InstructionProperty>> {
+    let header = data.get(8..8 + SLAB_HEADER_LEN)?;
+    let bump_index = read::<u64>(header)? as usize;
+    let nodes = &data[8 + SLAB_HEADER_LEN..];
+
+    // Nodes past the bump index were never used, and freed leaves are tagged as free nodes.
+    let mut leaves: Vec<LeafNode> = nodes.chunks_exact(SLAB_NODE_LEN)
+        .take(bump_index)
+        .filter(|node| read::<u32>(node) == Some(SLAB_LEAF_TAG))
+        .filter_map(|node| read::<LeafNode>(node))
+        .collect();
+
+    // Order ids are the price followed by a sequence number, so they order the book by price
+    // and then by time.
+    leaves.sort_by_key(|leaf| leaf.order_id());
+    if side == Side::Bid {
+        leaves.reverse();
+    }
+
+    let mut properties = vec![
+        account.property("", "side", side_name(&side), ValueType::EnumName),
+    ];
+    let mut levels: Vec<(u64, u64, u64)> = vec![];
+    for (idx, leaf) in leaves.iter().enumerate() {
+        let price = (leaf.order_id() >> 64) as u64;
+        let parent_key = path::join(&["orders", &idx.to_string()]);
+        properties.extend(vec![
+            account.property(&parent_key, "price", price.to_string(), ValueType::U64),
+            account.property(&parent_key, "quantity", leaf.quantity().to_string(),
+                             ValueType::U64),
+            account.property(&parent_key, "order_id", leaf.order_id().to_string(),
+                             ValueType::U128),
+            account.property(&parent_key, "open_orders", key_string(leaf.owner()),
+                             ValueType::Pubkey),
+            account.property(&parent_key, "owner_slot", leaf.owner_slot().to_string(),
+                             ValueType::U64),
+            account.property(&parent_key, "client_order_id", leaf.client_order_id().to_string(),
+                             ValueType::U64),
+        ]);
+
+        match levels.last_mut() {
+            Some((level_price, quantity, orders)) if *level_price == price => {
+                *quantity += leaf.quantity();
+                *orders += 1;
+            }
+            _ => levels.push((price, leaf.quantity(), 1)),
+        }
+    }
+
+    for (idx, (price, quantity, orders)) in levels.iter().enumerate() {
+        let parent_key = path::join(&["levels", &idx.to_string()]);
+        properties.extend(vec![
+            account.property(&parent_key, "price", price.to_string(), ValueType::U64),
+            account.property(&parent_key, "quantity", quantity.to_string(), ValueType::U64),
+            account.property(&parent_key, "orders", orders.to_string(), ValueType::U64),
+        ]);
+    }
+
+    Some(properties)
+}
+
+/// The header of a request or event queue, along with the entries waiting in it, oldest first.
+/// The queue is a ring buffer, read from its head onwards.
+fn queue_properties<T: Pod>(
+    account: &AccountRecord,
+    data: &[u8],
+) -> Option<(Vec<InstructionProperty>, Vec<T>)> {
+    let header = data.get(8..8 + QUEUE_HEADER_LEN)?;
+    let head = read::<u64>(&header[0..8])?;
+    let count = read::<u64>(&header[8..16])?;
+    let seq_num = read::<u64>(&header[16..24])?;
+
+    let slots: Vec<&[u8]> = data[8 + QUEUE_HEADER_LEN..]
+        .chunks_exact(size_of::<T>())
+        .collect();
+    if slots.is_empty() || count > slots.len() as u64 {
+        return None;
+    }
+
+    // The head of a malformed queue can be anywhere, so it is brought within the queue before
+    // the entries are counted from it.
+    let start = (head % slots.len() as u64) as usize;
+    let entries = (0..count as usize)
+        .map(|idx| read::<T>(slots[(start + idx) % slots.len()]))
+        .collect::<Option<Vec<T>>>()?;
+
+    let properties = vec![
+        account.property("", "head", head.to_string(), ValueType::U64),
+        account.property("", "count", count.to_string(), ValueType::U64),
+        account.property("", "seq_num", seq_num.to_string(), ValueType::U64),
+    ];
+
+    Some((properties, entries))
+}
+
+/// The requests waiting in the queue to be matched, oldest first.
+fn request_queue_properties(
+    account: &AccountRecord,
+    data: &[u8],
+) -> Option<Vec<InstructionProperty>> {
+    let (mut properties, requests) = queue_properties::<Request>(account, data)?;
+
+    for (idx, request) in requests.iter().enumerate() {
+        let parent_key = path::join(&["requests", &idx.to_string()]);
+        properties.extend(match request.as_view().ok()? {
+            RequestView::NewOrder {
+                side, order_type, owner_slot, order_id, max_coin_qty, native_pc_qty_locked,
+                owner, client_order_id, self_trade_behavior, ..
+            } => vec![
+                account.property(&parent_key, "type", "NewOrder".to_string(),
+                                 ValueType::EnumName),
+                account.property(&parent_key, "side", side_name(&side), ValueType::EnumName),
+                account.property(&parent_key, "order_type", order_type_name(&order_type),
+                                 ValueType::EnumName),
+                account.property(&parent_key, "order_id", order_id.to_string(), ValueType::U128),
+                account.property(&parent_key, "price", ((order_id >> 64) as u64).to_string(),
+                                 ValueType::U64),
+                account.property(&parent_key, "max_coin_qty", max_coin_qty.to_string(),
+                                 ValueType::U64),
+                account.optional_property(&parent_key, "native_pc_qty_locked",
+                                          native_pc_qty_locked.map(|qty| qty.to_string()),
+                                          ValueType::U64),
+                account.property(&parent_key, "open_orders", key_string(owner),
+                                 ValueType::Pubkey),
+                account.property(&parent_key, "owner_slot", owner_slot.to_string(),
+                                 ValueType::U64),
+                account.property(&parent_key, "client_order_id", client_order_id.to_string(),
+                                 ValueType::U64),
+                account.property(&parent_key, "self_trade_behavior",
+                                 self_trade_behavior_name(&self_trade_behavior),
+                                 ValueType::EnumName),
+            ],
+            RequestView::CancelOrder {
+                side, order_id, cancel_id, expected_owner_slot, expected_owner,
+                client_order_id, ..
+            } => vec![
+                account.property(&parent_key, "type", "CancelOrder".to_string(),
+                                 ValueType::EnumName),
+                account.property(&parent_key, "side", side_name(&side), ValueType::EnumName),
+                account.property(&parent_key, "order_id", order_id.to_string(), ValueType::U128),
+                account.property(&parent_key, "cancel_id", cancel_id.to_string(),
+                                 ValueType::U64),
+                account.property(&parent_key, "open_orders", key_string(expected_owner),
+                                 ValueType::Pubkey),
+                account.property(&parent_key, "owner_slot", expected_owner_slot.to_string(),
+                                 ValueType::U64),
+                account.optional_property(&parent_key, "client_order_id",
+                                          client_order_id.map(|id| id.to_string()),
+                                          ValueType::U64),
+            ],
+        });
+    }
+
+    Some(properties)
+}
+
+/// The events waiting in the queue to be consumed, oldest first.
+fn event_queue_properties(
+    account: &AccountRecord,
+    data: &[u8],
+) -> Option<Vec<InstructionProperty>> {
+    let (mut properties, events) = queue_properties::<Event>(account, data)?;
+
+    for (idx, event) in events.iter().enumerate() {
+        let parent_key = path::join(&["events", &idx.to_string()]);
+        properties.extend(match event.as_view().ok()? {
+            EventView::Fill {
+                side, maker, native_qty_paid, native_qty_received, native_fee_or_rebate,
+                order_id, owner, owner_slot, client_order_id, ..
+            } => vec![
+                account.property(&parent_key, "type", "Fill".to_string(), ValueType::EnumName),
+                account.property(&parent_key, "side", side_name(&side), ValueType::EnumName),
+                account.property(&parent_key, "maker", maker.to_string(), ValueType::Bool),
+                account.property(&parent_key, "native_qty_paid", native_qty_paid.to_string(),
+                                 ValueType::U64),
+                account.property(&parent_key, "native_qty_received",
+                                 native_qty_received.to_string(), ValueType::U64),
+                account.property(&parent_key, "native_fee_or_rebate",
+                                 native_fee_or_rebate.to_string(), ValueType::U64),
+                account.property(&parent_key, "order_id", order_id.to_string(), ValueType::U128),
+                account.property(&parent_key, "price", ((order_id >> 64) as u64).to_string(),
+                                 ValueType::U64),
+                account.property(&parent_key, "open_orders", key_string(owner),
+                                 ValueType::Pubkey),
+                account.property(&parent_key, "owner_slot", owner_slot.to_string(),
+                                 ValueType::U64),
+                account.optional_property(&parent_key, "client_order_id",
+                                          client_order_id.map(|id| id.to_string()),
+                                          ValueType::U64),
+            ],
+            EventView::Out {
+                side, release_funds, native_qty_unlocked, native_qty_still_locked, order_id,
+                owner, owner_slot, client_order_id, ..
+            } => vec![
+                account.property(&parent_key, "type", "Out".to_string(), ValueType::EnumName),
+                account.property(&parent_key, "side", side_name(&side), ValueType::EnumName),
+                account.property(&parent_key, "release_funds", release_funds.to_string(),
+                                 ValueType::Bool),
+                account.property(&parent_key, "native_qty_unlocked",
+                                 native_qty_unlocked.to_string(), ValueType::U64),
+                account.property(&parent_key, "native_qty_still_locked",
+                                 native_qty_still_locked.to_string(), ValueType::U64),
+                account.property(&parent_key, "order_id", order_id.to_string(), ValueType::U128),
+                account.property(&parent_key, "price", ((order_id >> 64) as u64).to_string(),
+                                 ValueType::U64),
+                account.property(&parent_key, "open_orders", key_string(owner),
+                                 ValueType::Pubkey),
+                account.property(&parent_key, "owner_slot", owner_slot.to_string(),
+                                 ValueType::U64),
+                account.optional_property(&parent_key, "client_order_id",
+                                          client_order_id.map(|id| id.to_string()),
+                                          ValueType::U64),
+            ],
+        });
+    }
+
+    Some(properties)
+}
+
+/// The roles of the accounts each instruction expects, in order.
+fn account_roles(instruction: &MarketInstruction) -> &'static [&'static str] {
+    match instruction {
+        MarketInstruction::InitializeMarket(_) => &[
+            "market", "request_queue", "event_queue", "bids", "asks", "coin_vault", "pc_vault",
+            "coin_mint", "pc_mint", "rent_sysvar",
+        ],
+        MarketInstruction::NewOrder(_) | MarketInstruction::NewOrderV2(_) => &[
+            "market", "open_orders", "request_queue", "payer", "owner", "coin_vault", "pc_vault",
+            "token_program", "rent_sysvar", "fee_discount_account",
+        ],
+        MarketInstruction::MatchOrders(_) => &[
+            "market", "request_queue", "event_queue", "bids", "asks", "coin_fee_receivable",
+            "pc_fee_receivable",
+        ],
+        MarketInstruction::ConsumeEvents(_) => &[
+            "open_orders*", "market", "event_queue", "coin_fee_receivable", "pc_fee_receivable",
+        ],
+        MarketInstruction::CancelOrder(_) | MarketInstruction::CancelOrderByClientId(_) => &[
+            "market", "open_orders", "request_queue", "owner",
+        ],
+        MarketInstruction::SettleFunds => &[
+            "market", "open_orders", "owner", "coin_vault", "pc_vault", "coin_wallet", "pc_wallet",
+            "vault_signer", "token_program", "referrer_pc_wallet",
+        ],
+        MarketInstruction::DisableMarket => &["market", "disable_authority"],
+        MarketInstruction::SweepFees => &[
+            "market", "pc_vault", "fee_sweeping_authority", "fee_receivable", "vault_signer",
+            "token_program",
+        ],
+        MarketInstruction::NewOrderV3(_) => &[
+            "market", "open_orders", "request_queue", "event_queue", "bids", "asks", "payer",
+            "owner", "coin_vault", "pc_vault", "token_program", "rent_sysvar",
+            "fee_discount_account",
+        ],
+        MarketInstruction::CancelOrderV2(_) | MarketInstruction::CancelOrderByClientIdV2(_) => &[
+            "market", "bids", "asks", "open_orders", "owner", "event_queue",
+        ],
+        MarketInstruction::SendTake(_) => &[
+            "market", "request_queue", "event_queue", "bids", "asks", "coin_wallet", "pc_wallet",
+            "owner", "coin_vault", "pc_vault", "token_program", "vault_signer",
+            "fee_discount_account",
+        ],
+        MarketInstruction::CloseOpenOrders => &["open_orders", "owner", "destination", "market"],
+        MarketInstruction::InitOpenOrders => &[
+            "open_orders", "owner", "market", "rent_sysvar", "market_authority",
+        ],
+        MarketInstruction::Prune(_) => &[
+            "market", "bids", "asks", "prune_authority", "open_orders", "open_orders_owner",
+            "event_queue",
+        ],
+    }
+}
+
+fn side_name(side: &Side) -> String {
+    match side {
+        Side::Bid => "Bid".to_string(),
+        Side::Ask => "Ask".to_string(),
+    }
+}
+
+fn order_type_name(order_type: &OrderType) -> String {
+    match order_type {
+        OrderType::Limit => "Limit".to_string(),
+        OrderType::ImmediateOrCancel => "ImmediateOrCancel".to_string(),
+        OrderType::PostOnly => "PostOnly".to_string(),
+    }
+}
+
+fn self_trade_behavior_name(self_trade_behavior: &SelfTradeBehavior) -> String {
+    match self_trade_behavior {
+        SelfTradeBehavior::DecrementTake => "DecrementTake".to_string(),
+        SelfTradeBehavior::CancelProvide => "CancelProvide".to_string(),
+        SelfTradeBehavior::AbortTransaction => "AbortTransaction".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::decode_account;
+
+    #[test]
+    fn serum_slabs_are_grouped_into_price_levels() {
+        // Leaves are tagged 2, with their key (price and sequence number) at offset 8 and their
+        // quantity at offset 56.
+        let leaf = |price: u64, seq_num: u64, quantity: u64| {
+            let mut node = vec![0u8; 72];
+            node[0..4].copy_from_slice(&2u32.to_le_bytes());
+            node[8..24].copy_from_slice(&(((price as u128) << 64) | seq_num as u128)
+                .to_le_bytes());
+            node[56..64].copy_from_slice(&quantity.to_le_bytes());
+            node
+        };
+
+        let mut data = b"serum".to_vec();
+        data.extend_from_slice(&(1u64 | 1 << 6).to_le_bytes());
+        data.extend_from_slice(&3u64.to_le_bytes());
+        data.extend_from_slice(&[0; 24]);
+        data.extend(leaf(12, 1, 5));
+        data.extend(leaf(10, 2, 1));
+        data.extend(leaf(10, 3, 2));
+        data.extend_from_slice(b"padding");
+
+        let asks = decode_account(PROGRAM_ADDRESS_V3, data).state;
+        assert_eq!(asks.function.function_name, "asks");
+        let json = path::properties_to_json(&asks.properties);
+        assert_eq!(json["levels"], serde_json::json!([
+            {"price": 10, "quantity": 3, "orders": 2},
+            {"price": 12, "quantity": 5, "orders": 1},
+        ]));
+        assert_eq!(json["orders"][0]["quantity"], 1);
+    }
+
+    #[test]
+    fn serum_queues_are_read_around_a_malformed_head() {
+        let owner = Pubkey::new_unique();
+        let mut cancel = vec![0u8; size_of::<Request>()];
+        // Cancel a bid, by the order and client order ids.
+        cancel[0] = 0x02 | 0x04;
+        cancel[1] = 3;
+        cancel[8..16].copy_from_slice(&9u64.to_le_bytes());
+        cancel[24..40].copy_from_slice(&((10u128 << 64) | 1).to_le_bytes());
+        cancel[40..72].copy_from_slice(owner.as_ref());
+        cancel[72..80].copy_from_slice(&9u64.to_le_bytes());
+
+        // A head far past the two slots of the queue points to its second one.
+        let mut data = b"serum".to_vec();
+        data.extend_from_slice(
+            &(AccountFlag::Initialized as u64 | AccountFlag::RequestQueue as u64).to_le_bytes());
+        data.extend_from_slice(&u64::MAX.to_le_bytes());
+        data.extend_from_slice(&1u64.to_le_bytes());
+        data.extend_from_slice(&4u64.to_le_bytes());
+        data.extend(vec![0u8; size_of::<Request>()]);
+        data.extend(cancel);
+        data.extend_from_slice(b"padding");
+
+        let requests = decode_account(PROGRAM_ADDRESS_V3, data).state;
+        assert_eq!(requests.function.function_name, "request-queue");
+        let json = path::properties_to_json(&requests.properties);
+        assert_eq!(json["head"], u64::MAX);
+        assert_eq!(json["requests"][0]["type"], "CancelOrder");
+        assert_eq!(json["requests"][0]["side"], "Bid");
+        assert_eq!(json["requests"][0]["cancel_id"], 9);
+        assert_eq!(json["requests"][0]["open_orders"], owner.to_string());
+        assert_eq!(json["requests"][0]["owner_slot"], 3);
+    }
 }
@@ -0,0 +1,70 @@
+//! What the secp256k1 and ed25519 signature verification programs have in common: how checking
+//! one of their signatures turns out, and how that is reported.
+
+use std::fmt::Debug;
+
+use crate::{Instruction, InstructionProperty, Record, ValueType};
+
+/// Why a signature did not pass.
+#[derive(Debug)]
+pub(crate) enum Failure<E> {
+    // The program would have rejected the signature, with this error.
+    Invalid(E),
+    // The signature points into an instruction that was not handed over, so it cannot be checked.
+    MissingInstruction,
+}
+
+impl<E> From<E> for Failure<E> {
+    fn from(err: E) -> Self {
+        Failure::Invalid(err)
+    }
+}
+
+/// The parts a signature is made of, as far as they could be found, and whether it passed.
+pub(crate) struct SignatureCheck<P, E> {
+    pub parts: P,
+    pub failure: Option<Failure<E>>,
+}
+
+impl<P: Default, E> SignatureCheck<P, E> {
+    /// Checks a signature with `verify`, which fills the parts in as it finds them.
+    pub fn run(verify: impl FnOnce(&mut P) -> Result<(), Failure<E>>) -> Self {
+        let mut parts = P::default();
+        let failure = verify(&mut parts).err();
+
+        SignatureCheck { parts, failure }
+    }
+
+    /// A signature whose offsets could not even be read.
+    pub fn invalid(err: E) -> Self {
+        SignatureCheck { parts: P::default(), failure: Some(Failure::Invalid(err)) }
+    }
+}
+
+/// What fails the whole instruction: its first invalid signature. Otherwise, a signature that
+/// could not be checked for lack of the other instructions leaves the outcome unknown.
+pub(crate) fn instruction_failure<E>(failures: &[Option<Failure<E>>]) -> Option<&Failure<E>> {
+    failures.iter()
+        .flatten()
+        .find(|failure| matches!(failure, Failure::Invalid(_)))
+        .or_else(|| failures.iter().flatten().next())
+}
+
+/// `Verified`, `Failed` along with the error the program would have failed with, or `Unknown`
+/// when the instructions needed to tell are missing. Verified signatures have no failure.
+pub(crate) fn status_properties<E: Debug>(
+    instruction: &Instruction,
+    parent_key: &str,
+    failure: Option<&Failure<E>>,
+) -> Vec<InstructionProperty> {
+    let (status, reason) = match failure {
+        None => ("Verified", None),
+        Some(Failure::Invalid(err)) => ("Failed", Some(format!("{:?}", err))),
+        Some(Failure::MissingInstruction) => ("Unknown", Some("MissingInstruction".to_string())),
+    };
+
+    vec![
+        instruction.property(parent_key, "status", status.to_string(), ValueType::EnumName),
+        instruction.optional_property(parent_key, "failure", reason, ValueType::EnumName),
+    ]
+}
@@ -1,7 +1,9 @@
 #![allow(missing_docs)]
 /// Derived from https://github.com/project-serum/anchor/blob/9224e0fa99093943a6190e396bccbc3387e5b230/examples/pyth/programs/pyth/src/pc.rs
+use std::mem::size_of;
+
 use bytemuck::{
-    Pod, Zeroable,
+    bytes_of_mut, Pod, PodCastError, Zeroable,
 };
 
 pub const MAGIC: u32 = 0xa1b2c3d4;
@@ -34,6 +36,7 @@ pub enum PriceStatus {
     Trading,
     Halted,
     Auction,
+    Ignored,
 }
 
 #[derive(Copy, Clone)]
@@ -55,9 +58,9 @@ pub struct PriceInfo {
 #[derive(Copy, Clone)]
 #[repr(C)]
 pub struct PriceComp {
-    publisher: AccKey,
-    agg: PriceInfo,
-    latest: PriceInfo,
+    pub publisher: AccKey,
+    pub agg: PriceInfo,
+    pub latest: PriceInfo,
 }
 
 #[derive(PartialEq, Copy, Clone)]
@@ -95,11 +98,73 @@ pub struct Price {
     pub comp: [PriceComp; 32], // price components one per quoter
 }
 
+/// `PriceInfo` as it is laid out in account data, with its enums left as the `u32`s they are
+/// stored as. The data can hold values `PriceStatus` and `CorpAction` have no variant for.
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct RawPriceInfo {
+    pub price: i64,
+    pub conf: u64,
+    pub status: u32,
+    pub corp_act: u32,
+    pub pub_slot: u64,
+}
+
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct RawPriceComp {
+    pub publisher: AccKey,
+    pub agg: RawPriceInfo,
+    pub latest: RawPriceInfo,
+}
+
+/// An exponentially-weighted moving average, kept as the fraction `numer / denom`, with `val`
+/// its value at the last update.
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct RawEma {
+    pub val: i64,
+    pub numer: i64,
+    pub denom: i64,
+}
+
+/// A price account as version 2 of the program lays it out, with its enums left as the `u32`s
+/// they are stored as, so any account data can be read into it.
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct RawPrice {
+    pub magic: u32,               // pyth magic number
+    pub ver: u32,                 // program version
+    pub atype: u32,               // account type
+    pub size: u32,                // price account size
+    pub ptype: u32,               // price or calculation type
+    pub expo: i32,                // price exponent
+    pub num: u32,                 // number of component prices
+    pub num_qt: u32,              // number of quoters that make up aggregate
+    pub last_slot: u64,           // slot of last valid (not unknown) aggregate price
+    pub valid_slot: u64,          // valid slot-time of agg. price
+    pub twap: RawEma,             // time-weighted average price
+    pub twac: RawEma,             // time-weighted average confidence interval
+    pub drv1: i64,                // space for future derived values
+    pub min_pub: u8,              // minimum number of publishers for status to be trading
+    pub drv2: u8,                 // space for future derived values
+    pub drv3: u16,                // space for future derived values
+    pub drv4: u32,                // space for future derived values
+    pub prod: AccKey,             // product account key
+    pub next: AccKey,             // next Price account in linked list
+    pub prev_slot: u64,           // valid slot of previous update
+    pub prev_price: i64,          // aggregate price of previous update
+    pub prev_conf: u64,           // confidence interval of previous update
+    pub prev_timestamp: i64,      // unix timestamp of previous update
+    pub agg: RawPriceInfo,        // aggregate price info
+    pub comp: [RawPriceComp; 32], // price components one per quoter
+}
+
 #[cfg(target_endian = "little")]
-unsafe impl Zeroable for Price {}
+unsafe impl Zeroable for RawPrice {}
 
 #[cfg(target_endian = "little")]
-unsafe impl Pod for Price {}
+unsafe impl Pod for RawPrice {}
 
 #[derive(Copy, Clone)]
 #[repr(C)]
@@ -116,4 +181,17 @@ pub struct Product {
 unsafe impl Zeroable for Product {}
 
 #[cfg(target_endian = "little")]
-unsafe impl Pod for Product {}
\ No newline at end of file
+unsafe impl Pod for Product {}
+
+/// Reads a `RawPrice` or `Product` from the start of an account's data. The data is copied rather
+/// than cast in place, as account data is not guaranteed to be aligned.
+pub fn load<T: Pod>(data: &[u8]) -> Result<T, PodCastError> {
+    let size = size_of::<T>();
+    if data.len() < size {
+        return Err(PodCastError::SizeMismatch);
+    }
+
+    let mut value = T::zeroed();
+    bytes_of_mut(&mut value).copy_from_slice(&data[..size]);
+    Ok(value)
+}
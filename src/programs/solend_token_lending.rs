@@ -1,15 +1,26 @@
 use solana_program::program_error::ProgramError;
+use solana_program::program_pack::Pack;
 use solana_sdk::pubkey::Pubkey;
 use crate::programs::solend::instruction::LendingInstruction;
+use crate::programs::solend::math::{Decimal, TryDiv};
+use crate::programs::solend::state::{LastUpdate, LendingMarket, Obligation, Reserve};
 use tracing::error;
 
-use crate::{Instruction, InstructionFunction, InstructionProperty, InstructionSet};
+use crate::error::DecodeError;
+use crate::programs::lending_account::{
+    common_lending_market_properties, common_obligation_properties, common_reserve_properties,
+    lending_account_properties, CommonBorrow, CommonDeposit, CommonLastUpdate,
+    CommonLendingMarket, CommonObligation, CommonReserve, LendingState,
+};
+use crate::{
+    AccountRecord, Instruction, InstructionFunction, InstructionProperty, InstructionSet, ValueType,
+};
 
 pub const PROGRAM_ADDRESS: &str = "So1endDq2YkqhipRh3WViPa8hdiSpxWy6z3Z6tMCpAo";
 
 pub async fn fragment_instruction(
     instruction: Instruction
-) -> Option<InstructionSet> {
+) -> Result<InstructionSet, DecodeError> {
     // Unpack the instruction via the spl_token_swap library
     let unpack_result = LendingInstruction::unpack(
         instruction.data.as_slice());
@@ -17,12 +28,14 @@ pub async fn fragment_instruction(
     return match unpack_result {
         Ok(ref unpacked) => {
             let lending_instruction = unpacked.clone();
-            match lending_instruction {
+            let account_properties = instruction.account_properties(
+                account_roles(&lending_instruction));
+            let instruction_set = match lending_instruction {
                 LendingInstruction::InitLendingMarket {
                     owner,
                     quote_currency,
                 } => {
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -30,6 +43,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "init-lending-market".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![
                             InstructionProperty {
@@ -38,8 +53,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "owner".to_string(),
                                 value: owner.to_string(),
+                                value_type: ValueType::Pubkey,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -47,14 +65,17 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "quote_currency".to_string(),
                                 value: Pubkey::new_from_array(quote_currency).to_string(),
+                                value_type: ValueType::Pubkey,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                         ],
                     })
                 }
                 LendingInstruction::SetLendingMarketOwner { new_owner } => {
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -62,6 +83,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "set-lending-market-owner".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![
                             InstructionProperty {
@@ -70,8 +93,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "new_owner".to_string(),
                                 value: new_owner.to_string(),
+                                value_type: ValueType::Pubkey,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             }
                         ],
                     })
@@ -80,7 +106,7 @@ pub async fn fragment_instruction(
                     liquidity_amount,
                     config,
                 } => {
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -88,6 +114,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "init-reserve".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![
                             InstructionProperty {
@@ -96,8 +124,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "liquidity_amount".to_string(),
                                 value: liquidity_amount.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -105,8 +136,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "flash_loan_fee_wad".to_string(),
                                 value: config.fees.flash_loan_fee_wad.to_string(),
-                                parent_key: "fees".to_string(),
+                                value_type: ValueType::U64,
+                                parent_key: "config/fees".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -114,8 +148,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "borrow_fee_wad".to_string(),
                                 value: config.fees.borrow_fee_wad.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "config/fees".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -123,8 +160,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "host_fee_percentage".to_string(),
                                 value: config.fees.host_fee_percentage.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "config/fees".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -132,8 +172,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "liquidation_threshold".to_string(),
                                 value: config.liquidation_threshold.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "config".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -141,8 +184,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "loan_to_value_ratio".to_string(),
                                 value: config.loan_to_value_ratio.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "config".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -150,8 +196,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "max_borrow_rate".to_string(),
                                 value: config.max_borrow_rate.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "config".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -159,8 +208,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "min_borrow_rate".to_string(),
                                 value: config.min_borrow_rate.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "config".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -168,8 +220,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "optimal_borrow_rate".to_string(),
                                 value: config.optimal_borrow_rate.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "config".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -177,14 +232,17 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "optimal_utilization_rate".to_string(),
                                 value: config.optimal_utilization_rate.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "config".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                         ],
                     })
                 }
                 LendingInstruction::RefreshReserve => {
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -192,12 +250,14 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "refresh-reserve".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![],
                     })
                 }
                 LendingInstruction::DepositReserveLiquidity { liquidity_amount } => {
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -205,6 +265,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "deposit-reserve-liquidity".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![
                             InstructionProperty {
@@ -213,14 +275,17 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "liquidity_amount".to_string(),
                                 value: liquidity_amount.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                         ],
                     })
                 }
                 LendingInstruction::RedeemReserveCollateral { collateral_amount } => {
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -228,6 +293,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "redeem-reserve-collateral".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![
                             InstructionProperty {
@@ -236,14 +303,17 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "collateral_amount".to_string(),
                                 value: collateral_amount.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             }
                         ],
                     })
                 }
                 LendingInstruction::InitObligation => {
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -251,12 +321,14 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "init-obligation".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![],
                     })
                 }
                 LendingInstruction::RefreshObligation => {
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -264,12 +336,14 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "refresh-obligation".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![],
                     })
                 }
                 LendingInstruction::DepositObligationCollateral { collateral_amount } => {
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -277,6 +351,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "deposit-obligation-collateral".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![
                             InstructionProperty {
@@ -285,14 +361,17 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "collateral_amount".to_string(),
                                 value: collateral_amount.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             }
                         ],
                     })
                 }
                 LendingInstruction::WithdrawObligationCollateral { collateral_amount } => {
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -300,6 +379,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "withdraw-obligation-collateral".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![
                             InstructionProperty {
@@ -308,14 +389,17 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "collateral_amount".to_string(),
                                 value: collateral_amount.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             }
                         ],
                     })
                 }
                 LendingInstruction::BorrowObligationLiquidity { liquidity_amount } => {
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -323,6 +407,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "borrow-obligation-liquidity".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![
                             InstructionProperty {
@@ -331,14 +417,17 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "liquidity_amount".to_string(),
                                 value: liquidity_amount.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                         ],
                     })
                 }
                 LendingInstruction::RepayObligationLiquidity { liquidity_amount } => {
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -346,6 +435,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "repay-obligation-liquidity".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![
                             InstructionProperty {
@@ -354,14 +445,17 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "liquidity_amount".to_string(),
                                 value: liquidity_amount.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                         ],
                     })
                 }
                 LendingInstruction::LiquidateObligation { liquidity_amount } => {
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -369,6 +463,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "liquidate-obligation".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![
                             InstructionProperty {
@@ -377,14 +473,17 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "liquidity_amount".to_string(),
                                 value: liquidity_amount.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                         ],
                     })
                 }
                 LendingInstruction::FlashLoan { amount } => {
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -392,6 +491,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "flash-loan".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![
                             InstructionProperty {
@@ -400,8 +501,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "amount".to_string(),
                                 value: amount.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             }
                         ],
                     })
@@ -409,7 +513,7 @@ pub async fn fragment_instruction(
                 LendingInstruction::DepositReserveLiquidityAndObligationCollateral {
                     liquidity_amount
                 } => {
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -417,6 +521,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "deposit-reserve-liquidity-and-obligation-collateral".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![
                             InstructionProperty {
@@ -425,8 +531,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "liquidity_amount".to_string(),
                                 value: liquidity_amount.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             }
                         ],
                     })
@@ -434,7 +543,7 @@ pub async fn fragment_instruction(
                 LendingInstruction::WithdrawObligationCollateralAndRedeemReserveCollateral {
                     collateral_amount
                 } => {
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -442,6 +551,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "withdraw-obligation-collateral-and-redeem-reserve-collateral".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![
                             InstructionProperty {
@@ -450,14 +561,17 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "collateral_amount".to_string(),
                                 value: collateral_amount.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             }
                         ],
                     })
                 }
                 LendingInstruction::UpdateReserveConfig { config } => {
-                    Some(InstructionSet {
+                    Ok(InstructionSet {
                         function: InstructionFunction {
                             tx_instruction_id: instruction.tx_instruction_id.clone(),
                             transaction_hash: instruction.transaction_hash.clone(),
@@ -465,6 +579,8 @@ pub async fn fragment_instruction(
                             program: instruction.program.clone(),
                             function_name: "update-reserve-config".to_string(),
                             timestamp: instruction.timestamp.clone(),
+                            slot: instruction.slot.clone(),
+                            block_time: instruction.block_time.clone(),
                         },
                         properties: vec![
                             InstructionProperty {
@@ -473,8 +589,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "borrow_fee_wad".to_string(),
                                 value: config.fees.borrow_fee_wad.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "config/fees".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -482,8 +601,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "flash_loan_fee_wad".to_string(),
                                 value: config.fees.flash_loan_fee_wad.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "config/fees".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -491,8 +613,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "host_fee_percentage".to_string(),
                                 value: config.fees.host_fee_percentage.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "config/fees".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -500,8 +625,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "optimal_utilization_rate".to_string(),
                                 value: config.optimal_utilization_rate.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "config".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -509,8 +637,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "optimal_borrow_rate".to_string(),
                                 value: config.optimal_borrow_rate.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "config".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -518,8 +649,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "loan_to_value_ratio".to_string(),
                                 value: config.loan_to_value_ratio.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "config".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -527,8 +661,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "max_borrow_rate".to_string(),
                                 value: config.max_borrow_rate.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "config".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -536,8 +673,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "min_borrow_rate".to_string(),
                                 value: config.min_borrow_rate.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "config".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -545,8 +685,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "liquidation_bonus".to_string(),
                                 value: config.liquidation_bonus.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "config".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -554,8 +697,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "liquidation_threshold".to_string(),
                                 value: config.liquidation_threshold.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "config".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -563,8 +709,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "fee_receiver".to_string(),
                                 value: config.fee_receiver.to_string(),
+                                value_type: ValueType::Pubkey,
                                 parent_key: "config".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -572,8 +721,11 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "deposit_limit".to_string(),
                                 value: config.deposit_limit.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "config".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                             InstructionProperty {
                                 tx_instruction_id: instruction.tx_instruction_id.clone(),
@@ -581,38 +733,302 @@ pub async fn fragment_instruction(
                                 parent_index: instruction.parent_index.clone(),
                                 key: "borrow_limit".to_string(),
                                 value: config.borrow_limit.to_string(),
+                                value_type: ValueType::U64,
                                 parent_key: "config".to_string(),
                                 timestamp: instruction.timestamp.clone(),
+                                slot: instruction.slot.clone(),
+                                block_time: instruction.block_time.clone(),
                             },
                         ],
                     })
                 }
-            }
+            };
+
+            instruction_set.map(|mut set| {
+                set.properties.extend(account_properties);
+                set
+            })
         }
         Err(err) => {
-            let err_msg = match err {
-                ProgramError::Custom(_) => "Custom".to_string(),
-                ProgramError::InvalidArgument => "InvalidArgument".to_string(),
-                ProgramError::InvalidInstructionData => "InvalidInstructionData".to_string(),
-                ProgramError::InvalidAccountData => "InvalidAccountData".to_string(),
-                ProgramError::AccountDataTooSmall => "AccountDataTooSmall".to_string(),
-                ProgramError::InsufficientFunds => "InsufficientFunds".to_string(),
-                ProgramError::IncorrectProgramId => "IncorrectProgramId".to_string(),
-                ProgramError::MissingRequiredSignature => "MissingRequiredSignature".to_string(),
-                ProgramError::AccountAlreadyInitialized => "AccountAlreadyInitialized".to_string(),
-                ProgramError::UninitializedAccount => "UninitializedAccount".to_string(),
-                ProgramError::NotEnoughAccountKeys => "NotEnoughAccountKeys".to_string(),
-                ProgramError::AccountBorrowFailed => "AccountBorrowFailed".to_string(),
-                ProgramError::MaxSeedLengthExceeded => "MaxSeedLengthExceeded".to_string(),
-                ProgramError::InvalidSeeds => "InvalidSeeds".to_string(),
-                ProgramError::BorshIoError(_) => "BorshIoError".to_string(),
-                ProgramError::AccountNotRentExempt => "AccountNotRentExempt".to_string(),
-                ProgramError::UnsupportedSysvar => "UnsupportedSysvar".to_string(),
-                ProgramError::IllegalOwner => "IllegalOwner".to_string(),
-            };
+            let decode_error = DecodeError::from_program_error(&instruction.program, &err);
             error!("{} Reason: {}", "[processors/programs/solend_token_lending] FATAL: Unrecognised instruction.".to_string(),
-            err_msg);
-            None
+            decode_error);
+            Err(decode_error)
         }
     };
 }
+
+/// Decodes the data of a Solend reserve, obligation or lending market account, telling them
+/// apart by the length of their data.
+pub async fn fragment_account(
+    // The record holding the account's data
+    account: AccountRecord,
+) -> Result<InstructionSet, DecodeError> {
+    match account.data.len() {
+        Reserve::LEN => lending_account_properties::<Reserve>(&account),
+        Obligation::LEN => lending_account_properties::<Obligation>(&account),
+        LendingMarket::LEN => lending_account_properties::<LendingMarket>(&account),
+        _ => {
+            let decode_error = DecodeError::from_program_error(
+                &account.owner, &ProgramError::InvalidAccountData);
+            error!("[spi-wrapper/programs/solend_token_lending] Attempt to parse account {} \
+            failed due to {}.", account.pubkey, decode_error);
+
+            Err(decode_error)
+        }
+    }
+}
+
+impl LendingState for LendingMarket {
+    const FUNCTION_NAME: &'static str = "lending-market";
+
+    fn properties(&self, account: &AccountRecord) -> Vec<InstructionProperty> {
+        let mut properties = common_lending_market_properties(account, CommonLendingMarket {
+            version: self.version,
+            bump_seed: self.bump_seed,
+            owner: &self.owner,
+            quote_currency: &self.quote_currency,
+            token_program_id: &self.token_program_id,
+            oracle_program_id: &self.oracle_program_id,
+        });
+        properties.push(account.property("", "switchboard_oracle_program_id",
+                                         self.switchboard_oracle_program_id.to_string(),
+                                         ValueType::Pubkey));
+
+        properties
+    }
+}
+
+impl LendingState for Reserve {
+    const FUNCTION_NAME: &'static str = "reserve";
+
+    fn properties(&self, account: &AccountRecord) -> Vec<InstructionProperty> {
+        let liquidity = &self.liquidity;
+        let collateral = &self.collateral;
+        let config = &self.config;
+
+        let mut properties = common_reserve_properties(account, CommonReserve {
+            version: self.version,
+            lending_market: &self.lending_market,
+            last_update: common_last_update(&self.last_update),
+            liquidity_mint_pubkey: &liquidity.mint_pubkey,
+            liquidity_mint_decimals: liquidity.mint_decimals,
+            liquidity_supply_pubkey: &liquidity.supply_pubkey,
+            liquidity_available_amount: liquidity.available_amount,
+            liquidity_borrowed_amount_wads: &liquidity.borrowed_amount_wads,
+            liquidity_cumulative_borrow_rate_wads: &liquidity.cumulative_borrow_rate_wads,
+            liquidity_market_price: &liquidity.market_price,
+            collateral_mint_pubkey: &collateral.mint_pubkey,
+            collateral_mint_total_supply: collateral.mint_total_supply,
+            collateral_supply_pubkey: &collateral.supply_pubkey,
+            config_optimal_utilization_rate: config.optimal_utilization_rate,
+            config_loan_to_value_ratio: config.loan_to_value_ratio,
+            config_liquidation_bonus: config.liquidation_bonus,
+            config_liquidation_threshold: config.liquidation_threshold,
+            config_min_borrow_rate: config.min_borrow_rate,
+            config_optimal_borrow_rate: config.optimal_borrow_rate,
+            config_max_borrow_rate: config.max_borrow_rate,
+            config_borrow_fee_wad: config.fees.borrow_fee_wad,
+            config_flash_loan_fee_wad: config.fees.flash_loan_fee_wad,
+            config_host_fee_percentage: config.fees.host_fee_percentage,
+        });
+        properties.extend(vec![
+            account.property("liquidity", "pyth_oracle_pubkey",
+                             liquidity.pyth_oracle_pubkey.to_string(), ValueType::Pubkey),
+            account.property("liquidity", "switchboard_oracle_pubkey",
+                             liquidity.switchboard_oracle_pubkey.to_string(), ValueType::Pubkey),
+            account.property("config", "deposit_limit", config.deposit_limit.to_string(),
+                             ValueType::U64),
+            account.property("config", "borrow_limit", config.borrow_limit.to_string(),
+                             ValueType::U64),
+            account.property("config", "fee_receiver", config.fee_receiver.to_string(),
+                             ValueType::Pubkey),
+        ]);
+
+        properties
+    }
+}
+
+impl LendingState for Obligation {
+    const FUNCTION_NAME: &'static str = "obligation";
+
+    fn properties(&self, account: &AccountRecord) -> Vec<InstructionProperty> {
+        let loan_to_value = if self.deposited_value == Decimal::zero() {
+            None
+        } else {
+            self.borrowed_value.try_div(self.deposited_value).ok().map(|ltv| ltv.to_string())
+        };
+
+        common_obligation_properties(account, CommonObligation {
+            version: self.version,
+            lending_market: &self.lending_market,
+            owner: &self.owner,
+            last_update: common_last_update(&self.last_update),
+            deposits: self.deposits.iter()
+                .map(|deposit| CommonDeposit {
+                    deposit_reserve: &deposit.deposit_reserve,
+                    deposited_amount: deposit.deposited_amount,
+                    market_value: &deposit.market_value,
+                })
+                .collect(),
+            borrows: self.borrows.iter()
+                .map(|borrow| CommonBorrow {
+                    borrow_reserve: &borrow.borrow_reserve,
+                    cumulative_borrow_rate_wads: &borrow.cumulative_borrow_rate_wads,
+                    borrowed_amount_wads: &borrow.borrowed_amount_wads,
+                    market_value: &borrow.market_value,
+                })
+                .collect(),
+            deposited_value: &self.deposited_value,
+            borrowed_value: &self.borrowed_value,
+            allowed_borrow_value: &self.allowed_borrow_value,
+            unhealthy_borrow_value: &self.unhealthy_borrow_value,
+            loan_to_value,
+        })
+    }
+}
+
+fn common_last_update(last_update: &LastUpdate) -> CommonLastUpdate {
+    CommonLastUpdate { slot: last_update.slot, stale: last_update.stale }
+}
+
+/// The roles of the accounts each instruction expects, in order.
+fn account_roles(instruction: &LendingInstruction) -> &'static [&'static str] {
+    match instruction {
+        LendingInstruction::InitLendingMarket { .. } => &[
+            "lending_market", "rent_sysvar", "token_program", "oracle_program",
+            "switchboard_oracle_program",
+        ],
+        LendingInstruction::InitReserve { .. } => &[
+            "source_liquidity", "destination_collateral", "reserve", "reserve_liquidity_mint",
+            "reserve_liquidity_supply", "reserve_liquidity_fee_receiver", "pyth_product",
+            "pyth_price", "switchboard_feed", "reserve_collateral_mint",
+            "reserve_collateral_supply", "lending_market", "lending_market_authority",
+            "lending_market_owner", "user_transfer_authority", "clock_sysvar", "rent_sysvar",
+            "token_program",
+        ],
+        LendingInstruction::RefreshReserve => &[
+            "reserve", "pyth_price", "switchboard_feed", "clock_sysvar",
+        ],
+        LendingInstruction::SetLendingMarketOwner { .. } => &[
+            "lending_market", "lending_market_owner",
+        ],
+        LendingInstruction::DepositReserveLiquidity { .. } => &[
+            "source_liquidity", "destination_collateral", "reserve", "reserve_liquidity_supply",
+            "reserve_collateral_mint", "lending_market", "lending_market_authority",
+            "user_transfer_authority", "clock_sysvar", "token_program",
+        ],
+        LendingInstruction::RedeemReserveCollateral { .. } => &[
+            "source_collateral", "destination_liquidity", "reserve", "reserve_collateral_mint",
+            "reserve_liquidity_supply", "lending_market", "lending_market_authority",
+            "user_transfer_authority", "clock_sysvar", "token_program",
+        ],
+        LendingInstruction::InitObligation => &[
+            "obligation", "lending_market", "obligation_owner", "clock_sysvar", "rent_sysvar",
+            "token_program",
+        ],
+        LendingInstruction::RefreshObligation => &["obligation", "clock_sysvar", "reserves*"],
+        LendingInstruction::DepositObligationCollateral { .. } => &[
+            "source_collateral", "destination_collateral", "deposit_reserve", "obligation",
+            "lending_market", "obligation_owner", "user_transfer_authority", "clock_sysvar",
+            "token_program",
+        ],
+        LendingInstruction::WithdrawObligationCollateral { .. } => &[
+            "source_collateral", "destination_collateral", "withdraw_reserve", "obligation",
+            "lending_market", "lending_market_authority", "obligation_owner", "clock_sysvar",
+            "token_program",
+        ],
+        LendingInstruction::BorrowObligationLiquidity { .. } => &[
+            "source_liquidity", "destination_liquidity", "borrow_reserve",
+            "borrow_reserve_liquidity_fee_receiver", "obligation", "lending_market",
+            "lending_market_authority", "obligation_owner", "clock_sysvar", "token_program",
+            "host_fee_receiver",
+        ],
+        LendingInstruction::RepayObligationLiquidity { .. } => &[
+            "source_liquidity", "destination_liquidity", "repay_reserve", "obligation",
+            "lending_market", "user_transfer_authority", "clock_sysvar", "token_program",
+        ],
+        LendingInstruction::LiquidateObligation { .. } => &[
+            "source_liquidity", "destination_collateral", "repay_reserve",
+            "repay_reserve_liquidity_supply", "withdraw_reserve",
+            "withdraw_reserve_collateral_supply", "obligation", "lending_market",
+            "lending_market_authority", "user_transfer_authority", "clock_sysvar", "token_program",
+        ],
+        LendingInstruction::FlashLoan { .. } => &[
+            "source_liquidity", "destination_liquidity", "reserve", "flash_loan_fee_receiver",
+            "host_fee_receiver", "lending_market", "lending_market_authority", "token_program",
+            "flash_loan_receiver_program", "flash_loan_receiver_accounts*",
+        ],
+        LendingInstruction::DepositReserveLiquidityAndObligationCollateral { .. } => &[
+            "source_liquidity", "destination_collateral", "reserve", "reserve_liquidity_supply",
+            "reserve_collateral_mint", "lending_market", "lending_market_authority",
+            "destination_deposit_collateral", "obligation", "obligation_owner", "pyth_price",
+            "switchboard_feed", "user_transfer_authority", "clock_sysvar", "token_program",
+        ],
+        LendingInstruction::WithdrawObligationCollateralAndRedeemReserveCollateral { .. } => &[
+            "source_collateral", "destination_collateral", "withdraw_reserve", "obligation",
+            "lending_market", "lending_market_authority", "destination_liquidity",
+            "reserve_collateral_mint", "reserve_liquidity_supply", "obligation_owner",
+            "user_transfer_authority", "clock_sysvar", "token_program",
+        ],
+        LendingInstruction::UpdateReserveConfig { .. } => &[
+            "reserve", "lending_market", "lending_market_authority", "lending_market_owner",
+            "pyth_product", "pyth_price", "switchboard_feed",
+        ],
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::programs::solend::state::{
+        ObligationLiquidity, ReserveConfig, ReserveLiquidity, PROGRAM_VERSION,
+    };
+
+    use super::*;
+    use crate::test_utils::decode_account;
+
+    #[test]
+    fn reserves_and_obligations_are_decoded() {
+        let switchboard_oracle = Pubkey::new_unique();
+        let mut reserve = vec![0; Reserve::LEN];
+        Reserve::pack(Reserve {
+            version: PROGRAM_VERSION,
+            liquidity: ReserveLiquidity {
+                switchboard_oracle_pubkey: switchboard_oracle,
+                available_amount: 3,
+                ..ReserveLiquidity::default()
+            },
+            config: ReserveConfig {
+                deposit_limit: 1_000,
+                ..ReserveConfig::default()
+            },
+            ..Reserve::default()
+        }, &mut reserve).unwrap();
+
+        let reserve = decode_account(PROGRAM_ADDRESS, reserve).to_json();
+        assert_eq!(reserve["function_name"], "reserve");
+        let liquidity = &reserve["properties"]["liquidity"];
+        assert_eq!(liquidity["switchboard_oracle_pubkey"], switchboard_oracle.to_string());
+        assert_eq!(liquidity["available_amount"], 3);
+        assert_eq!(reserve["properties"]["config"]["deposit_limit"], 1_000);
+
+        let borrow_reserve = Pubkey::new_unique();
+        let mut obligation = vec![0; Obligation::LEN];
+        Obligation::pack(Obligation {
+            version: PROGRAM_VERSION,
+            borrows: vec![ObligationLiquidity {
+                borrow_reserve,
+                borrowed_amount_wads: Decimal::from(4u64),
+                ..ObligationLiquidity::default()
+            }],
+            ..Obligation::default()
+        }, &mut obligation).unwrap();
+
+        let obligation = decode_account(PROGRAM_ADDRESS, obligation).to_json();
+        assert_eq!(obligation["function_name"], "obligation");
+        let borrow = &obligation["properties"]["borrows"][0];
+        assert_eq!(borrow["borrow_reserve"], borrow_reserve.to_string());
+        assert_eq!(borrow["borrowed_amount_wads"], "4.000000000000000000");
+        assert!(obligation["properties"]["loan_to_value"].is_null());
+    }
+}
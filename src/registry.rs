@@ -0,0 +1,478 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use solana_sdk::instruction::CompiledInstruction;
+
+use crate::programs;
+use crate::{AccountRecord, DecodeError, Instruction, InstructionSet};
+
+/// The future a parser hands back when asked to decode an instruction.
+pub type ParseFuture = Pin<Box<dyn Future<Output = Result<InstructionSet, DecodeError>> + Send>>;
+
+/// A decoder for the instructions of one or more on-chain programs.
+///
+/// Implement this to plug your own program into [`ParserRegistry`] without forking the crate.
+pub trait ProgramParser: Send + Sync {
+    /// A human readable name for the parser, e.g. `native_system`.
+    fn name(&self) -> &str;
+
+    /// The program addresses this parser decodes instructions for.
+    fn program_ids(&self) -> Vec<String>;
+
+    /// Decodes an instruction into its function and properties, or tells why it could not.
+    ///
+    /// `og_instructions` are the compiled instructions of the transaction the instruction belongs
+    /// to, for parsers that need to look across instructions (e.g. signature verification).
+    fn parse(
+        &self,
+        instruction: Instruction,
+        og_instructions: Option<Vec<CompiledInstruction>>,
+    ) -> ParseFuture;
+}
+
+/// The function behind a `FnParser`.
+pub type ParseFn =
+    dyn Fn(Instruction, Option<Vec<CompiledInstruction>>) -> ParseFuture + Send + Sync;
+
+/// A parser backed by a function or closure, which is how the built-in programs are registered.
+/// Closures may capture whatever configuration the parser needs.
+pub struct FnParser {
+    name: String,
+    program_ids: Vec<String>,
+    parse: Box<ParseFn>,
+}
+
+impl FnParser {
+    pub fn new<F>(name: &str, program_ids: &[&str], parse: F) -> Self
+        where F: Fn(Instruction, Option<Vec<CompiledInstruction>>) -> ParseFuture
+                    + Send + Sync + 'static {
+        FnParser {
+            name: name.to_string(),
+            program_ids: program_ids.iter().map(|id| id.to_string()).collect(),
+            parse: Box::new(parse),
+        }
+    }
+}
+
+impl ProgramParser for FnParser {
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    fn program_ids(&self) -> Vec<String> {
+        self.program_ids.clone()
+    }
+
+    fn parse(
+        &self,
+        instruction: Instruction,
+        og_instructions: Option<Vec<CompiledInstruction>>,
+    ) -> ParseFuture {
+        (self.parse)(instruction, og_instructions)
+    }
+}
+
+/// A decoder for the accounts owned by one or more on-chain programs.
+///
+/// Kept apart from [`ProgramParser`] as accounts are not instructions: they are handed over as an
+/// [`AccountRecord`].
+pub trait AccountParser: Send + Sync {
+    /// A human readable name for the parser, e.g. `native_token`.
+    fn name(&self) -> &str;
+
+    /// The program addresses this parser decodes the accounts of.
+    fn program_ids(&self) -> Vec<String>;
+
+    /// Decodes the state of an account into a function and properties, or tells why it could
+    /// not.
+    fn parse(&self, account: AccountRecord) -> ParseFuture;
+}
+
+/// The function behind a `FnAccountParser`.
+pub type AccountParseFn = dyn Fn(AccountRecord) -> ParseFuture + Send + Sync;
+
+/// An account parser backed by a function or closure, like `FnParser`.
+pub struct FnAccountParser {
+    name: String,
+    program_ids: Vec<String>,
+    parse: Box<AccountParseFn>,
+}
+
+impl FnAccountParser {
+    pub fn new<F>(name: &str, program_ids: &[&str], parse: F) -> Self
+        where F: Fn(AccountRecord) -> ParseFuture + Send + Sync + 'static {
+        FnAccountParser {
+            name: name.to_string(),
+            program_ids: program_ids.iter().map(|id| id.to_string()).collect(),
+            parse: Box::new(parse),
+        }
+    }
+}
+
+impl AccountParser for FnAccountParser {
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    fn program_ids(&self) -> Vec<String> {
+        self.program_ids.clone()
+    }
+
+    fn parse(&self, account: AccountRecord) -> ParseFuture {
+        (self.parse)(account)
+    }
+}
+
+/// Maps program addresses to the parser that decodes them.
+///
+/// Registering a parser for an address that already has one overrides it, so callers can swap
+/// out a built-in decoder for their own.
+///
+/// Instructions and accounts have separate parsers: account parsers are looked up by the program
+/// owning the account, and are handed the account as a record (see `Account::to_record`).
+#[derive(Clone, Default)]
+pub struct ParserRegistry {
+    parsers: HashMap<String, Arc<dyn ProgramParser>>,
+    account_parsers: HashMap<String, Arc<dyn AccountParser>>,
+}
+
+impl ParserRegistry {
+    /// Creates a registry without any parsers.
+    pub fn new() -> Self {
+        ParserRegistry::default()
+    }
+
+    /// Creates a registry with every program supported by this crate.
+    pub fn with_builtins() -> Self {
+        let mut registry = ParserRegistry::new();
+
+        registry.register(FnParser::new(
+            "metaplex_token_metadata",
+            &[programs::metaplex_token_metadata::PROGRAM_ADDRESS],
+            |instruction, _| Box::pin(
+                programs::metaplex_token_metadata::fragment_instruction(instruction)),
+        ));
+        registry.register(FnParser::new(
+            "native_address_lookup_table",
+            &[programs::native_address_lookup_table::PROGRAM_ADDRESS],
+            |instruction, _| Box::pin(
+                programs::native_address_lookup_table::fragment_instruction(instruction)),
+        ));
+        registry.register(FnParser::new(
+            "native_associated_token_account",
+            &[programs::native_associated_token_account::PROGRAM_ADDRESS],
+            |instruction, _| Box::pin(
+                programs::native_associated_token_account::fragment_instruction(instruction)),
+        ));
+        registry.register(FnParser::new(
+            "native_compute_budget",
+            &[programs::native_compute_budget::PROGRAM_ADDRESS],
+            |instruction, _| Box::pin(
+                programs::native_compute_budget::fragment_instruction(instruction)),
+        ));
+        registry.register(FnParser::new(
+            "native_config",
+            &[programs::native_config::PROGRAM_ADDRESS],
+            |instruction, _| Box::pin(
+                programs::native_config::fragment_instruction(instruction)),
+        ));
+        registry.register(FnParser::new(
+            "native_ed25519",
+            &[programs::native_ed25519::PROGRAM_ADDRESS],
+            |instruction, og_instructions| Box::pin(async move {
+                programs::native_ed25519::fragment_instruction(instruction,
+                                                               og_instructions.as_deref())
+                    .await
+            }),
+        ));
+        registry.register(FnParser::new(
+            "native_loader",
+            &[programs::native_loader::PROGRAM_ADDRESS],
+            |instruction, _| Box::pin(
+                programs::native_loader::fragment_instruction(instruction)),
+        ));
+        registry.register(FnParser::new(
+            "native_memo",
+            &[programs::native_memo::PROGRAM_ADDRESS_V1, programs::native_memo::PROGRAM_ADDRESS_V2],
+            |instruction, _| Box::pin(
+                programs::native_memo::fragment_instruction(instruction)),
+        ));
+        registry.register(FnParser::new(
+            "bpf_loader",
+            &[programs::bpf_loader::PROGRAM_ADDRESS, programs::bpf_loader::PROGRAM_ADDRESS_2],
+            |instruction, _| Box::pin(
+                programs::bpf_loader::fragment_instruction(instruction)),
+        ));
+        registry.register(FnParser::new(
+            "bpf_loader_upgradeable",
+            &[programs::bpf_loader_upgradeable::PROGRAM_ADDRESS],
+            |instruction, _| Box::pin(
+                programs::bpf_loader_upgradeable::fragment_instruction(instruction)),
+        ));
+        registry.register(FnParser::new(
+            "native_secp256k1",
+            &[programs::native_secp256k1::PROGRAM_ADDRESS],
+            |instruction, og_instructions| Box::pin(async move {
+                programs::native_secp256k1::fragment_instruction(instruction,
+                                                                 og_instructions.as_deref())
+                    .await
+            }),
+        ));
+        registry.register(FnParser::new(
+            "native_stake",
+            &[programs::native_stake::PROGRAM_ADDRESS],
+            |instruction, _| Box::pin(
+                programs::native_stake::fragment_instruction(instruction)),
+        ));
+        registry.register(FnParser::new(
+            "native_stake_pool",
+            &[programs::native_stake_pool::PROGRAM_ADDRESS],
+            |instruction, _| Box::pin(
+                programs::native_stake_pool::fragment_instruction(instruction)),
+        ));
+        registry.register(FnParser::new(
+            "native_system",
+            &[programs::native_system::PROGRAM_ADDRESS],
+            |instruction, _| Box::pin(
+                programs::native_system::fragment_instruction(instruction)),
+        ));
+        registry.register(FnParser::new(
+            "native_token",
+            &[programs::native_token::PROGRAM_ADDRESS],
+            |instruction, _| Box::pin(
+                programs::native_token::fragment_instruction(instruction)),
+        ));
+        registry.register(FnParser::new(
+            "native_token_lending",
+            &[programs::native_token_lending::PROGRAM_ADDRESS],
+            |instruction, _| Box::pin(
+                programs::native_token_lending::fragment_instruction(instruction)),
+        ));
+        registry.register(FnParser::new(
+            "native_token_swap",
+            &[programs::native_token_swap::PROGRAM_ADDRESS],
+            |instruction, _| Box::pin(
+                programs::native_token_swap::fragment_instruction(instruction)),
+        ));
+        registry.register(FnParser::new(
+            "serum_market",
+            &[
+                programs::serum_market::PROGRAM_ADDRESS_V1,
+                programs::serum_market::PROGRAM_ADDRESS_V2,
+                programs::serum_market::PROGRAM_ADDRESS_V3,
+            ],
+            |instruction, _| Box::pin(
+                programs::serum_market::fragment_instruction(instruction)),
+        ));
+        registry.register(FnParser::new(
+            "native_vote",
+            &[programs::native_vote::PROGRAM_ADDRESS],
+            |instruction, _| Box::pin(
+                programs::native_vote::fragment_instruction(instruction)),
+        ));
+        registry.register(FnParser::new(
+            "solend_token_lending",
+            &[programs::solend_token_lending::PROGRAM_ADDRESS],
+            |instruction, _| Box::pin(
+                programs::solend_token_lending::fragment_instruction(instruction)),
+        ));
+
+        registry.register_account_parser(FnAccountParser::new(
+            "bpf_loader_upgradeable",
+            &[programs::bpf_loader_upgradeable::PROGRAM_ADDRESS],
+            |account| Box::pin(
+                programs::bpf_loader_upgradeable::fragment_account(account)),
+        ));
+        registry.register_account_parser(FnAccountParser::new(
+            "metaplex_token_metadata",
+            &[programs::metaplex_token_metadata::PROGRAM_ADDRESS],
+            |account| Box::pin(
+                programs::metaplex_token_metadata::fragment_account(account)),
+        ));
+        registry.register_account_parser(FnAccountParser::new(
+            "native_address_lookup_table",
+            &[programs::native_address_lookup_table::PROGRAM_ADDRESS],
+            |account| Box::pin(
+                programs::native_address_lookup_table::fragment_account(account)),
+        ));
+        registry.register_account_parser(FnAccountParser::new(
+            "native_config",
+            &[programs::native_config::PROGRAM_ADDRESS],
+            |account| Box::pin(
+                programs::native_config::fragment_account(account)),
+        ));
+        registry.register_account_parser(FnAccountParser::new(
+            "native_stake",
+            &[programs::native_stake::PROGRAM_ADDRESS],
+            |account| Box::pin(
+                programs::native_stake::fragment_account(account)),
+        ));
+        registry.register_account_parser(FnAccountParser::new(
+            "native_stake_pool",
+            &[programs::native_stake_pool::PROGRAM_ADDRESS],
+            |account| Box::pin(
+                programs::native_stake_pool::fragment_account(account)),
+        ));
+        registry.register_account_parser(FnAccountParser::new(
+            "native_system",
+            &[programs::native_system::PROGRAM_ADDRESS],
+            |account| Box::pin(
+                programs::native_system::fragment_account(account)),
+        ));
+        registry.register_account_parser(FnAccountParser::new(
+            "native_token",
+            &[programs::native_token::PROGRAM_ADDRESS],
+            |account| Box::pin(
+                programs::native_token::fragment_account(account)),
+        ));
+        registry.register_account_parser(FnAccountParser::new(
+            "native_token_lending",
+            &[programs::native_token_lending::PROGRAM_ADDRESS],
+            |account| Box::pin(
+                programs::native_token_lending::fragment_account(account)),
+        ));
+        registry.register_account_parser(FnAccountParser::new(
+            "native_token_swap",
+            &[programs::native_token_swap::PROGRAM_ADDRESS],
+            |account| Box::pin(
+                programs::native_token_swap::fragment_account(account)),
+        ));
+        registry.register_account_parser(FnAccountParser::new(
+            "native_vote",
+            &[programs::native_vote::PROGRAM_ADDRESS],
+            |account| Box::pin(
+                programs::native_vote::fragment_account(account)),
+        ));
+        registry.register_account_parser(FnAccountParser::new(
+            "pyth_oracle",
+            &[
+                programs::pyth_oracle::PROGRAM_ADDRESS,
+                programs::pyth_oracle::PROGRAM_ADDRESS_DEVNET,
+                programs::pyth_oracle::PROGRAM_ADDRESS_TESTNET,
+            ],
+            |account| Box::pin(
+                programs::pyth_oracle::fragment_account(account)),
+        ));
+        registry.register_account_parser(FnAccountParser::new(
+            "serum_market",
+            &[
+                programs::serum_market::PROGRAM_ADDRESS_V1,
+                programs::serum_market::PROGRAM_ADDRESS_V2,
+                programs::serum_market::PROGRAM_ADDRESS_V3,
+            ],
+            |account| Box::pin(
+                programs::serum_market::fragment_account(account)),
+        ));
+        registry.register_account_parser(FnAccountParser::new(
+            "solend_token_lending",
+            &[programs::solend_token_lending::PROGRAM_ADDRESS],
+            |account| Box::pin(
+                programs::solend_token_lending::fragment_account(account)),
+        ));
+
+        registry
+    }
+
+    /// Registers a parser for every program address it declares, overriding any parser that
+    /// was previously registered for those addresses.
+    pub fn register<P: ProgramParser + 'static>(&mut self, parser: P) {
+        let parser: Arc<dyn ProgramParser> = Arc::new(parser);
+        for program_id in parser.program_ids() {
+            self.parsers.insert(program_id, parser.clone());
+        }
+    }
+
+    /// Stops decoding the given program address, returning the parser that handled it.
+    pub fn unregister(&mut self, program_id: &str) -> Option<Arc<dyn ProgramParser>> {
+        self.parsers.remove(program_id)
+    }
+
+    /// Registers a parser for the accounts owned by every program address it declares,
+    /// overriding any account parser that was previously registered for those addresses.
+    pub fn register_account_parser<P: AccountParser + 'static>(&mut self, parser: P) {
+        let parser: Arc<dyn AccountParser> = Arc::new(parser);
+        for program_id in parser.program_ids() {
+            self.account_parsers.insert(program_id, parser.clone());
+        }
+    }
+
+    /// Stops decoding the accounts owned by the given program address, returning the parser that
+    /// handled them.
+    pub fn unregister_account_parser(
+        &mut self,
+        program_id: &str,
+    ) -> Option<Arc<dyn AccountParser>> {
+        self.account_parsers.remove(program_id)
+    }
+
+    /// Stops decoding every program address handled by the parser with the given name, both
+    /// instructions and accounts.
+    pub fn disable(&mut self, name: &str) {
+        self.parsers.retain(|_, parser| parser.name() != name);
+        self.account_parsers.retain(|_, parser| parser.name() != name);
+    }
+
+    /// Looks up the parser for a program address.
+    pub fn get(&self, program_id: &str) -> Option<Arc<dyn ProgramParser>> {
+        self.parsers.get(program_id).cloned()
+    }
+
+    /// Looks up the parser for the accounts owned by a program address.
+    pub fn get_account_parser(&self, owner: &str) -> Option<Arc<dyn AccountParser>> {
+        self.account_parsers.get(owner).cloned()
+    }
+
+    /// Every program address that currently has a parser.
+    pub fn program_ids(&self) -> Vec<String> {
+        self.parsers.keys().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::process_with_registry;
+    use crate::test_utils::{block_on, instruction};
+
+    #[test]
+    fn registry_overrides_and_disables_parsers() {
+        let mut registry = ParserRegistry::with_builtins();
+        let system = programs::native_system::PROGRAM_ADDRESS;
+        assert_eq!(registry.get(system).unwrap().name(), "native_system");
+
+        registry.register(FnParser::new("custom_system", &[system], |_, _| {
+            Box::pin(async {
+                Err(DecodeError::deserialization("", "NotDecoded", "Not decoded."))
+            })
+        }));
+        assert_eq!(registry.get(system).unwrap().name(), "custom_system");
+
+        registry.disable("serum_market");
+        assert!(registry.get(programs::serum_market::PROGRAM_ADDRESS_V3).is_none());
+
+        assert!(registry.unregister(system).is_some());
+        assert!(registry.get(system).is_none());
+    }
+
+    #[test]
+    fn registry_parsers_capture_configuration() {
+        let program = programs::native_system::PROGRAM_ADDRESS;
+        let function_name = "configured".to_string();
+
+        let mut registry = ParserRegistry::new();
+        registry.register(FnParser::new("configured_system", &[program], move |instruction, _| {
+            let function = instruction.function(&function_name);
+            Box::pin(async move {
+                Ok(InstructionSet { function, properties: vec![] })
+            })
+        }));
+
+        let outcomes = block_on(process_with_registry(
+            &registry, vec![instruction(program, &[], vec![])], None));
+
+        let set = outcomes[0].clone().instruction_set().unwrap();
+        assert_eq!(set.function.function_name, "configured");
+    }
+}
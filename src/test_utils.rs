@@ -0,0 +1,48 @@
+//! Fixtures shared by the tests of the crate.
+
+use std::future::Future;
+
+use crate::transaction::TOP_LEVEL_PARENT_INDEX;
+use crate::{process, process_accounts, AccountSet, Instruction, InstructionSet};
+
+/// A top level instruction of `program`, at the start of an empty transaction.
+pub(crate) fn instruction(program: &str, accounts: &[&str], data: Vec<u8>) -> Instruction {
+    Instruction {
+        tx_instruction_id: 0,
+        transaction_hash: "".to_string(),
+        program: program.to_string(),
+        accounts: accounts.iter().map(|account| account.to_string()).collect(),
+        data,
+        parent_index: TOP_LEVEL_PARENT_INDEX,
+        timestamp: 0,
+        slot: 0,
+        block_time: None,
+    }
+}
+
+/// Runs a future to completion on a single threaded runtime.
+pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+        .block_on(future)
+}
+
+/// Decodes a single instruction with the builtin parsers, expecting it to decode.
+pub(crate) fn decode(instruction: Instruction) -> InstructionSet {
+    block_on(process(vec![instruction], None))
+        .remove(0)
+        .instruction_set()
+        .unwrap()
+}
+
+/// Decodes the data of a single account owned by `owner` with the builtin parsers, expecting it
+/// to decode.
+pub(crate) fn decode_account(owner: &str, data: Vec<u8>) -> AccountSet {
+    block_on(process_accounts(vec![
+        ("account".to_string(), owner.to_string(), data, 0, 7),
+    ], 0))
+        .remove(0)
+        .account_set()
+        .unwrap()
+}
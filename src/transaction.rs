@@ -0,0 +1,511 @@
+use solana_sdk::instruction::CompiledInstruction;
+use solana_sdk::transaction::{Transaction, TransactionError};
+use solana_transaction_status::{
+    ConfirmedTransaction, EncodedConfirmedTransaction, EncodedTransaction,
+    EncodedTransactionWithStatusMeta, InnerInstructions, TransactionWithStatusMeta,
+    UiCompiledInstruction, UiInstruction, UiMessage,
+};
+use tracing::error;
+
+use crate::{
+    process_with_registry, DecodeError, Instruction, InstructionOutcome, ParserRegistry, Record,
+    ValueType,
+};
+
+/// The `parent_index` of instructions invoked directly by the transaction.
+pub const TOP_LEVEL_PARENT_INDEX: i16 = -1;
+
+/// The `parent_key` of the properties describing the error a failed instruction raised.
+pub const ERROR_PARENT_KEY: &str = "error";
+
+/// A transaction broken down into the pieces needed to build `Instruction`s from it.
+pub struct FlattenedTransaction {
+    // The first signature of the transaction.
+    pub transaction_hash: String,
+    // Every account key referenced by the transaction's message, in order.
+    pub account_keys: Vec<String>,
+    // The instructions invoked directly by the transaction.
+    pub instructions: Vec<CompiledInstruction>,
+    // The instructions invoked by programs during execution, grouped by the index of the
+    // top-level instruction that invoked them.
+    pub inner_instructions: Vec<InnerInstructions>,
+    // The error the transaction failed with, if it failed.
+    pub error: Option<TransactionError>,
+    // The logs of the transaction, used to tell which program raised its error.
+    pub log_messages: Vec<String>,
+}
+
+impl FlattenedTransaction {
+    pub fn from_transaction(
+        transaction: &Transaction,
+        inner_instructions: Option<Vec<InnerInstructions>>,
+    ) -> Self {
+        FlattenedTransaction {
+            transaction_hash: transaction.signatures.first()
+                .map(|signature| signature.to_string())
+                .unwrap_or_default(),
+            account_keys: transaction.message.account_keys.iter()
+                .map(|key| key.to_string())
+                .collect(),
+            instructions: transaction.message.instructions.clone(),
+            inner_instructions: inner_instructions.unwrap_or_default(),
+            error: None,
+            log_messages: vec![],
+        }
+    }
+
+    pub fn from_transaction_with_status_meta(transaction: &TransactionWithStatusMeta) -> Self {
+        FlattenedTransaction {
+            error: transaction.meta.as_ref().and_then(|meta| meta.status.clone().err()),
+            log_messages: transaction.meta.as_ref()
+                .and_then(|meta| meta.log_messages.clone())
+                .unwrap_or_default(),
+            ..FlattenedTransaction::from_transaction(
+                &transaction.transaction,
+                transaction.meta.as_ref().and_then(|meta| meta.inner_instructions.clone()),
+            )
+        }
+    }
+
+    /// Builds the flattened transaction out of an RPC-encoded one. Base58/base64 encodings and
+    /// raw (non-parsed) json messages are supported; an error is returned for anything else, or
+    /// when the data of an instruction is not valid base58.
+    pub fn from_encoded_transaction_with_status_meta(
+        transaction: &EncodedTransactionWithStatusMeta,
+    ) -> Result<Self, DecodeError> {
+        let inner_instructions = transaction.meta.as_ref()
+            .and_then(|meta| meta.inner_instructions.clone())
+            .unwrap_or_default()
+            .into_iter()
+            .map(|inner| Ok(InnerInstructions {
+                index: inner.index,
+                instructions: inner.instructions.iter()
+                    .map(|ui_instruction| match ui_instruction {
+                        UiInstruction::Compiled(compiled) => compiled_instruction(compiled),
+                        UiInstruction::Parsed(_) => {
+                            error!("[spi-wrapper/transaction] Inner instructions encoded as \
+                            jsonParsed cannot be decoded, request them as json, base58 or base64 \
+                            instead.");
+
+                            Err(DecodeError::deserialization(
+                                "", "ParsedInstruction",
+                                "Inner instructions encoded as jsonParsed cannot be decoded."))
+                        }
+                    })
+                    .collect::<Result<Vec<CompiledInstruction>, DecodeError>>()?,
+            }))
+            .collect::<Result<Vec<InnerInstructions>, DecodeError>>()?;
+        let error = transaction.meta.as_ref().and_then(|meta| meta.err.clone());
+        let log_messages = transaction.meta.as_ref()
+            .and_then(|meta| meta.log_messages.clone())
+            .unwrap_or_default();
+
+        if let Some(decoded) = transaction.transaction.decode() {
+            return Ok(FlattenedTransaction {
+                error,
+                log_messages,
+                ..FlattenedTransaction::from_transaction(&decoded, Some(inner_instructions))
+            });
+        }
+
+        match &transaction.transaction {
+            EncodedTransaction::Json(ui_transaction) => match &ui_transaction.message {
+                UiMessage::Raw(raw_message) => Ok(FlattenedTransaction {
+                    transaction_hash: ui_transaction.signatures.first()
+                        .cloned()
+                        .unwrap_or_default(),
+                    account_keys: raw_message.account_keys.clone(),
+                    instructions: raw_message.instructions.iter()
+                        .map(compiled_instruction)
+                        .collect::<Result<Vec<CompiledInstruction>, DecodeError>>()?,
+                    inner_instructions,
+                    error,
+                    log_messages,
+                }),
+                UiMessage::Parsed(_) => {
+                    error!("[spi-wrapper/transaction] Transactions encoded as jsonParsed cannot \
+                    be decoded, request them as json, base58 or base64 instead.");
+
+                    Err(DecodeError::deserialization(
+                        "", "ParsedMessage", "Messages encoded as jsonParsed cannot be decoded."))
+                }
+            },
+            _ => {
+                error!("[spi-wrapper/transaction] The encoded transaction could not be decoded.");
+
+                Err(DecodeError::deserialization(
+                    "", "InvalidTransaction", "The encoded transaction could not be decoded."))
+            }
+        }
+    }
+
+    /// Turns every instruction of the transaction into an `Instruction`, including the inner
+    /// ones. Top-level instructions keep their position as `tx_instruction_id` and have a
+    /// `parent_index` of `TOP_LEVEL_PARENT_INDEX`. Inner instructions are numbered after the
+    /// top-level ones, in execution order, and point at the instruction that invoked them.
+    pub fn to_instructions(
+        &self,
+        slot: u64,
+        block_time: Option<i64>,
+        timestamp: i64,
+    ) -> Vec<Instruction> {
+        let mut instructions: Vec<Instruction> = self.instructions.iter()
+            .enumerate()
+            .map(|(idx, compiled)| {
+                self.to_instruction(compiled, idx as i32, TOP_LEVEL_PARENT_INDEX, slot, block_time,
+                                    timestamp)
+            })
+            .collect();
+
+        let mut inner_instructions = self.inner_instructions.clone();
+        inner_instructions.sort_by_key(|inner| inner.index);
+
+        let mut next_id = instructions.len() as i32;
+        for inner in inner_instructions.iter() {
+            for compiled in inner.instructions.iter() {
+                instructions.push(self.to_instruction(compiled, next_id, inner.index as i16, slot,
+                                                      block_time, timestamp));
+                next_id += 1;
+            }
+        }
+
+        instructions
+    }
+
+    /// The index of the top-level instruction the transaction failed on, if it failed because
+    /// of an instruction.
+    pub fn failed_instruction_index(&self) -> Option<u8> {
+        match &self.error {
+            Some(TransactionError::InstructionError(idx, _)) => Some(*idx),
+            _ => None,
+        }
+    }
+
+    /// The error the transaction failed with. Errors raised by an instruction are attributed to
+    /// the program that raised them (see `failed_program`), so `Custom` codes are named after
+    /// that program's error type. When that program cannot be told, the error is left
+    /// unattributed: its `program` is empty and `Custom` codes are not named.
+    pub fn decode_error(&self) -> Option<DecodeError> {
+        let program = self.failed_program().unwrap_or_default();
+
+        self.error.as_ref()
+            .map(|err| DecodeError::from_transaction_error(&program, err))
+    }
+
+    /// The program that raised the error of the failed instruction. The first program the logs
+    /// report as failed is the one that raised it, as the programs that invoked it fail after
+    /// it. Without such a log, the program invoked by the failed instruction is only known to
+    /// have raised the error if it did not invoke any other program.
+    pub fn failed_program(&self) -> Option<String> {
+        let failed_idx = self.failed_instruction_index()?;
+        let compiled = self.instructions.get(failed_idx as usize)?;
+
+        if let Some(program) = self.log_messages.iter().find_map(|log| failed_program_log(log)) {
+            return Some(program.to_string());
+        }
+
+        let invoked_programs = self.inner_instructions.iter()
+            .any(|inner| inner.index == failed_idx && !inner.instructions.is_empty());
+        if invoked_programs {
+            return None;
+        }
+
+        Some(self.account_key(compiled.program_id_index))
+    }
+
+    fn to_instruction(
+        &self,
+        compiled: &CompiledInstruction,
+        tx_instruction_id: i32,
+        parent_index: i16,
+        slot: u64,
+        block_time: Option<i64>,
+        timestamp: i64,
+    ) -> Instruction {
+        Instruction {
+            tx_instruction_id,
+            transaction_hash: self.transaction_hash.clone(),
+            program: self.account_key(compiled.program_id_index),
+            accounts: compiled.accounts.iter()
+                .map(|account_index| self.account_key(*account_index))
+                .collect(),
+            data: compiled.data.clone(),
+            parent_index,
+            timestamp,
+            slot,
+            block_time,
+        }
+    }
+
+    fn account_key(&self, index: u8) -> String {
+        self.account_keys.get(index as usize)
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+/// Decodes every instruction of a confirmed transaction, inner instructions included.
+///
+/// The transaction's top-level instructions are handed to the parsers that need to look across
+/// instructions (e.g. `native_secp256k1`).
+pub async fn process_confirmed_transaction(
+    registry: &ParserRegistry,
+    transaction: &ConfirmedTransaction,
+    timestamp: i64,
+) -> Vec<InstructionOutcome> {
+    let flattened = FlattenedTransaction::from_transaction_with_status_meta(
+        &transaction.transaction);
+
+    process_flattened_transaction(registry, flattened, transaction.slot, transaction.block_time,
+                                  timestamp).await
+}
+
+/// Decodes every instruction of an RPC-encoded confirmed transaction, inner instructions
+/// included. An error is returned if the transaction itself cannot be decoded.
+pub async fn process_encoded_confirmed_transaction(
+    registry: &ParserRegistry,
+    transaction: &EncodedConfirmedTransaction,
+    timestamp: i64,
+) -> Result<Vec<InstructionOutcome>, DecodeError> {
+    let flattened = FlattenedTransaction::from_encoded_transaction_with_status_meta(
+        &transaction.transaction)?;
+
+    Ok(process_flattened_transaction(registry, flattened, transaction.slot,
+                                     transaction.block_time, timestamp).await)
+}
+
+/// The error a confirmed transaction failed with, if it failed.
+pub fn transaction_error(transaction: &ConfirmedTransaction) -> Option<DecodeError> {
+    FlattenedTransaction::from_transaction_with_status_meta(&transaction.transaction)
+        .decode_error()
+}
+
+/// The error an RPC-encoded confirmed transaction failed with, if it failed. An error is
+/// returned if the transaction itself cannot be decoded.
+pub fn encoded_transaction_error(
+    transaction: &EncodedConfirmedTransaction,
+) -> Result<Option<DecodeError>, DecodeError> {
+    FlattenedTransaction::from_encoded_transaction_with_status_meta(&transaction.transaction)
+        .map(|flattened| flattened.decode_error())
+}
+
+/// The program a `Program <program> failed: <reason>` log line reports as failed.
+fn failed_program_log(log: &str) -> Option<&str> {
+    let mut parts = log.strip_prefix("Program ")?.splitn(2, ' ');
+    let program = parts.next()?;
+
+    // Skips lines logged by programs themselves, e.g. `Program log: failed to ...`.
+    if program.ends_with(':') || !parts.next()?.starts_with("failed") {
+        return None;
+    }
+
+    Some(program)
+}
+
+/// Builds a compiled instruction out of an RPC-encoded one, whose data is base58 encoded.
+fn compiled_instruction(
+    compiled: &UiCompiledInstruction,
+) -> Result<CompiledInstruction, DecodeError> {
+    let data = bs58::decode(&compiled.data).into_vec().map_err(|err| {
+        error!("[spi-wrapper/transaction] The data of an instruction is not valid base58: {}.",
+               err);
+
+        DecodeError::deserialization("", "InvalidBase58", &err.to_string())
+    })?;
+
+    Ok(CompiledInstruction {
+        program_id_index: compiled.program_id_index,
+        accounts: compiled.accounts.clone(),
+        data,
+    })
+}
+
+/// Decodes the instructions of a flattened transaction. If the transaction failed on one of its
+/// instructions, the error is added to that instruction's properties under `ERROR_PARENT_KEY`.
+pub(crate) async fn process_flattened_transaction(
+    registry: &ParserRegistry,
+    flattened: FlattenedTransaction,
+    slot: u64,
+    block_time: Option<i64>,
+    timestamp: i64,
+) -> Vec<InstructionOutcome> {
+    let instructions = flattened.to_instructions(slot, block_time, timestamp);
+    // The instruction the transaction failed on, along with why.
+    let failed_instruction = flattened.failed_instruction_index()
+        .zip(flattened.decode_error())
+        .and_then(|(failed_idx, decode_error)| {
+            instructions.iter()
+                .find(|instruction| instruction.tx_instruction_id == failed_idx as i32
+                    && instruction.parent_index == TOP_LEVEL_PARENT_INDEX)
+                .cloned()
+                .map(|instruction| (instruction, decode_error))
+        });
+
+    let mut outcomes = process_with_registry(registry, instructions,
+                                             Some(flattened.instructions)).await;
+
+    if let Some((failed, decode_error)) = failed_instruction {
+        for outcome in outcomes.iter_mut() {
+            if let InstructionOutcome::Decoded(set) = outcome {
+                if set.function.tx_instruction_id == failed.tx_instruction_id
+                    && set.function.parent_index == TOP_LEVEL_PARENT_INDEX {
+                    // Errors that are not down to a program have none to name.
+                    let program = Some(decode_error.program.clone())
+                        .filter(|program| !program.is_empty());
+
+                    set.properties.extend(vec![
+                        failed.optional_property(ERROR_PARENT_KEY, "program", program,
+                                                 ValueType::Pubkey),
+                        failed.property(ERROR_PARENT_KEY, "code", decode_error.code.to_string(),
+                                        ValueType::U64),
+                        failed.property(ERROR_PARENT_KEY, "name", decode_error.name.clone(),
+                                        ValueType::EnumName),
+                        failed.property(ERROR_PARENT_KEY, "message",
+                                        decode_error.message.clone(), ValueType::String),
+                    ]);
+                }
+            }
+        }
+    }
+
+    outcomes
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_sdk::instruction::InstructionError;
+
+    fn compiled(program_id_index: u8) -> CompiledInstruction {
+        CompiledInstruction { program_id_index, accounts: vec![0, 1], data: vec![] }
+    }
+
+    #[test]
+    fn flattened_transaction_numbers_inner_instructions() {
+        let flattened = FlattenedTransaction {
+            transaction_hash: "hash".to_string(),
+            account_keys: vec!["payer".to_string(), "other".to_string(), "program".to_string()],
+            instructions: vec![compiled(2), compiled(2)],
+            inner_instructions: vec![
+                InnerInstructions { index: 1, instructions: vec![compiled(2)] },
+                InnerInstructions { index: 0, instructions: vec![compiled(2), compiled(2)] },
+            ],
+            error: None,
+            log_messages: vec![],
+        };
+
+        let instructions = flattened.to_instructions(0, None, 0);
+        let ids: Vec<(i32, i16)> = instructions.iter()
+            .map(|instruction| (instruction.tx_instruction_id, instruction.parent_index))
+            .collect();
+        assert_eq!(ids, vec![
+            (0, TOP_LEVEL_PARENT_INDEX), (1, TOP_LEVEL_PARENT_INDEX), (2, 0), (3, 0), (4, 1),
+        ]);
+        assert_eq!(instructions[4].program, "program");
+        assert_eq!(instructions[4].accounts, vec!["payer".to_string(), "other".to_string()]);
+    }
+
+    #[test]
+    fn corrupt_instruction_data_is_an_error() {
+        use solana_sdk::message::MessageHeader;
+        use solana_transaction_status::{UiRawMessage, UiTransaction};
+
+        let encoded = |data: &str| EncodedTransactionWithStatusMeta {
+            transaction: EncodedTransaction::Json(UiTransaction {
+                signatures: vec!["hash".to_string()],
+                message: UiMessage::Raw(UiRawMessage {
+                    header: MessageHeader {
+                        num_required_signatures: 1,
+                        num_readonly_signed_accounts: 0,
+                        num_readonly_unsigned_accounts: 1,
+                    },
+                    account_keys: vec!["payer".to_string(), "program".to_string()],
+                    recent_blockhash: "blockhash".to_string(),
+                    instructions: vec![UiCompiledInstruction {
+                        program_id_index: 1,
+                        accounts: vec![0],
+                        data: data.to_string(),
+                    }],
+                }),
+            }),
+            meta: None,
+        };
+
+        let flattened = FlattenedTransaction::from_encoded_transaction_with_status_meta(
+            &encoded("3Bxs4h24hBtQy9rw")).unwrap();
+        assert_eq!(flattened.instructions[0].data, bs58::decode("3Bxs4h24hBtQy9rw")
+            .into_vec().unwrap());
+
+        let corrupt = FlattenedTransaction::from_encoded_transaction_with_status_meta(
+            &encoded("0OIl")).err().unwrap();
+        assert_eq!(corrupt.name, "InvalidBase58");
+    }
+
+    #[test]
+    fn custom_errors_are_named_after_the_failing_program() {
+        let swap = "SwaPpA9LAaLfeLi3a68M4DjnLqgtticKg6CnyNwgAC8";
+        let token = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+        let flattened = |inner_instructions: Vec<InnerInstructions>, log_messages: Vec<&str>| {
+            FlattenedTransaction {
+                transaction_hash: "hash".to_string(),
+                account_keys: vec!["payer".to_string(), swap.to_string(), token.to_string()],
+                instructions: vec![compiled(1)],
+                inner_instructions,
+                error: Some(TransactionError::InstructionError(0, InstructionError::Custom(1))),
+                log_messages: log_messages.iter().map(|log| log.to_string()).collect(),
+            }
+        };
+        let transfer = vec![InnerInstructions { index: 0, instructions: vec![compiled(2)] }];
+
+        // Without any program invoked by the swap, the swap raised the error.
+        let error = flattened(vec![], vec![]).decode_error().unwrap();
+        assert_eq!(error.program, swap);
+        assert_eq!(error.name, "InvalidProgramAddress");
+
+        // The logs tell the token program, invoked by the swap, raised the error.
+        let logs = vec![
+            "Program SwaPpA9LAaLfeLi3a68M4DjnLqgtticKg6CnyNwgAC8 invoke [1]",
+            "Program log: failed to transfer",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [2]",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA failed: custom program error: 0x1",
+            "Program SwaPpA9LAaLfeLi3a68M4DjnLqgtticKg6CnyNwgAC8 failed: custom program error: 0x1",
+        ];
+        let error = flattened(transfer.clone(), logs).decode_error().unwrap();
+        assert_eq!(error.program, token);
+        assert_eq!(error.name, "InsufficientFunds");
+
+        // Without logs, either program could have raised it.
+        let error = flattened(transfer, vec![]).decode_error().unwrap();
+        assert_eq!(error.program, "");
+        assert_eq!(error.name, "Custom");
+    }
+    #[test]
+    fn errors_of_an_unknown_program_are_annotated_without_one() {
+        let system = crate::programs::native_system::PROGRAM_ADDRESS;
+        let transfer = CompiledInstruction {
+            program_id_index: 2,
+            accounts: vec![0, 1],
+            data: vec![2, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0],
+        };
+        // The transfer invoked another program, and there are no logs to tell which one failed.
+        let flattened = FlattenedTransaction {
+            transaction_hash: "hash".to_string(),
+            account_keys: vec!["payer".to_string(), "other".to_string(), system.to_string()],
+            instructions: vec![transfer.clone()],
+            inner_instructions: vec![InnerInstructions { index: 0, instructions: vec![transfer] }],
+            error: Some(TransactionError::InstructionError(0, InstructionError::Custom(1))),
+            log_messages: vec![],
+        };
+
+        let outcomes = crate::test_utils::block_on(process_flattened_transaction(
+            &ParserRegistry::with_builtins(), flattened, 0, None, 0));
+        let set = outcomes[0].clone().instruction_set().unwrap();
+        let error = |key: &str| set.properties.iter()
+            .find(|property| property.parent_key == ERROR_PARENT_KEY && property.key == key)
+            .unwrap()
+            .clone();
+        assert_eq!(error("program").value_type, ValueType::Null);
+        assert_eq!(error("program").value, "");
+        assert_eq!(error("name").value, "Custom");
+    }
+}